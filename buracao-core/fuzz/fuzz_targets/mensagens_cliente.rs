@@ -0,0 +1,19 @@
+#![no_main]
+
+use buracao_core::protocolo::decodificar;
+use buracao_core::{EstadoJogo, MsgCliente};
+use libfuzzer_sys::fuzz_target;
+
+// Bytes quaisquer, como chegariam pelo WebSocket. Nada pode entrar em pânico: nem a
+// leitura (login e demais mensagens) nem a ação aplicada a uma mesa recém-distribuída,
+// que precisa continuar íntegra depois dela.
+fuzz_target!(|dados: &[u8]| {
+    if let Ok(MsgCliente::Acao(acao)) = decodificar(dados) {
+        let mut estado = EstadoJogo::new();
+        estado.dar_cartas_com_semente(0);
+        let vez = estado.turno_atual;
+        let _ = estado.realizar_acao(vez, acao);
+        let problemas = estado.verificar_invariantes();
+        assert!(problemas.is_empty(), "{:?}", problemas);
+    }
+});
@@ -1,5 +1,120 @@
 use crate::baralho::{Carta, Verso};
+pub use crate::delta::{DeltaVisao, PatchMesa};
+use crate::protocolo::ErroMensagem;
 use serde::{Deserialize, Serialize}; // Atenção: Pode precisar de ajuste circular se Visao usar Estado
+use std::collections::HashMap;
+use std::fmt;
+
+/// Versão do protocolo cliente/servidor. Suba sempre que o formato das mensagens
+/// mudar de um jeito que um cliente antigo não entenda.
+pub const VERSAO_PROTOCOLO: u32 = 2;
+
+// --- O que o Cliente MANDA para o Servidor ---
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "tipo", content = "dados")]
+pub enum MsgCliente {
+    /// Primeira mensagem da conexão: quem sou e em qual sala quero sentar
+    Login(DadosLogin),
+
+    /// Jogada (só vale na vez do jogador)
+    Acao(AcaoJogador),
+
+    /// Moderação da sala (não passa pelo turno do jogo)
+    Sala(ComandoSala),
+
+    /// Mensagem de chat da mesa
+    Chat {
+        texto: String,
+        /// true = só o parceiro de dupla recebe
+        #[serde(default)]
+        apenas_time: bool,
+    },
+
+    /// Reação rápida ("Boa!", 👍...). Vale fora da vez.
+    Reagir { reacao: Reacao },
+
+    /// O cliente perdeu um delta (versão pulou) e quer a foto completa de novo
+    Resync,
+
+    /// Depois do fim da partida: o que o jogador quer fazer a seguir
+    VotoFimDeJogo(VotoFimDeJogo),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VotoFimDeJogo {
+    /// Mesmas duplas, vale para a série
+    Revanche,
+    /// Roda os parceiros (a série recomeça, já que os times mudam)
+    TrocarDuplas,
+    /// Sai da sala e libera o assento
+    Sair,
+}
+
+/// Placar da série de partidas entre as mesmas duplas (melhor de N).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlacarSerie {
+    pub vitorias_a: u32,
+    pub vitorias_b: u32,
+    pub melhor_de: u32,
+}
+
+impl Default for PlacarSerie {
+    fn default() -> Self {
+        Self {
+            vitorias_a: 0,
+            vitorias_b: 0,
+            melhor_de: 3,
+        }
+    }
+}
+
+impl PlacarSerie {
+    /// Algum time já tem vitórias suficientes para levar a série
+    pub fn decidida(&self) -> bool {
+        let necessarias = self.melhor_de / 2 + 1;
+        self.vitorias_a >= necessarias || self.vitorias_b >= necessarias
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DadosLogin {
+    /// Versão do protocolo do cliente. Clientes sem o campo contam como versão 0.
+    #[serde(default)]
+    pub versao: u32,
+    pub device_id: String,
+    pub nome: String,
+    pub sala: String,
+    /// Senha da sala (só é exigida de quem ainda não tem assento). Mesa de torneio não
+    /// tem senha: aqui vai a chave de assento que o jogador recebeu na inscrição.
+    #[serde(default)]
+    pub senha: Option<String>,
+    /// Token recebido no login anterior, obrigatório para reconectar
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Última `seq` recebida antes de cair. Se o servidor ainda tiver o que veio
+    /// depois, reenvia só isso; senão manda a foto completa.
+    #[serde(default)]
+    pub ultimo_seq: Option<u64>,
+    /// Entrar só para assistir, sem ocupar assento
+    #[serde(default)]
+    pub espectador: Option<ModoEspectador>,
+    /// Chave do perfil do jogador (ranking). Sem ela, o servidor cria um perfil novo.
+    #[serde(default)]
+    pub identidade: Option<String>,
+    /// Token de anfitrião recebido ao criar a sala pelo lobby
+    #[serde(default)]
+    pub token_anfitriao: Option<String>,
+}
+
+/// Como o espectador acompanha a mesa.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "modo", content = "jogador")]
+pub enum ModoEspectador {
+    /// Vê só o que é público (mesa, lixo, quantidade de cartas)
+    Publico,
+    /// Treinador: acompanha a mão de um jogador (se a sala permitir)
+    Treinador(u32),
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "tipo", content = "dados")] // Gera JSON limpo: { "tipo": "Descartar", "dados": { ... } }
@@ -18,9 +133,7 @@ pub enum AcaoJogador {
     },
 
     /// O jogador quer baixar jogos da mão (sequências ou trincas).
-    BaixarJogos {
-        jogos: Vec<Vec<Carta>>,
-    },
+    BaixarJogos { jogos: Vec<Vec<Carta>> },
 
     /// O jogador quer adicionar cartas a um jogo que já está na mesa.
     Ajuntar {
@@ -29,42 +142,341 @@ pub enum AcaoJogador {
     },
 
     /// O jogador descarta uma carta para finalizar o turno.
-    Descartar {
-        carta: Carta,
-    },
+    Descartar { carta: Carta },
+}
 
-    Mensagem {
-        texto: String,
+impl AcaoJogador {
+    /// Nome do tipo da ação, igual ao `tipo` do JSON (para logs).
+    pub fn nome(&self) -> &'static str {
+        match self {
+            AcaoJogador::ComprarBaralho => "ComprarBaralho",
+            AcaoJogador::ComprarLixo { .. } => "ComprarLixo",
+            AcaoJogador::BaixarJogos { .. } => "BaixarJogos",
+            AcaoJogador::Ajuntar { .. } => "Ajuntar",
+            AcaoJogador::Descartar { .. } => "Descartar",
+        }
+    }
+}
+
+/// Por que o core recusou uma ação. O jogador vê só o texto; o tipo é para quem
+/// conta as recusas (métricas) sem depender da redação das mensagens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipoRecusa {
+    /// Não é a vez do jogador
+    ForaDaVez,
+    PartidaEncerrada,
+    /// O monte acabou (Regra 20)
+    MonteEsgotado,
+    /// Lixo vazio, travado ou sem onde usar a carta do topo
+    Lixo,
+    /// Compra repetida, ou descarte antes de comprar
+    Compra,
+    PontosParaDescer,
+    /// Ficaria sem cartas sem poder bater
+    Batida,
+    CartaForaDaMao,
+    /// Jogo inválido, vazio ou que não é do time
+    JogoInvalido,
+}
+
+/// Ação recusada: o tipo e o texto para o jogador.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recusa {
+    pub tipo: TipoRecusa,
+    pub mensagem: String,
+}
+
+impl Recusa {
+    pub fn new(tipo: TipoRecusa, mensagem: impl Into<String>) -> Self {
+        Self {
+            tipo,
+            mensagem: mensagem.into(),
+        }
+    }
+}
+
+impl fmt::Display for Recusa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.mensagem)
+    }
+}
+
+impl From<Recusa> for String {
+    fn from(recusa: Recusa) -> Self {
+        recusa.mensagem
+    }
+}
+
+/// Conjunto fixo de reações rápidas da mesa.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Reacao {
+    Boa,
+    Ops,
+    AndaLogo,
+    Joinha,
+    Risada,
+    Pensando,
+}
+
+impl Reacao {
+    pub const TODAS: [Reacao; 6] = [
+        Reacao::Boa,
+        Reacao::Ops,
+        Reacao::AndaLogo,
+        Reacao::Joinha,
+        Reacao::Risada,
+        Reacao::Pensando,
+    ];
+
+    /// Texto exibido no balão
+    pub fn texto(&self) -> &'static str {
+        match self {
+            Reacao::Boa => "Boa!",
+            Reacao::Ops => "Ops",
+            Reacao::AndaLogo => "Anda logo",
+            Reacao::Joinha => "👍",
+            Reacao::Risada => "😂",
+            Reacao::Pensando => "🤔",
+        }
+    }
+}
+
+// --- Comandos de moderação da sala (não passam pelo turno do jogo) ---
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "tipo", content = "dados")]
+pub enum ComandoSala {
+    /// Anfitrião: remove o jogador e impede que ele volte
+    Expulsar { jogador: u32 },
+
+    /// Anfitrião: desconecta o jogador e deixa o assento (com a mão) livre para outra
+    /// pessoa. Com `robo`, o servidor joga por ele até alguém sentar.
+    LiberarAssento {
+        jogador: u32,
+        #[serde(default)]
+        robo: bool,
     },
+
+    /// Anfitrião: sala trancada não aceita jogadores novos (reconexões continuam valendo)
+    TrancarSala { trancada: bool },
+
+    /// Anfitrião: passa o controle da sala para outro jogador
+    TransferirHost { jogador: u32 },
+
+    /// Qualquer jogador: voto para expulsar alguém (maioria dos demais decide)
+    VotarExpulsao { jogador: u32 },
 }
 
 // --- O que o Servidor MANDA para o Cliente ---
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "tipo", content = "conteudo")]
 pub enum MsgServidor {
-    // Quando conecta
+    // Login aceito: assento do jogador e versão do protocolo do servidor.
+    // `retomada` = o que se perdeu vem reenviado logo em seguida (sem foto nova).
     BoasVindas {
         id_jogador: u32,
+        versao_protocolo: u32,
+        #[serde(default)]
+        retomada: bool,
+    },
+
+    // Perfil persistente usado no ranking. `chave` só vem quando o perfil acabou de ser
+    // criado: o cliente guarda e apresenta nos próximos logins (inclusive em outro aparelho).
+    Identidade {
+        id_perfil: i64,
+        #[serde(default)]
+        chave: Option<String>,
+    },
+
+    // Entrou como espectador (não tem assento nem joga)
+    BoasVindasEspectador {
+        versao_protocolo: u32,
+        /// Jogador cuja mão o treinador acompanha
+        treinando: Option<u32>,
+        /// O que chega ao espectador está atrasado esse tanto em relação à mesa
+        atraso_segundos: u32,
     },
 
-    // Atualização completa do estado (enviado a cada ação)
+    // Login recusado (a conexão é fechada em seguida)
+    LoginRecusado(MotivoRecusa),
+
+    // Quem está sentado em cada assento (enviado quando alguém entra/sai)
+    Nomes(HashMap<u32, String>),
+
+    // Token assinado do assento, que deve ser apresentado ao reconectar
+    Sessao {
+        token: String,
+    },
+
+    // Foto completa do estado (ao entrar, ao pedir Resync ou quando não há base para delta)
     Estado(VisaoJogador),
 
+    // Só o que mudou desde a versão anterior (enviado a cada ação)
+    Delta(DeltaVisao),
+
     // Notificações rápidas ("Fulano bateu", "Baralho no fim")
     Notificacao(String),
 
     // Erro de validação ("Não pode descartar essa carta")
     Erro(String),
 
+    // A mensagem nem chegou ao jogo: formato inválido, grande demais ou rápida demais
+    MensagemRejeitada(ErroMensagem),
+
+    // Quem manda na sala e se ela está trancada (enviado a cada mudança)
+    InfoSala {
+        host: Option<u32>,
+        trancada: bool,
+        // Jogador alvo -> quantos votos de expulsão ele já recebeu
+        votos_expulsao: HashMap<u32, usize>,
+        #[serde(default)]
+        espectadores: usize,
+    },
+
+    // Quem está conectado e com que latência (muda quando alguém cai/volta ou o ping varia)
+    Presenca(HashMap<u32, PresencaJogador>),
+
+    // Você foi removido da sala (a conexão será fechada em seguida)
+    Expulso {
+        motivo: String,
+    },
+
+    // Nova mensagem no chat da mesa
+    Chat(MensagemChat),
+
+    // Alguém da mesa mandou uma reação rápida
+    Reacao {
+        jogador: u32,
+        reacao: Reacao,
+    },
+
+    // Últimas mensagens da sala, enviadas ao entrar/reconectar
+    HistoricoChat(Vec<MensagemChat>),
+
     // Fim da partida com placar final
     FimDeJogo {
         vencedor_time: u8, // 0 (A) ou 1 (B)
         pontos_a: i32,
         pontos_b: i32,
         motivo: String, // "Batida" ou "Baralho Esgotado"
+        #[serde(default)]
+        empate: bool,
+        #[serde(default)]
+        serie: PlacarSerie,
+    },
+
+    // Votos de quem já escolheu o que fazer depois da partida
+    VotosFimDeJogo(HashMap<u32, VotoFimDeJogo>),
+
+    // Todos concordaram: cartas dadas de novo (o Estado completo vem em seguida)
+    NovaPartida {
+        serie: PlacarSerie,
+        duplas_trocadas: bool,
+    },
+}
+
+/// Como as mensagens do servidor trafegam: `{ "seq": 12, "msg": { "tipo": ..., "conteudo": ... } }`.
+/// A `seq` é contada por jogador dentro da sala e permite retomar a conexão sem perder
+/// nada. Mensagens de antes do assento (recusa de login, boas-vindas) vão sem `seq`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Envelope {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+    // Sem `flatten`: ele quebra os mapas com chave numérica (Nomes, InfoSala)
+    pub msg: MsgServidor,
+}
+
+// Por que o servidor não deixou o jogador entrar
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "motivo", content = "detalhes")]
+pub enum MotivoRecusa {
+    /// Cliente de outra versão: a página precisa ser recarregada
+    VersaoIncompativel {
+        servidor: u32,
+        cliente: u32,
     },
+    /// A primeira mensagem não era um Login válido
+    MensagemInvalida,
+    SalaCheia,
+    SalaTrancada,
+    Banido,
+    SenhaIncorreta,
+    /// Device já tem assento, mas o token não confere
+    SessaoInvalida,
+    SemEspectadores,
+    /// Pediu para treinar, mas a sala não permite (ou o assento está vazio)
+    TreinadorNaoPermitido,
+    /// Mesa de torneio: só entra quem foi escalado, com o nome inscrito e a chave de assento
+    AssentoReservado,
+    /// A sala não existe e o servidor já tem o máximo de salas abertas
+    LimiteDeSalas,
+    /// A sala fechou enquanto o login entrava nela
+    SalaFechada,
+    /// O perfil (identidade) do login já ocupa um assento desta sala
+    PerfilJaSentado,
+    /// Espectador pediu uma sala que não existe (assistir não abre sala nova)
+    SalaInexistente,
+}
+
+impl MotivoRecusa {
+    /// Texto para mostrar ao jogador
+    pub fn texto(&self) -> String {
+        match self {
+            MotivoRecusa::VersaoIncompativel { .. } => {
+                "Versão do jogo desatualizada. Recarregue a página.".to_string()
+            }
+            MotivoRecusa::MensagemInvalida => "Login inválido.".to_string(),
+            MotivoRecusa::SalaCheia => "Sala cheia!".to_string(),
+            MotivoRecusa::SalaTrancada => "A sala está trancada pelo anfitrião.".to_string(),
+            MotivoRecusa::Banido => "Você foi expulso desta sala.".to_string(),
+            MotivoRecusa::SenhaIncorreta => "Senha da sala incorreta.".to_string(),
+            MotivoRecusa::SessaoInvalida => "Sessão inválida para este assento.".to_string(),
+            MotivoRecusa::SemEspectadores => "Esta sala não aceita espectadores.".to_string(),
+            MotivoRecusa::TreinadorNaoPermitido => {
+                "Esta sala não permite acompanhar a mão de um jogador.".to_string()
+            }
+            MotivoRecusa::AssentoReservado => {
+                "Mesa de torneio: entre com o nome em que você foi inscrito e a sua chave de assento no campo de senha.".to_string()
+            }
+            MotivoRecusa::LimiteDeSalas => {
+                "O servidor está com o máximo de salas abertas. Tente mais tarde.".to_string()
+            }
+            MotivoRecusa::SalaFechada => "A sala acabou de ser fechada.".to_string(),
+            MotivoRecusa::PerfilJaSentado => {
+                "Você já está sentado nesta sala com o seu perfil (em outra aba ou aparelho)."
+                    .to_string()
+            }
+            MotivoRecusa::SalaInexistente => {
+                "Sala não encontrada. Confira o código.".to_string()
+            }
+        }
+    }
 }
 
+// Situação da conexão de um assento ocupado
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PresencaJogador {
+    pub conectado: bool,
+    /// Ida e volta do último ping do servidor (None até a primeira medição)
+    pub latencia_ms: Option<u32>,
+    /// Estourou o tempo várias vezes seguidas: o servidor está jogando por ele
+    #[serde(default)]
+    pub ausente: bool,
+}
+
+// Uma linha do chat da mesa
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MensagemChat {
+    pub autor_id: u32,
+    pub autor_nome: String,
+    pub texto: String,
+    /// Milissegundos desde a época Unix (horário do servidor)
+    pub timestamp_ms: u64,
+    pub apenas_time: bool,
+}
+
+/// `meu_id` da visão pública: não corresponde a nenhum assento.
+pub const ID_ESPECTADOR: u32 = u32::MAX;
+
 // --- A "Foto" do jogo filtrada para cada jogador ---
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct VisaoJogador {
@@ -97,10 +509,28 @@ pub struct VisaoJogador {
     pub qtd_lixo: u32,
 
     pub verso_topo: Option<Verso>,
+
+    // 6. Versão do estado que gerou esta visão (ver `DeltaVisao`)
+    #[serde(default)]
+    pub versao: u64,
+
+    // 7. Relógio do turno (preenchido pelo servidor quando a sala tem relógio)
+    #[serde(default)]
+    pub tempo: Option<TempoTurno>,
+}
+
+/// Quanto tempo o jogador da vez ainda tem, medido no momento do envio.
+/// O cliente faz a contagem regressiva localmente a partir daqui.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TempoTurno {
+    pub jogador: u32,
+    pub restante_turno_ms: u64,
+    /// Banco de tempo do jogador da vez (começa a ser gasto quando o turno zera)
+    pub banco_ms: u64,
 }
 
 // Uma representação simplificada de um jogo na mesa para o frontend
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct DetalheJogo {
     pub id: u32,
     pub cartas: Vec<Carta>,
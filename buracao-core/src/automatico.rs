@@ -0,0 +1,169 @@
+use crate::acoes::{AcaoJogador, Recusa, TipoRecusa};
+use crate::baralho::Carta;
+use crate::estado::EstadoJogo;
+use crate::registro::Lance;
+use crate::regras::validar_jogo;
+
+// --- JOGADA AUTOMÁTICA (TEMPO ESGOTADO) ---
+// Política conservadora usada quando o relógio do turno zera: compra do monte se
+// ainda não comprou e descarta a carta que menos ajuda o adversário. Nunca baixa
+// jogos da mão; só pega o lixo quando o monte acabou (Regra 20), já que aí é pegar
+// o lixo ou encerrar a mão.
+
+impl EstadoJogo {
+    /// Carta que o jogador pode soltar com menos risco: primeiro um 3 preto (tranca o lixo),
+    /// depois cartas que não encaixam em nenhum jogo do time adversário, guardando coringas
+    /// e cartas que servem nos jogos do próprio time. Entre as empatadas, a mais alta.
+    /// None se nenhum descarte é aceito: a última carta da mão só sai batendo, e para
+    /// bater é preciso ter Canastra Real e não ter pegado o lixo na rodada (Regra 24).
+    pub fn escolher_descarte_seguro(&self, id_jogador: u32) -> Option<Carta> {
+        let mao = self.maos.get(id_jogador as usize)?;
+        if mao.len() == 1
+            && (self.pegou_lixo_nesta_rodada || !self.conferir_real(id_jogador as usize))
+        {
+            return None;
+        }
+        let (meus_jogos, jogos_adversarios) = if id_jogador.is_multiple_of(2) {
+            (&self.jogos_time_a, &self.jogos_time_b)
+        } else {
+            (&self.jogos_time_b, &self.jogos_time_a)
+        };
+
+        let encaixa = |jogos: &std::collections::HashMap<u32, Vec<Carta>>, carta: &Carta| {
+            jogos.values().any(|jogo| {
+                let mut teste = jogo.clone();
+                teste.push(carta.clone());
+                validar_jogo(&teste)
+            })
+        };
+
+        mao.iter()
+            .min_by_key(|carta| {
+                let tres_preto = carta.eh_tres_preto();
+                let ajuda_adversario = encaixa(jogos_adversarios, carta);
+                let coringa = carta.eh_coringa() || carta.eh_joker();
+                let serve_para_nos = encaixa(meus_jogos, carta);
+                (
+                    !tres_preto,
+                    ajuda_adversario,
+                    coringa,
+                    serve_para_nos,
+                    std::cmp::Reverse(carta.valor_numerico_sequencia()),
+                )
+            })
+            .cloned()
+    }
+
+    /// Pega o lixo pelo jogador com o monte vazio: primeiro encaixando o topo num jogo
+    /// do time, senão baixando um jogo novo com duas cartas da mão. Só aceita pegadas
+    /// que deixam ao menos duas cartas na mão, para sobrar um descarte que não bate.
+    /// Devolve true se pegou.
+    fn pegar_lixo_automatico(&mut self, id_jogador: u32) -> bool {
+        let Some(topo) = self.lixo.last().cloned() else {
+            return false;
+        };
+        let mao = self.maos[id_jogador as usize].clone();
+        let meus_jogos = if id_jogador.is_multiple_of(2) {
+            &self.jogos_time_a
+        } else {
+            &self.jogos_time_b
+        };
+        // Mão + lixo, menos o topo e as cartas usadas, precisa deixar duas (sem subtrair:
+        // mão e lixo pequenos dariam overflow)
+        let sobra = |usadas: usize| mao.len() + self.lixo.len() >= usadas + 3;
+
+        let mut tentativas = Vec::new();
+        if sobra(0) {
+            let mut ids: Vec<u32> = meus_jogos.keys().copied().collect();
+            ids.sort_unstable();
+            for id_jogo in ids {
+                let mut teste = meus_jogos[&id_jogo].clone();
+                teste.push(topo.clone());
+                if validar_jogo(&teste) {
+                    tentativas.push(AcaoJogador::ComprarLixo {
+                        novos_jogos: Vec::new(),
+                        cartas_em_jogos_existentes: vec![(id_jogo, Vec::new())],
+                    });
+                }
+            }
+        }
+        if sobra(2) {
+            for (i, primeira) in mao.iter().enumerate() {
+                for segunda in &mao[i + 1..] {
+                    let jogo = vec![primeira.clone(), segunda.clone()];
+                    let mut teste = jogo.clone();
+                    teste.push(topo.clone());
+                    if validar_jogo(&teste) {
+                        tentativas.push(AcaoJogador::ComprarLixo {
+                            novos_jogos: vec![jogo],
+                            cartas_em_jogos_existentes: Vec::new(),
+                        });
+                    }
+                }
+            }
+        }
+
+        // As recusas (pontos de abertura, por exemplo) não mudam nada: tenta a próxima
+        tentativas
+            .into_iter()
+            .any(|acao| self.realizar_acao(id_jogador, acao).is_ok())
+    }
+
+    /// Joga pelo jogador da vez: compra (se ainda não comprou) e descarta.
+    pub fn jogada_automatica(&mut self, id_jogador: u32) -> Result<String, Recusa> {
+        if self.partida_encerrada {
+            return Err(Recusa::new(
+                TipoRecusa::PartidaEncerrada,
+                "A partida já encerrou.",
+            ));
+        }
+        if self.turno_atual != id_jogador {
+            return Err(Recusa::new(
+                TipoRecusa::ForaDaVez,
+                format!("Não é seu turno. Vez do jogador {}.", self.turno_atual),
+            ));
+        }
+
+        if !self.comprou_nesta_rodada && !self.pegou_lixo_nesta_rodada {
+            if let Err(erro) = self.realizar_acao(id_jogador, AcaoJogador::ComprarBaralho) {
+                // Monte e lixo esgotados: a própria compra encerrou a mão (e virou lance)
+                if self.partida_encerrada {
+                    return Ok(format!("Tempo esgotado. {}", erro));
+                }
+                if !self.baralho_acabou_nesta_rodada {
+                    return Err(erro);
+                }
+                // Regra 20: sem monte, resta pegar o lixo; se não há como, a mão acaba
+                if !self.pegar_lixo_automatico(id_jogador) {
+                    self.encerrar_partida_por_esgotamento();
+                    self.lances.push(Lance::Esgotamento {
+                        jogador: id_jogador,
+                    });
+                    self.marcar_alteracao();
+                    return Ok(format!(
+                        "Tempo esgotado. O baralho acabou e o jogador {} não tinha como usar o lixo. Fim de jogo.",
+                        id_jogador
+                    ));
+                }
+            }
+        }
+
+        let carta = self.escolher_descarte_seguro(id_jogador).ok_or_else(|| {
+            Recusa::new(
+                TipoRecusa::Batida,
+                "Nenhuma carta pode ser descartada sem bater.",
+            )
+        })?;
+        self.realizar_acao(
+            id_jogador,
+            AcaoJogador::Descartar {
+                carta: carta.clone(),
+            },
+        )?;
+
+        Ok(format!(
+            "Tempo esgotado: jogador {} descartou {}.",
+            id_jogador, carta
+        ))
+    }
+}
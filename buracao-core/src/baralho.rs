@@ -222,6 +222,25 @@ impl Baralho {
         self.cartas.shuffle(&mut rng);
     }
 
+    /// Embaralha de forma reproduzível: a mesma semente, partindo do baralho na ordem de
+    /// `Baralho::new`, sempre dá a mesma ordem. O algoritmo faz parte do formato do registro
+    /// de partida (ver docs/registro-de-partida.md) e não pode mudar sem subir a versão:
+    /// Fisher–Yates do fim para o começo, sorteando com SplitMix64.
+    pub fn embaralhar_com_semente(&mut self, semente: u64) {
+        let mut estado = semente;
+        let mut proximo = || {
+            estado = estado.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = estado;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        for i in (1..self.cartas.len()).rev() {
+            let j = (proximo() % (i as u64 + 1)) as usize;
+            self.cartas.swap(i, j);
+        }
+    }
+
     /// Tira a carta do topo do baralho (para comprar ou dar as cartas)
     pub fn comprar(&mut self) -> Option<Carta> {
         self.cartas.pop()
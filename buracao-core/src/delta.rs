@@ -0,0 +1,228 @@
+use crate::acoes::{DetalheJogo, TempoTurno, VisaoJogador};
+use crate::baralho::{Carta, Verso};
+use serde::{Deserialize, Deserializer, Serialize};
+
+// --- ATUALIZAÇÃO INCREMENTAL DA VISÃO ---
+// Depois de cada jogada o servidor manda só os campos que mudaram desde a última
+// visão enviada para aquele jogador. `versao_base` encadeia os deltas: se o
+// cliente não estiver nessa versão, ele perdeu alguma coisa e pede um Resync.
+
+/// Campos ausentes (None) não mudaram.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DeltaVisao {
+    pub versao_base: u64,
+    pub versao: u64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minha_mao: Option<Vec<Carta>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub posso_jogar: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mesa_time_a: Option<PatchMesa>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mesa_time_b: Option<PatchMesa>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tres_vermelho_time_a: Option<Vec<Carta>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tres_vermelho_time_b: Option<Vec<Carta>>,
+
+    // Some(None) = o lixo ficou vazio
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "campo_anulavel"
+    )]
+    pub lixo: Option<Option<Carta>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qtd_cartas_jogadores: Option<Vec<usize>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pontuacao_a: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pontuacao_b: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turno_atual: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rodada: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cartas_no_monte: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qtd_monte: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qtd_lixo: Option<u32>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "campo_anulavel"
+    )]
+    pub verso_topo: Option<Option<Verso>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "campo_anulavel"
+    )]
+    pub tempo: Option<Option<TempoTurno>>,
+}
+
+/// Mudança nos jogos baixados de um time.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum PatchMesa {
+    /// Jogos novos ou que ganharam cartas (os demais continuam iguais)
+    Atualizar(Vec<DetalheJogo>),
+    /// A mesa mudou de forma que não dá para remendar (ex.: mão nova): troca tudo
+    Substituir(Vec<DetalheJogo>),
+}
+
+// Sem isso o serde lê `"lixo": null` como "não mudou" em vez de "ficou vazio"
+fn campo_anulavel<'de, D, T>(d: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(d).map(Some)
+}
+
+fn se_mudou<T: PartialEq + Clone>(antes: &T, depois: &T) -> Option<T> {
+    (antes != depois).then(|| depois.clone())
+}
+
+fn patch_mesa(antes: &[DetalheJogo], depois: &[DetalheJogo]) -> Option<PatchMesa> {
+    if antes == depois {
+        return None;
+    }
+    // Jogos nunca somem no meio da mão; se algum sumiu, a mesa foi recomeçada
+    let algum_sumiu = antes
+        .iter()
+        .any(|jogo| !depois.iter().any(|novo| novo.id == jogo.id));
+    if algum_sumiu {
+        return Some(PatchMesa::Substituir(depois.to_vec()));
+    }
+
+    let alterados = depois
+        .iter()
+        .filter(|novo| !antes.contains(novo))
+        .cloned()
+        .collect();
+    Some(PatchMesa::Atualizar(alterados))
+}
+
+fn aplicar_patch_mesa(mesa: &mut Vec<DetalheJogo>, patch: &PatchMesa) {
+    match patch {
+        PatchMesa::Substituir(jogos) => *mesa = jogos.clone(),
+        PatchMesa::Atualizar(jogos) => {
+            for jogo in jogos {
+                match mesa.iter_mut().find(|j| j.id == jogo.id) {
+                    Some(existente) => existente.cartas = jogo.cartas.clone(),
+                    None => mesa.push(jogo.clone()),
+                }
+            }
+            mesa.sort_by_key(|j| j.id);
+        }
+    }
+}
+
+impl DeltaVisao {
+    /// Delta que não muda nada além da versão
+    pub fn vazio(&self) -> bool {
+        *self
+            == DeltaVisao {
+                versao_base: self.versao_base,
+                versao: self.versao,
+                ..Default::default()
+            }
+    }
+}
+
+impl VisaoJogador {
+    /// O que mudou de `self` (última visão enviada) para `nova`.
+    pub fn diferenca(&self, nova: &VisaoJogador) -> DeltaVisao {
+        DeltaVisao {
+            versao_base: self.versao,
+            versao: nova.versao,
+            minha_mao: se_mudou(&self.minha_mao, &nova.minha_mao),
+            posso_jogar: se_mudou(&self.posso_jogar, &nova.posso_jogar),
+            mesa_time_a: patch_mesa(&self.mesa_time_a, &nova.mesa_time_a),
+            mesa_time_b: patch_mesa(&self.mesa_time_b, &nova.mesa_time_b),
+            tres_vermelho_time_a: se_mudou(&self.tres_vermelho_time_a, &nova.tres_vermelho_time_a),
+            tres_vermelho_time_b: se_mudou(&self.tres_vermelho_time_b, &nova.tres_vermelho_time_b),
+            lixo: se_mudou(&self.lixo, &nova.lixo),
+            qtd_cartas_jogadores: se_mudou(&self.qtd_cartas_jogadores, &nova.qtd_cartas_jogadores),
+            pontuacao_a: se_mudou(&self.pontuacao_a, &nova.pontuacao_a),
+            pontuacao_b: se_mudou(&self.pontuacao_b, &nova.pontuacao_b),
+            turno_atual: se_mudou(&self.turno_atual, &nova.turno_atual),
+            rodada: se_mudou(&self.rodada, &nova.rodada),
+            cartas_no_monte: se_mudou(&self.cartas_no_monte, &nova.cartas_no_monte),
+            qtd_monte: se_mudou(&self.qtd_monte, &nova.qtd_monte),
+            qtd_lixo: se_mudou(&self.qtd_lixo, &nova.qtd_lixo),
+            verso_topo: se_mudou(&self.verso_topo, &nova.verso_topo),
+            tempo: se_mudou(&self.tempo, &nova.tempo),
+        }
+    }
+
+    /// Aplica o delta. Se a versão não bater, devolve Err e não mexe em nada:
+    /// o cliente deve pedir a foto completa (Resync).
+    pub fn aplicar_delta(&mut self, delta: &DeltaVisao) -> Result<(), String> {
+        if delta.versao_base != self.versao {
+            return Err(format!(
+                "Delta fora de ordem: base {} mas estou na versão {}.",
+                delta.versao_base, self.versao
+            ));
+        }
+
+        if let Some(v) = &delta.minha_mao {
+            self.minha_mao = v.clone();
+        }
+        if let Some(v) = delta.posso_jogar {
+            self.posso_jogar = v;
+        }
+        if let Some(p) = &delta.mesa_time_a {
+            aplicar_patch_mesa(&mut self.mesa_time_a, p);
+        }
+        if let Some(p) = &delta.mesa_time_b {
+            aplicar_patch_mesa(&mut self.mesa_time_b, p);
+        }
+        if let Some(v) = &delta.tres_vermelho_time_a {
+            self.tres_vermelho_time_a = v.clone();
+        }
+        if let Some(v) = &delta.tres_vermelho_time_b {
+            self.tres_vermelho_time_b = v.clone();
+        }
+        if let Some(v) = &delta.lixo {
+            self.lixo = v.clone();
+        }
+        if let Some(v) = &delta.qtd_cartas_jogadores {
+            self.qtd_cartas_jogadores = v.clone();
+        }
+        if let Some(v) = delta.pontuacao_a {
+            self.pontuacao_a = v;
+        }
+        if let Some(v) = delta.pontuacao_b {
+            self.pontuacao_b = v;
+        }
+        if let Some(v) = delta.turno_atual {
+            self.turno_atual = v;
+        }
+        if let Some(v) = delta.rodada {
+            self.rodada = v;
+        }
+        if let Some(v) = delta.cartas_no_monte {
+            self.cartas_no_monte = v;
+        }
+        if let Some(v) = delta.qtd_monte {
+            self.qtd_monte = v;
+        }
+        if let Some(v) = delta.qtd_lixo {
+            self.qtd_lixo = v;
+        }
+        if let Some(v) = delta.verso_topo {
+            self.verso_topo = v;
+        }
+        if let Some(v) = delta.tempo {
+            self.tempo = v;
+        }
+
+        self.versao = delta.versao;
+        Ok(())
+    }
+}
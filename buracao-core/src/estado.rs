@@ -1,7 +1,9 @@
-use crate::acoes::AcaoJogador;
-use crate::acoes::{DetalheJogo, VisaoJogador};
+use crate::acoes::{AcaoJogador, Recusa, TipoRecusa};
+use crate::acoes::{DetalheJogo, VisaoJogador, ID_ESPECTADOR};
 use crate::baralho::{Baralho, Carta}; // Importa do módulo vizinho
-use crate::regras::{tem_coringa, validar_jogo};
+use crate::eventos::{self, EventoJogo};
+use crate::registro::Lance;
+use crate::regras::{tem_coringa, validar_jogo, ConfigRegras};
 use crate::Verso;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap; // Importa as funções puras
@@ -30,6 +32,53 @@ pub struct EstadoJogo {
     pub qtd_monte: u32,
     pub qtd_lixo: u32,
     pub verso_topo: Option<Verso>,
+    #[serde(default)]
+    pub regras: ConfigRegras,
+    /// Sobe a cada mudança aceita; os clientes usam para encadear os deltas da visão
+    #[serde(default)]
+    pub versao: u64,
+    /// Quem bateu (None enquanto ninguém bateu ou se o baralho esgotou)
+    #[serde(default)]
+    pub bateu: Option<u32>,
+    /// Semente do embaralhamento da mão atual e quem começou (reproduzem a distribuição)
+    #[serde(default)]
+    pub semente: u64,
+    #[serde(default)]
+    pub turno_inicial: u32,
+    /// Tudo que mudou o jogo desde a distribuição, em ordem (ver registro.rs)
+    #[serde(default)]
+    pub lances: Vec<Lance>,
+    /// Mesa refeita a partir de um registro: não avisa o observador de eventos, que
+    /// só deve ver o que aconteceu de verdade (ver eventos.rs)
+    #[serde(skip)]
+    pub silencioso: bool,
+}
+
+/// Placar de um time em uma mão, item por item (ver `composicao_placar`).
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ComposicaoPlacar {
+    /// Soma do valor das cartas baixadas
+    pub cartas_mesa: i32,
+    /// 300 por canastra limpa
+    pub canastras_limpas: i32,
+    /// 100 por canastra suja
+    pub canastras_sujas: i32,
+    /// +100 cada com canastra limpa, -100 cada sem
+    pub tres_vermelhos: i32,
+    pub batida: i32,
+    /// Cartas que sobraram na mão de quem não bateu (negativo)
+    pub cartas_na_mao: i32,
+}
+
+impl ComposicaoPlacar {
+    pub fn total(&self) -> i32 {
+        self.cartas_mesa
+            + self.canastras_limpas
+            + self.canastras_sujas
+            + self.tres_vermelhos
+            + self.batida
+            + self.cartas_na_mao
+    }
 }
 
 impl EstadoJogo {
@@ -59,6 +108,62 @@ impl EstadoJogo {
             qtd_monte: 0,
             qtd_lixo: 0,
             verso_topo: verso_inicial,
+            regras: ConfigRegras::default(),
+            versao: 0,
+            bateu: None,
+            semente: 0,
+            turno_inicial: 0,
+            lances: Vec::new(),
+            silencioso: false,
+        }
+    }
+
+    /// Partida nova com as mesmas regras, já com as cartas dadas. A versão continua
+    /// subindo para nenhum cliente confundir a mesa nova com uma visão antiga.
+    pub fn nova_partida(&self) -> EstadoJogo {
+        let mut novo = EstadoJogo::new();
+        novo.regras = self.regras.clone();
+        novo.numero_partida = self.numero_partida + 1;
+        // Regra 2: a cada partida começa o jogador seguinte
+        novo.turno_atual = novo.numero_partida % 4;
+        novo.versao = self.versao;
+        novo.dar_cartas();
+        novo.marcar_alteracao();
+        novo
+    }
+
+    /// A mesma mão dada de novo, do zero (mesmo número de partida e quem começa),
+    /// com cartas novas. Usado pela administração para destravar uma mesa.
+    pub fn reiniciar_mao(&self) -> EstadoJogo {
+        let mut novo = EstadoJogo::new();
+        novo.regras = self.regras.clone();
+        novo.numero_partida = self.numero_partida;
+        novo.turno_atual = self.turno_inicial;
+        novo.versao = self.versao;
+        novo.dar_cartas();
+        novo.marcar_alteracao();
+        novo
+    }
+
+    /// Canastras sem coringa baixadas pelo time.
+    pub fn canastras_limpas(&self, time_a: bool) -> u32 {
+        let jogos = if time_a {
+            &self.jogos_time_a
+        } else {
+            &self.jogos_time_b
+        };
+        jogos
+            .values()
+            .filter(|j| j.len() >= 7 && !tem_coringa(j))
+            .count() as u32
+    }
+
+    /// "Batida" ou "Baralho Esgotado" (só faz sentido com a partida encerrada)
+    pub fn motivo_fim(&self) -> &'static str {
+        if self.bateu.is_some() {
+            "Batida"
+        } else {
+            "Baralho Esgotado"
         }
     }
 
@@ -85,13 +190,23 @@ impl EstadoJogo {
         self.pegou_lixo_nesta_rodada = false;
         self.partida_encerrada = false;
         self.baralho_acabou_nesta_rodada = false;
+        self.bateu = None;
 
         // 3. Distribui cartas novamente
         self.dar_cartas();
     }
 
     pub fn dar_cartas(&mut self) {
-        self.baralho.shuffle();
+        self.dar_cartas_com_semente(rand::random());
+    }
+
+    /// Distribuição reproduzível: refaz o baralho na ordem padrão e embaralha com a semente.
+    pub fn dar_cartas_com_semente(&mut self, semente: u64) {
+        self.semente = semente;
+        self.turno_inicial = self.turno_atual;
+        self.lances.clear();
+        self.baralho = Baralho::new();
+        self.baralho.embaralhar_com_semente(semente);
 
         let mut maos: Vec<Vec<Carta>> = vec![Vec::new(); 4];
 
@@ -161,16 +276,16 @@ impl EstadoJogo {
     }
 
     fn pontos_para_descer(&self, id_jogador: u32) -> i32 {
-        if id_jogador.is_multiple_of(2) {
-            if self.pontuacao_a < 2500 {
-                80
-            } else {
-                100
-            }
-        } else if self.pontuacao_b < 2500 {
-            80
+        let pontuacao_time = if id_jogador.is_multiple_of(2) {
+            self.pontuacao_a
         } else {
-            100
+            self.pontuacao_b
+        };
+
+        if pontuacao_time < self.regras.limite_pontuacao_alta {
+            self.regras.pontos_para_descer
+        } else {
+            self.regras.pontos_para_descer_alto
         }
     }
 
@@ -203,110 +318,80 @@ impl EstadoJogo {
         canastras
     }
 
+    /// Soma ao placar o que a mão encerrada rendeu a cada time (ver `composicao_placar`).
     pub fn contar_pontos(&mut self) {
-        // 1. FASE DE LEITURA
-        // Agora passamos referências para os HashMaps
-        let saldo_a =
-            Self::calcular_pontuacao_parcial(&self.jogos_time_a, &self.tres_vermelhos_time_a);
-        let saldo_b =
-            Self::calcular_pontuacao_parcial(&self.jogos_time_b, &self.tres_vermelhos_time_b);
-
-        // 2. FASE DE ESCRITA
-        self.pontuacao_a += saldo_a;
-        self.pontuacao_b += saldo_b;
+        self.pontuacao_a += self.composicao_placar(true).total();
+        self.pontuacao_b += self.composicao_placar(false).total();
     }
 
-    /// Função pura auxiliar: calcula a pontuação de um time baseada em seus jogos e 3 vermelhos.
-    /// Como ela não precisa de nada do 'self' além dos argumentos, pode ser uma função associada (sem &self)
-    /// ou um método de leitura.
-    /// Calcula a pontuação usando HashMap<u32, Vec<Carta>>
-    fn calcular_pontuacao_parcial(
-        jogos: &HashMap<u32, Vec<Carta>>,
-        tres_vermelhos: &[Carta],
-    ) -> i32 {
-        // --- Passo 1: Analisar Canastras ---
-
-        // .values() ignora as chaves (IDs) e foca apenas nos vetores de cartas
+    /// O que a mão encerrada rende a um time, item por item. É a conta que vai para o
+    /// placar (`contar_pontos`), tanto na batida quanto no esgotamento do monte.
+    pub fn composicao_placar(&self, time_a: bool) -> ComposicaoPlacar {
+        let (jogos, tres_vermelhos) = if time_a {
+            (&self.jogos_time_a, &self.tres_vermelhos_time_a)
+        } else {
+            (&self.jogos_time_b, &self.tres_vermelhos_time_b)
+        };
+        let time = if time_a { 0 } else { 1 };
+
         let tem_canastra_limpa = jogos
             .values()
             .filter(|j| j.len() >= 7)
             .any(|j| !tem_coringa(j));
+        let qtd_3 = tres_vermelhos.len() as i32 * 100;
 
-        // --- Passo 2: Calcular 3 Vermelhos ---
-        let qtd_3 = tres_vermelhos.len() as i32;
-        let pontos_3 = if tem_canastra_limpa {
-            qtd_3 * 100
-        } else {
-            -(qtd_3 * 100)
+        let mut composicao = ComposicaoPlacar {
+            cartas_mesa: jogos.values().flatten().map(|c| c.pontos()).sum(),
+            tres_vermelhos: if tem_canastra_limpa { qtd_3 } else { -qtd_3 },
+            ..Default::default()
         };
-
-        // --- Passo 3: Calcular Pontos dos Jogos (Cartas + Bonificações) ---
-        let pontos_jogos: i32 = jogos
-            .values()
-            .map(|jogo| {
-                // Soma o valor de cada carta individualmente
-                let soma_cartas: i32 = jogo.iter().map(|c| c.pontos()).sum();
-
-                // Calcula bônus de canastra (7+ cartas)
-                let bonus = if jogo.len() >= 7 {
-                    if tem_coringa(jogo) {
-                        100
-                    } else {
-                        300
-                    } //
-                } else {
-                    0
-                };
-
-                soma_cartas + bonus
-            })
-            .sum();
-
-        pontos_3 + pontos_jogos
+        for jogo in jogos.values().filter(|j| j.len() >= 7) {
+            if tem_coringa(jogo) {
+                composicao.canastras_sujas += 100;
+            } else {
+                composicao.canastras_limpas += 300;
+            }
+        }
+        if let Some(batedor) = self.bateu {
+            if batedor % 2 == time {
+                composicao.batida = 100;
+            }
+            // REGRA 19: só paga as cartas da mão quem perdeu para uma batida; se a mão
+            // morreu no monte (ninguém bateu), ninguém é penalizado
+            if self.maos.iter().any(|mao| mao.is_empty()) {
+                composicao.cartas_na_mao = -self
+                    .maos
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i as u32 % 2 == time)
+                    .flat_map(|(_, mao)| mao.iter())
+                    .map(|c| c.pontos())
+                    .sum::<i32>();
+            }
+        }
+        composicao
     }
 
-    pub fn batida(&mut self, id_jogador: u32) {
-        println!("Jogador {} bateu!", id_jogador);
-
-        // Bonificação de batida (geralmente 100 pontos)
-        if id_jogador.is_multiple_of(2) {
-            self.pontuacao_a += 100;
-        } else {
-            self.pontuacao_b += 100;
+    /// Repassa o evento ao observador, a não ser que a mesa esteja sendo refeita.
+    fn emitir(&self, evento: EventoJogo) {
+        if !self.silencioso {
+            eventos::emitir(evento);
         }
+    }
 
+    pub fn batida(&mut self, id_jogador: u32) {
         self.partida_encerrada = true;
+        self.bateu = Some(id_jogador);
 
-        // Chama a contagem final dos pontos da mesa e mãos restantes
-        self.contar_pontos_final();
-
-        // reiniciar jogo
-    }
-
-    // Função auxiliar para penalizar quem ficou com cartas na mão
-    fn contar_pontos_final(&mut self) {
-        // Soma os pontos da mesa (canastras)
+        // Pontos da mesa, bônus da batida e as cartas que sobraram nas mãos
         self.contar_pontos();
+        self.emitir(EventoJogo::Batida {
+            jogador: id_jogador,
+            pontos_a: self.pontuacao_a,
+            pontos_b: self.pontuacao_b,
+        });
 
-        // REGRA 19: Caso as cartas do monte acabem, ninguém é penalizado
-        // Se alguém bateu, self.baralho não necessariamente está vazio.
-        // Se o jogo acabou porque o baralho acabou (e ninguém bateu), a penalidade é ignorada.
-
-        // Assumindo que se self.partida_encerrada == true E baralho vazio, aplica regra 19?
-        // Geralmente a regra é: Se alguém bateu, paga. Se o jogo morreu no baralho, não paga.
-        let deve_penalizar = self.maos.iter().any(|mao| mao.is_empty());
-
-        if deve_penalizar {
-            for (i, mao) in self.maos.iter().enumerate() {
-                let pontos_penalidade: i32 = mao.iter().map(|c| c.pontos()).sum();
-
-                if i % 2 == 0 {
-                    self.pontuacao_a -= pontos_penalidade;
-                } else {
-                    self.pontuacao_b -= pontos_penalidade;
-                }
-            }
-        }
+        // reiniciar jogo
     }
 
     pub fn tentar_comprar_lixo(
@@ -314,22 +399,30 @@ impl EstadoJogo {
         jogador_id: u32,
         mut novos_jogos: Vec<Vec<Carta>>, // <--- Agora é MUT (para inserirmos a carta do lixo se der certo)
         mut ajuntes: Vec<(u32, Vec<Carta>)>, // <--- Agora é MUT
-    ) -> Result<(), String> {
+    ) -> Result<(), Recusa> {
         // --- 1. VALIDAÇÕES BÁSICAS ---
         if self.turno_atual != jogador_id {
-            return Err("Não é seu turno".to_string());
+            return Err(Recusa::new(TipoRecusa::ForaDaVez, "Não é seu turno"));
         }
 
         if self.comprou_nesta_rodada {
-            return Err(
-                "Você já comprou uma carta nessa rodada, portanto não pode pegar lixo.".to_string(),
-            );
+            return Err(Recusa::new(
+                TipoRecusa::Compra,
+                "Você já comprou uma carta nessa rodada, portanto não pode pegar lixo.",
+            ));
         }
 
-        let carta_topo_lixo = self.lixo.last().ok_or("Lixo vazio")?.clone();
+        let carta_topo_lixo = self
+            .lixo
+            .last()
+            .ok_or_else(|| Recusa::new(TipoRecusa::Lixo, "Lixo vazio"))?
+            .clone();
 
         if carta_topo_lixo.trava_o_lixo() {
-            return Err("O lixo está travado (3 Preto, 2 ou Joker).".to_string());
+            return Err(Recusa::new(
+                TipoRecusa::Lixo,
+                "O lixo está travado (3 Preto, 2 ou Joker).",
+            ));
         }
 
         let time_id = jogador_id % 2;
@@ -383,9 +476,10 @@ impl EstadoJogo {
         }
 
         if !lixo_usado {
-            return Err(
-                "Você deve usar a carta do topo em um jogo válido (novo ou existente).".to_string(),
-            );
+            return Err(Recusa::new(
+                TipoRecusa::Lixo,
+                "Você deve usar a carta do topo em um jogo válido (novo ou existente).",
+            ));
         }
 
         // --- 3. VALIDAR INTEGRIDADE DOS JOGOS (Agora os vetores já têm a carta do lixo) ---
@@ -393,7 +487,10 @@ impl EstadoJogo {
         // Validar novos jogos
         for jogo in &novos_jogos {
             if !validar_jogo(jogo) {
-                return Err("Um dos novos jogos é inválido.".to_string());
+                return Err(Recusa::new(
+                    TipoRecusa::JogoInvalido,
+                    "Um dos novos jogos é inválido.",
+                ));
             }
         }
 
@@ -404,13 +501,18 @@ impl EstadoJogo {
             } else {
                 &self.jogos_time_b
             };
-            let jogo_mesa = mesa.get(id_jogo).ok_or("Jogo de ajunte não encontrado.")?;
+            let jogo_mesa = mesa.get(id_jogo).ok_or_else(|| {
+                Recusa::new(TipoRecusa::JogoInvalido, "Jogo de ajunte não encontrado.")
+            })?;
 
             let mut jogo_combinado = jogo_mesa.clone();
             jogo_combinado.extend(cartas_somadas.clone()); // cartas_somadas já inclui a do lixo se foi usada aqui
 
             if !validar_jogo(&jogo_combinado) {
-                return Err("Um dos ajuntes resultou em um jogo inválido.".to_string());
+                return Err(Recusa::new(
+                    TipoRecusa::JogoInvalido,
+                    "Um dos ajuntes resultou em um jogo inválido.",
+                ));
             }
         }
 
@@ -433,10 +535,13 @@ impl EstadoJogo {
             }
 
             if total_pontos < self.pontos_para_descer(jogador_id) {
-                return Err(format!(
-                    "Pontos insuficientes para abrir. Necessário: {}, Obtido: {}",
-                    self.pontos_para_descer(jogador_id),
-                    total_pontos
+                return Err(Recusa::new(
+                    TipoRecusa::PontosParaDescer,
+                    format!(
+                        "Pontos insuficientes para abrir. Necessário: {}, Obtido: {}",
+                        self.pontos_para_descer(jogador_id),
+                        total_pontos
+                    ),
                 ));
             }
         }
@@ -501,14 +606,20 @@ impl EstadoJogo {
         &mut self,
         id_jogador: u32,
         jogos_propostos: Vec<Vec<Carta>>,
-    ) -> Result<(), String> {
+    ) -> Result<(), Recusa> {
         // --- 1. VALIDAÇÕES BÁSICAS ---
         if self.turno_atual != id_jogador {
-            return Err("Não é a sua vez de jogar.".to_string());
+            return Err(Recusa::new(
+                TipoRecusa::ForaDaVez,
+                "Não é a sua vez de jogar.",
+            ));
         }
 
         if jogos_propostos.is_empty() {
-            return Err("Nenhum jogo foi enviado.".to_string());
+            return Err(Recusa::new(
+                TipoRecusa::JogoInvalido,
+                "Nenhum jogo foi enviado.",
+            ));
         }
 
         let jogador_idx = id_jogador as usize;
@@ -520,7 +631,10 @@ impl EstadoJogo {
         for jogo in &jogos_propostos {
             // A. Valida se o jogo em si é válido (sequência/trinca)
             if !validar_jogo(jogo) {
-                return Err("Um dos jogos enviados é inválido.".to_string());
+                return Err(Recusa::new(
+                    TipoRecusa::JogoInvalido,
+                    "Um dos jogos enviados é inválido.",
+                ));
             }
 
             // B. Tenta remover as cartas da mão simulada
@@ -528,7 +642,10 @@ impl EstadoJogo {
                 if let Some(pos) = mao_simulada.iter().position(|c| c == carta) {
                     mao_simulada.remove(pos);
                 } else {
-                    return Err(format!("Você não possui a carta {:?}.", carta));
+                    return Err(Recusa::new(
+                        TipoRecusa::CartaForaDaMao,
+                        format!("Você não possui a carta {:?}.", carta),
+                    ));
                 }
             }
         }
@@ -548,7 +665,10 @@ impl EstadoJogo {
                 .sum();
 
             if total_pontos < self.pontos_para_descer(id_jogador) {
-                return Err("Pontuação insuficiente para abrir o jogo.".to_string());
+                return Err(Recusa::new(
+                    TipoRecusa::PontosParaDescer,
+                    "Pontuação insuficiente para abrir o jogo.",
+                ));
             }
         }
 
@@ -600,9 +720,12 @@ impl EstadoJogo {
         &self,
         id_jogador: usize,
         novos_jogos: &[Vec<Carta>],
-    ) -> Result<(), String> {
+    ) -> Result<(), Recusa> {
         if self.pegou_lixo_nesta_rodada {
-            return Err("Proibido bater após comprar o lixo.".to_string());
+            return Err(Recusa::new(
+                TipoRecusa::Batida,
+                "Proibido bater após comprar o lixo.",
+            ));
         }
 
         //Verifica se já tem real na mesa OU se alguma das novas é real
@@ -610,7 +733,10 @@ impl EstadoJogo {
         let tem_real_novos = novos_jogos.iter().any(|j| j.len() >= 7 && !tem_coringa(j));
 
         if !tem_real_mesa && !tem_real_novos {
-            return Err("Você precisa de pelo menos uma Canastra Real para bater.".to_string());
+            return Err(Recusa::new(
+                TipoRecusa::Batida,
+                "Você precisa de pelo menos uma Canastra Real para bater.",
+            ));
         }
 
         Ok(())
@@ -621,7 +747,7 @@ impl EstadoJogo {
         id_jogador: u32,
         id_jogo: u32,
         cartas_novas: Vec<Carta>,
-    ) -> Result<(), String> {
+    ) -> Result<(), Recusa> {
         let jogador_idx = id_jogador as usize;
         let time_id = id_jogador % 2;
 
@@ -631,7 +757,12 @@ impl EstadoJogo {
         } else {
             self.jogos_time_b.get_mut(&id_jogo)
         }
-        .ok_or("Jogo não encontrado ou não pertence ao seu time.")?;
+        .ok_or_else(|| {
+            Recusa::new(
+                TipoRecusa::JogoInvalido,
+                "Jogo não encontrado ou não pertence ao seu time.",
+            )
+        })?;
 
         // 2. Validar se o jogador tem as cartas novas na mão
         let mut mao_simulada = self.maos[jogador_idx].clone();
@@ -639,7 +770,10 @@ impl EstadoJogo {
             if let Some(pos) = mao_simulada.iter().position(|x| x == c) {
                 mao_simulada.remove(pos);
             } else {
-                return Err(format!("Você não tem a carta {:?} na mão.", c));
+                return Err(Recusa::new(
+                    TipoRecusa::CartaForaDaMao,
+                    format!("Você não tem a carta {:?} na mão.", c),
+                ));
             }
         }
 
@@ -649,7 +783,10 @@ impl EstadoJogo {
 
         // 4. Validar a nova formação (Aqui entra a lógica do coringa e do 4 ao A)
         if !validar_jogo(&jogo_simulado) {
-            return Err("A nova formação do jogo é inválida.".to_string());
+            return Err(Recusa::new(
+                TipoRecusa::JogoInvalido,
+                "A nova formação do jogo é inválida.",
+            ));
         }
 
         // 5. Se passou na validação, aplicar as mudanças
@@ -671,15 +808,19 @@ impl EstadoJogo {
         Ok(())
     }
 
-    pub fn comprar_carta(&mut self, id_jogador: usize) -> Result<Carta, String> {
+    pub fn comprar_carta(&mut self, id_jogador: usize) -> Result<Carta, Recusa> {
         if self.comprou_nesta_rodada {
-            return Err("Você já comprou uma carta neste turno. Jogue ou descarte.".to_string());
+            return Err(Recusa::new(
+                TipoRecusa::Compra,
+                "Você já comprou uma carta neste turno. Jogue ou descarte.",
+            ));
         }
 
         if self.pegou_lixo_nesta_rodada {
-            return Err(
-                "Você já pegou lixo nessa rodada, portanto não pode comprar uma carta".to_string(),
-            );
+            return Err(Recusa::new(
+                TipoRecusa::Compra,
+                "Você já pegou lixo nessa rodada, portanto não pode comprar uma carta",
+            ));
         }
 
         // Verifica se ainda tem cartas no monte
@@ -712,13 +853,16 @@ impl EstadoJogo {
         if lixo_vazio || lixo_travado {
             // Se não tem baralho e o lixo não pode ser pego, ACABOU.
             self.encerrar_partida_por_esgotamento();
-            Err("O baralho acabou e o lixo está vazio ou travado. Fim de jogo.".to_string())
+            Err(Recusa::new(
+                TipoRecusa::MonteEsgotado,
+                "O baralho acabou e o lixo está vazio ou travado. Fim de jogo.",
+            ))
         } else {
             // O baralho acabou, mas o lixo está disponível.
             // Marcamos a flag para saber que o jogo deve acabar logo após esse turno.
             self.baralho_acabou_nesta_rodada = true;
 
-            Err("O baralho acabou! Esta é a última chance. Você deve tentar comprar o lixo (fazer jogo/ajunte) ou o jogo encerrará.".to_string())
+            Err(Recusa::new(TipoRecusa::MonteEsgotado, "O baralho acabou! Esta é a última chance. Você deve tentar comprar o lixo (fazer jogo/ajunte) ou o jogo encerrará."))
         }
     }
 
@@ -736,15 +880,16 @@ impl EstadoJogo {
         &mut self,
         id_jogador: u32,
         carta_descarte: Carta,
-    ) -> Result<(), String> {
+    ) -> Result<(), Recusa> {
         if self.turno_atual != id_jogador {
-            return Err("Não é seu turno".to_string());
+            return Err(Recusa::new(TipoRecusa::ForaDaVez, "Não é seu turno"));
         }
 
         if !self.comprou_nesta_rodada && !self.pegou_lixo_nesta_rodada {
-            return Err(
-                "Você precisa comprar do baralho ou pegar o lixo antes de descartar.".to_string(),
-            );
+            return Err(Recusa::new(
+                TipoRecusa::Compra,
+                "Você precisa comprar do baralho ou pegar o lixo antes de descartar.",
+            ));
         }
 
         let jogador_idx = id_jogador as usize;
@@ -755,7 +900,7 @@ impl EstadoJogo {
         if self.pegou_lixo_nesta_rodada && self.maos[jogador_idx].len() == 1 {
             // Verifica se a carta a ser descartada é a única que resta
             if self.maos[jogador_idx].contains(&carta_descarte) {
-                return Err("Regra 24: Você pegou o lixo, então não pode bater (ficar sem cartas) neste turno.".to_string());
+                return Err(Recusa::new(TipoRecusa::Batida, "Regra 24: Você pegou o lixo, então não pode bater (ficar sem cartas) neste turno."));
             }
         }
 
@@ -763,21 +908,24 @@ impl EstadoJogo {
         let pos = self.maos[jogador_idx]
             .iter()
             .position(|c| c == &carta_descarte)
-            .ok_or("Carta não encontrada.")?;
+            .ok_or_else(|| Recusa::new(TipoRecusa::CartaForaDaMao, "Carta não encontrada."))?;
+
+        // Regra 16: ficar sem cartas é bater, e bater exige Real. Confere antes de
+        // mexer na mão, para a recusa não levar a carta embora.
+        let vai_bater = self.maos[jogador_idx].len() == 1;
+        if vai_bater && !self.conferir_real(jogador_idx) {
+            return Err(Recusa::new(
+                TipoRecusa::Batida,
+                "Você ficaria sem cartas mas não tem Canastra Real!",
+            ));
+        }
 
         let carta = self.maos[jogador_idx].remove(pos);
         self.lixo.push(carta);
 
-        // Verifica batida (Regra 16 - Precisa de Real)
-        if self.maos[jogador_idx].is_empty() {
-            if self.conferir_real(jogador_idx) {
-                self.batida(id_jogador);
-                return Ok(()); // Jogo acabou por batida
-            } else {
-                // Rollback visual (em memória real teria que devolver a carta,
-                // mas aqui retornamos erro fatal ou tratamos)
-                return Err("Você ficou sem cartas mas não tem Canastra Real!".to_string());
-            }
+        if vai_bater {
+            self.batida(id_jogador);
+            return Ok(()); // Jogo acabou por batida
         }
 
         // --- AQUI ENTRA A REGRA 20 (Fim da Rodada Extra) ---
@@ -795,34 +943,60 @@ impl EstadoJogo {
         Ok(())
     }
 
-    fn encerrar_partida_por_esgotamento(&mut self) {
-        println!("Fim de jogo por esgotamento do baralho!");
+    pub(crate) fn encerrar_partida_por_esgotamento(&mut self) {
         self.partida_encerrada = true;
 
-        // Calcula apenas os pontos da mesa (positivos)
-        // REGRA 19: Ninguém é penalizado pelas cartas na mão
-
-        // Time A
-        let saldo_a =
-            Self::calcular_pontuacao_parcial(&self.jogos_time_a, &self.tres_vermelhos_time_a);
-        self.pontuacao_a += saldo_a;
+        // Só os pontos da mesa: ninguém bateu, então ninguém paga as cartas da mão (REGRA 19)
+        self.contar_pontos();
 
-        // Time B
-        let saldo_b =
-            Self::calcular_pontuacao_parcial(&self.jogos_time_b, &self.tres_vermelhos_time_b);
-        self.pontuacao_b += saldo_b;
+        self.emitir(EventoJogo::Esgotamento {
+            pontos_a: self.pontuacao_a,
+            pontos_b: self.pontuacao_b,
+        });
     }
 
     /// Função principal que recebe a intenção do jogador e executa no Core.
-    pub fn realizar_acao(&mut self, id_jogador: u32, acao: AcaoJogador) -> Result<String, String> {
+    /// A compra com o monte vazio é recusada mas muda o jogo (Regra 20): também vira lance.
+    pub fn realizar_acao(&mut self, id_jogador: u32, acao: AcaoJogador) -> Result<String, Recusa> {
+        let (encerrada, monte_vazio) = (self.partida_encerrada, self.baralho_acabou_nesta_rodada);
+        let resultado = self.executar_acao(id_jogador, acao.clone());
+        let lance = match &resultado {
+            Ok(_) => Some(Lance::Acao {
+                jogador: id_jogador,
+                acao,
+            }),
+            Err(_) if self.partida_encerrada && !encerrada => Some(Lance::Esgotamento {
+                jogador: id_jogador,
+            }),
+            Err(_) if self.baralho_acabou_nesta_rodada && !monte_vazio => Some(Lance::MonteVazio {
+                jogador: id_jogador,
+            }),
+            Err(_) => None,
+        };
+        if let Some(lance) = lance {
+            self.lances.push(lance);
+            self.marcar_alteracao();
+        }
+        resultado
+    }
+
+    /// Para mudanças feitas fora de `realizar_acao` (ex.: o servidor reinicia a mão).
+    pub fn marcar_alteracao(&mut self) {
+        self.versao += 1;
+    }
+
+    fn executar_acao(&mut self, id_jogador: u32, acao: AcaoJogador) -> Result<String, Recusa> {
         // 1. Validação de Turno e Fim de Jogo
         if self.partida_encerrada {
-            return Err("A partida já encerrou.".to_string());
+            return Err(Recusa::new(
+                TipoRecusa::PartidaEncerrada,
+                "A partida já encerrou.",
+            ));
         }
         if self.turno_atual != id_jogador {
-            return Err(format!(
-                "Não é seu turno. Vez do jogador {}.",
-                self.turno_atual
+            return Err(Recusa::new(
+                TipoRecusa::ForaDaVez,
+                format!("Não é seu turno. Vez do jogador {}.", self.turno_atual),
             ));
         }
 
@@ -878,11 +1052,6 @@ impl EstadoJogo {
                     Ok("Carta descartada. Turno passou.".to_string())
                 }
             }
-
-            AcaoJogador::Mensagem { texto } => {
-                println!("{}", texto);
-                Ok("Mensagem enviada".to_string())
-            }
         }
     }
     pub fn gerar_visao_para(&self, id_observador: u32) -> VisaoJogador {
@@ -935,10 +1104,20 @@ impl EstadoJogo {
             qtd_lixo: self.qtd_lixo,
             qtd_monte: self.qtd_monte,
             verso_topo: self.verso_topo,
+            versao: self.versao,
+            tempo: None,
             // Assumindo que você tem lógica de morto, senão hardcode false
         }
     }
 
+    /// Visão de quem assiste: só o que é público, ou a mão do jogador que o treinador acompanha.
+    /// Nunca pode jogar.
+    pub fn gerar_visao_espectador(&self, treinando: Option<u32>) -> VisaoJogador {
+        let mut visao = self.gerar_visao_para(treinando.unwrap_or(ID_ESPECTADOR));
+        visao.posso_jogar = false;
+        visao
+    }
+
     pub fn gerar_visao_para_jogador(&self, id_observador: u32) -> VisaoJogador {
         // 1. Clonar a mesa (Pública para todos)
         // Aqui assumo que você tem uma função para converter seu HashMap da mesa em Vec<DetalheJogo>
@@ -977,6 +1156,8 @@ impl EstadoJogo {
             qtd_lixo: self.qtd_lixo,
             qtd_monte: self.qtd_monte,
             verso_topo: self.verso_topo,
+            versao: self.versao,
+            tempo: None,
         }
     }
 
@@ -1015,6 +1196,7 @@ impl EstadoJogo {
         self.pegou_lixo_nesta_rodada = false;
 
         self.partida_encerrada = false;
+        self.bateu = None;
         self.proximo_id_jogo += 1;
         self.baralho_acabou_nesta_rodada = false;
         self.comprou_nesta_rodada = false;
@@ -0,0 +1,37 @@
+use std::sync::OnceLock;
+
+// --- EVENTOS DO JOGO ---
+// O core não escreve nada na saída: quem usa (o servidor) instala um observador e
+// decide o que fazer com cada evento (log estruturado, métricas...). Sem observador,
+// os eventos são descartados. O observador roda na hora, dentro da chamada que gerou
+// o evento, então o contexto de quem chamou (sala, jogador) continua valendo.
+// Mesas refeitas a partir de um registro (validação, invariantes, replay) ficam
+// silenciosas: o que já aconteceu não é avisado de novo.
+
+/// Algo que aconteceu dentro do jogo e que não volta como resultado de uma ação.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventoJogo {
+    /// Um jogador bateu e a mão acabou (placar já somado).
+    Batida {
+        jogador: u32,
+        pontos_a: i32,
+        pontos_b: i32,
+    },
+    /// O monte acabou e a mão foi encerrada sem batida (placar já somado).
+    Esgotamento { pontos_a: i32, pontos_b: i32 },
+}
+
+pub type Observador = fn(&EventoJogo);
+
+static OBSERVADOR: OnceLock<Observador> = OnceLock::new();
+
+/// Instala o observador do processo. Só o primeiro vale; devolve false se já havia um.
+pub fn definir_observador(observador: Observador) -> bool {
+    OBSERVADOR.set(observador).is_ok()
+}
+
+pub(crate) fn emitir(evento: EventoJogo) {
+    if let Some(observador) = OBSERVADOR.get() {
+        observador(&evento);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::baralho::{Baralho, Carta};
+use crate::estado::EstadoJogo;
+use crate::registro::MaoRegistrada;
+use crate::regras::validar_jogo;
+
+// --- INVARIANTES DA MESA ---
+// Conferência usada pela administração do servidor quando uma mesa parece travada:
+// as 108 cartas continuam todas lá, os jogos baixados são válidos e o estado é o mesmo
+// que se obtém refazendo a mão a partir da semente e dos lances.
+
+impl EstadoJogo {
+    /// Lista os problemas encontrados na mesa (vazia = tudo certo).
+    pub fn verificar_invariantes(&self) -> Vec<String> {
+        let mut problemas = Vec::new();
+
+        if self.maos.len() != 4 {
+            problemas.push(format!("Há {} mãos em vez de 4.", self.maos.len()));
+        }
+        if self.turno_atual >= 4 {
+            problemas.push(format!("Turno {} não é um assento.", self.turno_atual));
+        }
+        if self.bateu.is_some() && !self.partida_encerrada {
+            problemas.push("Alguém bateu, mas a partida não está encerrada.".to_string());
+        }
+
+        // Todas as cartas: nenhuma sumiu, nenhuma apareceu duas vezes
+        let mut cartas: Vec<Carta> = self
+            .baralho
+            .cartas
+            .iter()
+            .chain(&self.lixo)
+            .chain(self.maos.iter().flatten())
+            .chain(self.jogos_time_a.values().flatten())
+            .chain(self.jogos_time_b.values().flatten())
+            .chain(&self.tres_vermelhos_time_a)
+            .chain(&self.tres_vermelhos_time_b)
+            .cloned()
+            .collect();
+        let mut esperadas = Baralho::new().cartas;
+        cartas.sort();
+        esperadas.sort();
+        if cartas != esperadas {
+            problemas.push(format!(
+                "A mesa tem {} cartas que não batem com as {} do baralho.",
+                cartas.len(),
+                esperadas.len()
+            ));
+        }
+
+        for (time, jogos) in [("A", &self.jogos_time_a), ("B", &self.jogos_time_b)] {
+            for (id, jogo) in jogos {
+                if !validar_jogo(jogo) {
+                    problemas.push(format!("Jogo {} do time {} é inválido.", id, time));
+                }
+            }
+        }
+
+        // A mão refeita do zero precisa chegar exatamente aqui
+        match MaoRegistrada::de_estado(self).reproduzir(&self.regras) {
+            Err(e) => problemas.push(format!("A mão não pode ser refeita: {}", e)),
+            Ok(estados) => {
+                if let Some(refeito) = estados.last() {
+                    let confere = refeito.maos == self.maos
+                        && refeito.lixo == self.lixo
+                        && refeito.jogos_time_a == self.jogos_time_a
+                        && refeito.jogos_time_b == self.jogos_time_b
+                        && refeito.turno_atual == self.turno_atual
+                        && refeito.pontuacao_a == self.pontuacao_a
+                        && refeito.pontuacao_b == self.pontuacao_b
+                        && refeito.partida_encerrada == self.partida_encerrada
+                        && refeito.baralho_acabou_nesta_rodada == self.baralho_acabou_nesta_rodada;
+                    if !confere {
+                        problemas.push(
+                            "O estado não é o mesmo que se obtém refazendo os lances da mão."
+                                .to_string(),
+                        );
+                    }
+                }
+            }
+        }
+
+        problemas
+    }
+}
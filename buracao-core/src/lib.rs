@@ -1,10 +1,19 @@
 // Conteúdo de lib.rs
 pub mod acoes;
+pub mod automatico;
 pub mod baralho;
+pub mod delta;
 pub mod estado;
+pub mod eventos;
+pub mod invariantes;
+pub mod lobby;
+pub mod protocolo;
+pub mod registro;
 pub mod regras;
+pub mod torneio;
 
 // Facilita a vida de quem usa:
-pub use acoes::AcaoJogador;
+pub use acoes::{AcaoJogador, MsgCliente, MsgServidor, Recusa, TipoRecusa, VERSAO_PROTOCOLO};
 pub use baralho::{Carta, Naipe, Valor, Verso};
 pub use estado::EstadoJogo;
+pub use regras::ConfigRegras;
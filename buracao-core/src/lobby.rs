@@ -0,0 +1,161 @@
+use crate::regras::ConfigRegras;
+use serde::{Deserialize, Serialize};
+
+// --- TIPOS DO LOBBY (API HTTP) ---
+// Compartilhados entre o servidor (que responde) e o web (que monta o navegador de salas).
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusSala {
+    /// Ainda há assentos livres
+    AguardandoJogadores,
+    /// Os quatro assentos estão ocupados e a partida está rolando
+    EmAndamento,
+    /// A partida terminou (batida ou baralho esgotado)
+    Encerrada,
+}
+
+/// Um dos quatro lugares da mesa.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AssentoSala {
+    pub id: u32,
+    /// Nome de quem ocupa o assento (None = livre)
+    pub nome: Option<String>,
+    /// Se o dono do assento está com a conexão aberta agora
+    pub conectado: bool,
+}
+
+/// Resumo público de uma sala, sem nada que revele o jogo em si.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResumoSala {
+    pub codigo: String,
+    pub nome: String,
+    pub publica: bool,
+    pub tem_senha: bool,
+    pub status: StatusSala,
+    pub assentos: Vec<AssentoSala>,
+    pub regras: ConfigRegras,
+    #[serde(default)]
+    pub relogio: Option<ConfigRelogio>,
+    #[serde(default)]
+    pub espectadores: ConfigEspectadores,
+    #[serde(default)]
+    pub qtd_espectadores: usize,
+}
+
+impl ResumoSala {
+    pub fn assentos_ocupados(&self) -> usize {
+        self.assentos.iter().filter(|a| a.nome.is_some()).count()
+    }
+}
+
+/// Resposta da criação de sala: o resumo e o token que faz de quem criou o anfitrião.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SalaCriada {
+    #[serde(flatten)]
+    pub resumo: ResumoSala,
+    /// Vale uma vez: o primeiro login que trouxer o token vira o anfitrião
+    pub token_anfitriao: String,
+}
+
+/// Corpo do pedido de criação de sala.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NovaSala {
+    pub nome: String,
+    #[serde(default = "publica_por_padrao")]
+    pub publica: bool,
+    #[serde(default)]
+    pub senha: Option<String>,
+    /// None = regras padrão do servidor
+    #[serde(default)]
+    pub regras: Option<ConfigRegras>,
+    /// Relógio de turno (None = sem limite de tempo)
+    #[serde(default)]
+    pub relogio: Option<ConfigRelogio>,
+    #[serde(default)]
+    pub espectadores: ConfigEspectadores,
+}
+
+/// Tempo por turno com banco de tempo: estourado o turno, o jogador gasta o banco;
+/// quando o banco também acaba, o servidor joga por ele.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct ConfigRelogio {
+    pub segundos_por_turno: u32,
+    /// Reserva de cada jogador para a partida inteira
+    pub banco_segundos: u32,
+}
+
+impl Default for ConfigRelogio {
+    fn default() -> Self {
+        Self {
+            segundos_por_turno: 60,
+            banco_segundos: 120,
+        }
+    }
+}
+
+impl ConfigRelogio {
+    pub fn validar(&self) -> Result<(), String> {
+        if !(10..=600).contains(&self.segundos_por_turno) {
+            return Err("O tempo por turno deve ficar entre 10 e 600 segundos.".to_string());
+        }
+        if self.banco_segundos > 1800 {
+            return Err("O banco de tempo pode ter no máximo 1800 segundos.".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Linha do ranking (GET /api/ranking).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EntradaRanking {
+    pub id_perfil: i64,
+    pub nome: String,
+    pub rating: f64,
+    pub partidas: u32,
+    pub vitorias: u32,
+    pub batidas: u32,
+    pub canastras_limpas: u32,
+    /// Média dos pontos do time por partida (cada partida é uma mão)
+    pub media_pontos: f64,
+}
+
+/// O que quem só assiste pode ver e fazer.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct ConfigEspectadores {
+    pub permitidos: bool,
+    /// Atraso da transmissão, para quem assiste não soprar jogadas para a mesa
+    pub atraso_segundos: u32,
+    /// Espectadores podem falar no chat da mesa
+    pub chat: bool,
+    /// Permite treinador: um espectador que acompanha a mão de um jogador (sem atraso).
+    /// O jogador é avisado quando alguém passa a ver a mão dele.
+    pub treinador: bool,
+}
+
+impl Default for ConfigEspectadores {
+    fn default() -> Self {
+        Self {
+            permitidos: true,
+            atraso_segundos: 0,
+            chat: false,
+            treinador: false,
+        }
+    }
+}
+
+impl ConfigEspectadores {
+    pub fn validar(&self) -> Result<(), String> {
+        if self.atraso_segundos > 600 {
+            return Err(
+                "O atraso para espectadores pode ser de no máximo 600 segundos.".to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+fn publica_por_padrao() -> bool {
+    true
+}
@@ -0,0 +1,100 @@
+use crate::acoes::{DadosLogin, MsgCliente};
+use serde::{Deserialize, Serialize};
+
+// --- LEITURA DAS MENSAGENS DO CLIENTE ---
+// Tudo que chega pelo WebSocket passa por `decodificar` antes de tocar no jogo: bytes que
+// não são texto, JSON quebrado ou um login com campos absurdos viram um `ErroMensagem`
+// que volta para o cliente, em vez de serem ignorados em silêncio.
+
+// Tamanho máximo (em caracteres) de cada campo do login
+pub const TAMANHO_MAX_NOME: usize = 40;
+pub const TAMANHO_MAX_SALA: usize = 40;
+pub const TAMANHO_MAX_DEVICE: usize = 64;
+pub const TAMANHO_MAX_SENHA: usize = 64;
+pub const TAMANHO_MAX_CHAVE: usize = 128;
+// O detalhe do erro do serde pode repetir pedaços da entrada: não devolvemos mais que isso
+const TAMANHO_MAX_DETALHE: usize = 120;
+
+/// Por que uma mensagem do cliente foi rejeitada antes de chegar ao jogo.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "erro", content = "detalhes")]
+pub enum ErroMensagem {
+    /// Frame binário ou texto que não é UTF-8
+    NaoTexto,
+    /// Maior que o limite do servidor (em bytes)
+    MuitoGrande { limite: usize },
+    /// Não é JSON ou não é nenhuma mensagem conhecida
+    JsonInvalido { detalhe: String },
+    /// Campo do login vazio ou longo demais
+    CampoInvalido { campo: String, limite: usize },
+    /// Mensagens rápidas demais: o servidor ignora tudo pelos próximos `espera_ms`
+    RitmoExcedido { espera_ms: u64 },
+}
+
+impl ErroMensagem {
+    /// Texto para mostrar ao jogador
+    pub fn texto(&self) -> String {
+        match self {
+            ErroMensagem::NaoTexto => "Mensagem em formato desconhecido.".to_string(),
+            ErroMensagem::MuitoGrande { limite } => {
+                format!("Mensagem grande demais (máximo {} bytes).", limite)
+            }
+            ErroMensagem::JsonInvalido { .. } => "Mensagem inválida.".to_string(),
+            ErroMensagem::CampoInvalido { campo, limite } => {
+                format!(
+                    "Campo '{}' vazio ou longo demais (máximo {}).",
+                    campo, limite
+                )
+            }
+            ErroMensagem::RitmoExcedido { espera_ms } => format!(
+                "Calma! Muitas ações seguidas; aguarde {:.1}s.",
+                *espera_ms as f64 / 1000.0
+            ),
+        }
+    }
+}
+
+fn campo(nome: &str, valor: &str, limite: usize, obrigatorio: bool) -> Result<(), ErroMensagem> {
+    let tamanho = valor.chars().count();
+    if tamanho > limite || (obrigatorio && valor.trim().is_empty()) {
+        return Err(ErroMensagem::CampoInvalido {
+            campo: nome.to_string(),
+            limite,
+        });
+    }
+    Ok(())
+}
+
+impl DadosLogin {
+    /// Confere os tamanhos antes de o servidor usar os campos como chave de sala/sessão.
+    pub fn validar(&self) -> Result<(), ErroMensagem> {
+        campo("device_id", &self.device_id, TAMANHO_MAX_DEVICE, true)?;
+        campo("nome", &self.nome, TAMANHO_MAX_NOME, false)?;
+        campo("sala", &self.sala, TAMANHO_MAX_SALA, true)?;
+        let opcionais = [
+            ("senha", &self.senha, TAMANHO_MAX_SENHA),
+            ("token", &self.token, TAMANHO_MAX_CHAVE),
+            ("identidade", &self.identidade, TAMANHO_MAX_CHAVE),
+            ("token_anfitriao", &self.token_anfitriao, TAMANHO_MAX_CHAVE),
+        ];
+        for (nome, valor, limite) in opcionais {
+            if let Some(valor) = valor {
+                campo(nome, valor, limite, false)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bytes de um frame do WebSocket -> mensagem do cliente (login já validado).
+pub fn decodificar(bytes: &[u8]) -> Result<MsgCliente, ErroMensagem> {
+    let texto = std::str::from_utf8(bytes).map_err(|_| ErroMensagem::NaoTexto)?;
+    let msg =
+        serde_json::from_str::<MsgCliente>(texto).map_err(|e| ErroMensagem::JsonInvalido {
+            detalhe: e.to_string().chars().take(TAMANHO_MAX_DETALHE).collect(),
+        })?;
+    if let MsgCliente::Login(dados) = &msg {
+        dados.validar()?;
+    }
+    Ok(msg)
+}
@@ -0,0 +1,228 @@
+use crate::acoes::AcaoJogador;
+use crate::estado::EstadoJogo;
+use crate::regras::ConfigRegras;
+use serde::{Deserialize, Serialize};
+
+// --- REGISTRO DE PARTIDA ---
+// Formato de arquivo de uma partida terminada ("PGN do buraco"): quem jogou em cada
+// assento, a semente de cada distribuição e todos os lances em ordem. Com isso qualquer
+// ferramenta refaz a partida carta a carta. O formato está documentado em
+// docs/registro-de-partida.md; mudança incompatível sobe VERSAO_REGISTRO.
+
+pub const FORMATO_REGISTRO: &str = "buracao-registro";
+pub const VERSAO_REGISTRO: u32 = 1;
+
+/// Algo que mudou o jogo, na ordem em que aconteceu.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "tipo")]
+pub enum Lance {
+    /// Ação aceita (inclusive as que o servidor faz quando o tempo do jogador acaba)
+    Acao { jogador: u32, acao: AcaoJogador },
+    /// O jogador tentou comprar com o monte vazio e o lixo ainda podia ser pego: a mão
+    /// acaba no descarte dele (Regra 20)
+    MonteVazio { jogador: u32 },
+    /// O monte acabou e a mão foi encerrada: na compra, com o lixo vazio ou travado, ou
+    /// pelo servidor, quando quem estava sem tempo não tinha como usar o lixo
+    Esgotamento { jogador: u32 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JogadorRegistro {
+    pub assento: u32,
+    pub nome: String,
+    /// Perfil do ranking, quando o servidor identificou o jogador
+    #[serde(default)]
+    pub id_perfil: Option<i64>,
+}
+
+/// Uma mão: distribuição reproduzível, lances e o placar que ela deu.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MaoRegistrada {
+    pub numero_partida: u32,
+    pub semente: u64,
+    /// Quem jogou primeiro
+    pub turno_inicial: u32,
+    pub lances: Vec<Lance>,
+    pub pontos_a: i32,
+    pub pontos_b: i32,
+    /// Assento de quem bateu (None = monte esgotado)
+    #[serde(default)]
+    pub bateu: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegistroPartida {
+    /// Sempre "buracao-registro"
+    pub formato: String,
+    pub versao: u32,
+    /// Nome da sala em que foi jogada
+    pub sala: String,
+    /// Fim da partida, em segundos desde 1970 (UTC)
+    pub terminou_em: i64,
+    pub regras: ConfigRegras,
+    pub jogadores: Vec<JogadorRegistro>,
+    pub maos: Vec<MaoRegistrada>,
+    pub pontos_a: i32,
+    pub pontos_b: i32,
+}
+
+/// Linha da lista de partidas arquivadas (GET /api/partidas).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResumoRegistro {
+    pub id: i64,
+    pub sala: String,
+    pub terminou_em: i64,
+    /// Nomes na ordem dos assentos
+    pub jogadores: Vec<String>,
+    pub pontos_a: i32,
+    pub pontos_b: i32,
+    /// Veio de um arquivo enviado, não de uma mesa deste servidor
+    pub importada: bool,
+}
+
+impl MaoRegistrada {
+    /// Registro da mão que o estado está jogando (ou acabou de jogar).
+    pub fn de_estado(estado: &EstadoJogo) -> Self {
+        Self {
+            numero_partida: estado.numero_partida,
+            semente: estado.semente,
+            turno_inicial: estado.turno_inicial,
+            lances: estado.lances.clone(),
+            pontos_a: estado.pontuacao_a,
+            pontos_b: estado.pontuacao_b,
+            bateu: estado.bateu,
+        }
+    }
+
+    /// A mesa logo depois da distribuição. Nada do que for refeito nela vira evento.
+    pub fn estado_inicial(&self, regras: &ConfigRegras) -> EstadoJogo {
+        let mut estado = EstadoJogo::new();
+        estado.silencioso = true;
+        estado.regras = regras.clone();
+        estado.numero_partida = self.numero_partida;
+        estado.turno_atual = self.turno_inicial;
+        estado.dar_cartas_com_semente(self.semente);
+        estado
+    }
+
+    /// Aplica um lance como a mesa original aplicou.
+    pub fn aplicar(estado: &mut EstadoJogo, lance: &Lance) -> Result<(), String> {
+        match lance {
+            Lance::Acao { jogador, acao } => {
+                estado.realizar_acao(*jogador, acao.clone())?;
+            }
+            Lance::MonteVazio { jogador } => {
+                // A compra é recusada; o que conta é ela ter virado o mesmo lance
+                let _ = estado.realizar_acao(*jogador, AcaoJogador::ComprarBaralho);
+                if !matches!(estado.lances.last(), Some(Lance::MonteVazio { jogador: j }) if j == jogador)
+                {
+                    return Err("O monte não estava vazio.".to_string());
+                }
+            }
+            Lance::Esgotamento { jogador } => {
+                if estado.partida_encerrada {
+                    return Err("A mão já tinha acabado.".to_string());
+                }
+                if estado.baralho.restantes() > 0 {
+                    return Err("O monte ainda tinha cartas.".to_string());
+                }
+                estado.encerrar_partida_por_esgotamento();
+                estado.lances.push(Lance::Esgotamento { jogador: *jogador });
+                estado.marcar_alteracao();
+            }
+        }
+        Ok(())
+    }
+
+    /// Estados da mão: a distribuição e um depois de cada lance.
+    pub fn reproduzir(&self, regras: &ConfigRegras) -> Result<Vec<EstadoJogo>, String> {
+        let mut estado = self.estado_inicial(regras);
+        let mut estados = vec![estado.clone()];
+        for (i, lance) in self.lances.iter().enumerate() {
+            Self::aplicar(&mut estado, lance)
+                .map_err(|e| format!("Lance {} não confere: {}", i + 1, e))?;
+            estados.push(estado.clone());
+        }
+        Ok(estados)
+    }
+}
+
+impl RegistroPartida {
+    pub fn novo(
+        sala: String,
+        terminou_em: i64,
+        estado: &EstadoJogo,
+        jogadores: Vec<JogadorRegistro>,
+    ) -> Self {
+        Self {
+            formato: FORMATO_REGISTRO.to_string(),
+            versao: VERSAO_REGISTRO,
+            sala,
+            terminou_em,
+            regras: estado.regras.clone(),
+            jogadores,
+            maos: vec![MaoRegistrada::de_estado(estado)],
+            pontos_a: estado.pontuacao_a,
+            pontos_b: estado.pontuacao_b,
+        }
+    }
+
+    /// Confere o cabeçalho e refaz cada mão: todos os lances precisam ser aceitos e o
+    /// placar final precisa bater com o registrado.
+    pub fn validar(&self) -> Result<(), String> {
+        if self.formato != FORMATO_REGISTRO {
+            return Err(format!("Formato desconhecido: '{}'.", self.formato));
+        }
+        if self.versao != VERSAO_REGISTRO {
+            return Err(format!(
+                "Versão {} do registro não suportada (esperada {}).",
+                self.versao, VERSAO_REGISTRO
+            ));
+        }
+        let mut assentos: Vec<u32> = self.jogadores.iter().map(|j| j.assento).collect();
+        assentos.sort();
+        if assentos != [0, 1, 2, 3] {
+            return Err("O registro precisa de um jogador em cada um dos 4 assentos.".to_string());
+        }
+        self.regras.validar()?;
+        if self.maos.is_empty() {
+            return Err("O registro não tem nenhuma mão.".to_string());
+        }
+
+        let (mut total_a, mut total_b) = (0, 0);
+        for (i, mao) in self.maos.iter().enumerate() {
+            let estados = mao
+                .reproduzir(&self.regras)
+                .map_err(|e| format!("Mão {}: {}", i + 1, e))?;
+            let fim = estados.last().unwrap();
+            if !fim.partida_encerrada {
+                return Err(format!("Mão {}: os lances não terminam a mão.", i + 1));
+            }
+            if (fim.pontuacao_a, fim.pontuacao_b) != (mao.pontos_a, mao.pontos_b) {
+                return Err(format!(
+                    "Mão {}: o placar refeito ({} x {}) não bate com o registrado ({} x {}).",
+                    i + 1,
+                    fim.pontuacao_a,
+                    fim.pontuacao_b,
+                    mao.pontos_a,
+                    mao.pontos_b
+                ));
+            }
+            total_a += mao.pontos_a;
+            total_b += mao.pontos_b;
+        }
+        if (total_a, total_b) != (self.pontos_a, self.pontos_b) {
+            return Err("O placar da partida não é a soma das mãos.".to_string());
+        }
+        Ok(())
+    }
+
+    /// Nome do jogador de um assento (para listas e para o visualizador).
+    pub fn nome(&self, assento: u32) -> &str {
+        self.jogadores
+            .iter()
+            .find(|j| j.assento == assento)
+            .map(|j| j.nome.as_str())
+            .unwrap_or("?")
+    }
+}
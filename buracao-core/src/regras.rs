@@ -1,4 +1,70 @@
 use crate::baralho::{Carta, Valor};
+use serde::{Deserialize, Serialize};
+
+// --- CONFIGURAÇÃO DE REGRAS DA SALA ---
+
+/// Parâmetros de regra que podem variar de uma sala para outra.
+/// O padrão segue as regras descritas no README.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ConfigRegras {
+    /// Pontos necessários para a primeira descida enquanto o time está abaixo do limite
+    pub pontos_para_descer: i32,
+    /// Pontos necessários para a primeira descida quando o time atingiu o limite
+    pub pontos_para_descer_alto: i32,
+    /// Pontuação do time a partir da qual a descida passa a exigir `pontos_para_descer_alto`
+    pub limite_pontuacao_alta: i32,
+}
+
+impl Default for ConfigRegras {
+    fn default() -> Self {
+        Self {
+            pontos_para_descer: 80,
+            pontos_para_descer_alto: 100,
+            limite_pontuacao_alta: 2500,
+        }
+    }
+}
+
+/// Nomes aceitos por `ConfigRegras::predefinida`.
+pub const REGRAS_PREDEFINIDAS: [&str; 3] = ["padrao", "rapido", "exigente"];
+
+impl ConfigRegras {
+    /// Conjuntos de regras prontos, escolhidos pelo nome (configuração do servidor).
+    /// "rapido" facilita a primeira descida; "exigente" pede mais e sobe mais cedo.
+    pub fn predefinida(nome: &str) -> Option<Self> {
+        match nome {
+            "padrao" => Some(Self::default()),
+            "rapido" => Some(Self {
+                pontos_para_descer: 50,
+                pontos_para_descer_alto: 75,
+                limite_pontuacao_alta: 2500,
+            }),
+            "exigente" => Some(Self {
+                pontos_para_descer: 100,
+                pontos_para_descer_alto: 120,
+                limite_pontuacao_alta: 1500,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Confere se os valores fazem sentido antes de aceitar a configuração de uma sala.
+    pub fn validar(&self) -> Result<(), String> {
+        if self.pontos_para_descer < 0 || self.pontos_para_descer_alto < 0 {
+            return Err("Os pontos para descer não podem ser negativos.".to_string());
+        }
+        if self.pontos_para_descer_alto < self.pontos_para_descer {
+            return Err(
+                "A descida alta não pode exigir menos pontos que a descida normal.".to_string(),
+            );
+        }
+        if self.limite_pontuacao_alta <= 0 {
+            return Err("O limite de pontuação alta deve ser positivo.".to_string());
+        }
+        Ok(())
+    }
+}
 
 pub fn validar_jogo(cartas: &[Carta]) -> bool {
     // Regra 1: Mínimo de 3 cartas
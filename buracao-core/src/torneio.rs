@@ -0,0 +1,141 @@
+use crate::lobby::ConfigRelogio;
+use crate::regras::ConfigRegras;
+use serde::{Deserialize, Serialize};
+
+// --- TIPOS DOS TORNEIOS (API HTTP) ---
+// Compartilhados entre o servidor (que organiza as rodadas) e a página de administração.
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "tipo")]
+pub enum FormatoTorneio {
+    /// Todas as duplas jogam todas as rodadas; cada rodada junta duplas com campanha
+    /// parecida, sem repetir confronto enquanto der
+    Suico { rodadas: u32 },
+    /// Quem perde sai; a última dupla de pé é a campeã
+    EliminatoriaSimples,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusTorneio {
+    /// Aceitando inscrições
+    Inscricoes,
+    /// Rodada em andamento (alguma mesa ainda sem resultado)
+    EmAndamento,
+    /// Rodada fechada, esperando o organizador gerar a próxima
+    EntreRodadas,
+    Encerrado,
+}
+
+/// Corpo do pedido de criação de torneio.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NovoTorneio {
+    pub nome: String,
+    pub formato: FormatoTorneio,
+    /// None = regras padrão do servidor
+    #[serde(default)]
+    pub regras: Option<ConfigRegras>,
+    #[serde(default)]
+    pub relogio: Option<ConfigRelogio>,
+}
+
+/// Inscrição de uma dupla. Os nomes são os apelidos com que cada jogador vai entrar
+/// na mesa: o assento fica reservado para quem entrar com aquele nome e a chave de
+/// assento que a inscrição gera para ele.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NovaDupla {
+    pub jogadores: [String; 2],
+    /// Nome da dupla (padrão: "Fulano & Beltrano")
+    #[serde(default)]
+    pub nome: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DuplaTorneio {
+    pub id: u32,
+    pub nome: String,
+    pub jogadores: [String; 2],
+    /// Chave de assento de cada jogador (vai no campo de senha do login). Só aparece
+    /// nas respostas ao organizador, que repassa a cada um a sua.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chaves: Option<[String; 2]>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResultadoMesa {
+    pub pontos_a: i32,
+    pub pontos_b: i32,
+}
+
+/// Confronto de uma rodada. A dupla A senta nos assentos 0 e 2, a dupla B no 1 e 3.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MesaTorneio {
+    /// Código da sala criada para o confronto (vazio na folga)
+    pub sala: String,
+    pub dupla_a: u32,
+    /// None = folga: a dupla A avança/pontua sem jogar
+    pub dupla_b: Option<u32>,
+    pub resultado: Option<ResultadoMesa>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RodadaTorneio {
+    pub numero: u32,
+    pub mesas: Vec<MesaTorneio>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LinhaClassificacao {
+    pub dupla: u32,
+    pub nome: String,
+    pub jogos: u32,
+    pub vitorias: u32,
+    pub empates: u32,
+    pub derrotas: u32,
+    /// Vitória vale 2, empate 1 (folga conta como vitória)
+    pub pontos_torneio: u32,
+    pub pontos_pro: i32,
+    pub pontos_contra: i32,
+    /// Na eliminatória: a dupla já caiu
+    pub eliminada: bool,
+}
+
+impl LinhaClassificacao {
+    pub fn saldo(&self) -> i32 {
+        self.pontos_pro - self.pontos_contra
+    }
+}
+
+/// Situação completa de um torneio (GET /api/torneios/{id}).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResumoTorneio {
+    pub id: u32,
+    pub nome: String,
+    pub formato: FormatoTorneio,
+    pub status: StatusTorneio,
+    pub duplas: Vec<DuplaTorneio>,
+    pub rodadas: Vec<RodadaTorneio>,
+    pub classificacao: Vec<LinhaClassificacao>,
+}
+
+impl ResumoTorneio {
+    pub fn dupla(&self, id: u32) -> Option<&DuplaTorneio> {
+        self.duplas.iter().find(|d| d.id == id)
+    }
+}
+
+/// Resposta da criação: a chave de organizador só aparece aqui.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TorneioCriado {
+    pub torneio: ResumoTorneio,
+    /// Vai no cabeçalho `x-chave-torneio` das rotas de organização
+    pub chave_admin: String,
+}
+
+/// Resultado lançado pelo organizador (desempate, mesa jogada fora do sistema, correção).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct ResultadoManual {
+    pub rodada: u32,
+    pub mesa: usize,
+    pub pontos_a: i32,
+    pub pontos_b: i32,
+}
@@ -0,0 +1,175 @@
+use buracao_core::registro::Lance;
+use buracao_core::{AcaoJogador, Carta, EstadoJogo, Naipe, TipoRecusa, Valor, Verso};
+
+fn carta(valor: Valor, naipe: Naipe) -> Carta {
+    Carta {
+        naipe,
+        valor,
+        verso: Verso::Red,
+    }
+}
+
+/// Vez do jogador 0 com o monte vazio; os outros jogadores seguram uma carta qualquer.
+fn mesa_sem_monte(mao: Vec<Carta>, lixo: Vec<Carta>) -> EstadoJogo {
+    let mut estado = EstadoJogo::new();
+    estado.baralho.cartas.clear();
+    estado.maos = vec![mao, Vec::new(), Vec::new(), Vec::new()];
+    for outro in 1..4 {
+        estado.maos[outro].push(carta(Valor::Quatro, Naipe::Paus));
+    }
+    estado.lixo = lixo;
+    estado
+}
+
+fn sequencia_de_copas(valores: &[Valor]) -> Vec<Carta> {
+    valores.iter().map(|v| carta(*v, Naipe::Copas)).collect()
+}
+
+#[test]
+fn monte_vazio_pega_o_lixo_que_encaixa_num_jogo_do_time() {
+    let mut estado = mesa_sem_monte(
+        vec![
+            carta(Valor::Rei, Naipe::Espadas),
+            carta(Valor::Nove, Naipe::Paus),
+        ],
+        vec![
+            carta(Valor::Dama, Naipe::Ouros),
+            carta(Valor::Oito, Naipe::Copas),
+        ],
+    );
+    estado.jogos_time_a.insert(
+        0,
+        sequencia_de_copas(&[Valor::Cinco, Valor::Seis, Valor::Sete]),
+    );
+
+    let resumo = estado.jogada_automatica(0).expect("jogada automática");
+
+    assert!(resumo.contains("descartou"), "{}", resumo);
+    assert_eq!(estado.jogos_time_a[&0].len(), 4);
+    // Regra 20: pegou o lixo na última chance, então a mão acaba com o descarte
+    assert!(estado.partida_encerrada);
+    assert_eq!(estado.bateu, None);
+    assert!(matches!(
+        estado.lances.last(),
+        Some(Lance::Acao {
+            acao: AcaoJogador::Descartar { .. },
+            ..
+        })
+    ));
+}
+
+#[test]
+fn monte_vazio_sem_jogo_para_o_lixo_encerra_a_mao() {
+    let mut estado = mesa_sem_monte(
+        vec![
+            carta(Valor::Rei, Naipe::Espadas),
+            carta(Valor::Nove, Naipe::Paus),
+        ],
+        vec![carta(Valor::Dama, Naipe::Ouros)],
+    );
+
+    estado.jogada_automatica(0).expect("jogada automática");
+
+    assert!(estado.partida_encerrada);
+    assert_eq!(estado.maos[0].len(), 2);
+    assert_eq!(estado.lixo.len(), 1);
+    assert!(matches!(
+        estado.lances.last(),
+        Some(Lance::Esgotamento { jogador: 0 })
+    ));
+}
+
+#[test]
+fn monte_e_lixo_vazios_encerram_a_mao_uma_vez_so() {
+    let mut estado = mesa_sem_monte(vec![carta(Valor::Rei, Naipe::Espadas)], Vec::new());
+    estado.jogos_time_a.insert(
+        0,
+        sequencia_de_copas(&[Valor::Cinco, Valor::Seis, Valor::Sete]),
+    );
+
+    estado.jogada_automatica(0).expect("jogada automática");
+
+    assert!(estado.partida_encerrada);
+    // Os pontos da mesa entram uma vez só
+    assert_eq!(estado.pontuacao_a, estado.composicao_placar(true).total());
+    assert_eq!(
+        estado
+            .lances
+            .iter()
+            .filter(|l| matches!(l, Lance::Esgotamento { .. }))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn ultima_carta_sem_real_nao_e_descartada() {
+    let mut estado = mesa_sem_monte(vec![carta(Valor::Rei, Naipe::Espadas)], Vec::new());
+    estado.comprou_nesta_rodada = true;
+
+    assert_eq!(estado.escolher_descarte_seguro(0), None);
+    assert!(estado.jogada_automatica(0).is_err());
+    assert_eq!(estado.maos[0].len(), 1);
+    assert!(estado.lixo.is_empty());
+    assert_eq!(estado.turno_atual, 0);
+}
+
+#[test]
+fn com_lixo_pego_na_rodada_a_ultima_carta_fica_na_mao() {
+    let mut estado = mesa_sem_monte(vec![carta(Valor::Rei, Naipe::Espadas)], Vec::new());
+    estado.jogos_time_a.insert(
+        0,
+        sequencia_de_copas(&[
+            Valor::Cinco,
+            Valor::Seis,
+            Valor::Sete,
+            Valor::Oito,
+            Valor::Nove,
+            Valor::Dez,
+            Valor::Valete,
+        ]),
+    );
+    estado.comprou_nesta_rodada = true;
+    estado.pegou_lixo_nesta_rodada = true;
+
+    // Tem Real, mas pegou o lixo: não pode bater nesta rodada (Regra 24)
+    assert_eq!(estado.escolher_descarte_seguro(0), None);
+    assert!(estado.jogada_automatica(0).is_err());
+    assert_eq!(estado.maos[0].len(), 1);
+}
+
+#[test]
+fn descarte_recusado_por_falta_de_real_nao_tira_a_carta_da_mao() {
+    let rei = carta(Valor::Rei, Naipe::Espadas);
+    let mut estado = mesa_sem_monte(vec![rei.clone()], Vec::new());
+    estado.comprou_nesta_rodada = true;
+
+    let resultado = estado.realizar_acao(0, AcaoJogador::Descartar { carta: rei.clone() });
+
+    assert_eq!(resultado.unwrap_err().tipo, TipoRecusa::Batida);
+    assert_eq!(estado.maos[0], vec![rei]);
+    assert!(estado.lixo.is_empty());
+}
+
+#[test]
+fn monte_vazio_com_uma_carta_na_mao_e_no_lixo_encerra_a_mao() {
+    let mut estado = mesa_sem_monte(
+        vec![carta(Valor::Rei, Naipe::Espadas)],
+        vec![carta(Valor::Dama, Naipe::Ouros)],
+    );
+    estado.jogos_time_a.insert(
+        0,
+        sequencia_de_copas(&[Valor::Cinco, Valor::Seis, Valor::Sete]),
+    );
+
+    // Pegar o lixo deixaria uma carta só: não pega, e a mão acaba
+    estado.jogada_automatica(0).expect("jogada automática");
+
+    assert!(estado.partida_encerrada);
+    assert_eq!(estado.maos[0].len(), 1);
+    assert_eq!(estado.lixo.len(), 1);
+    assert!(matches!(
+        estado.lances.last(),
+        Some(Lance::Esgotamento { jogador: 0 })
+    ));
+}
@@ -0,0 +1,33 @@
+use buracao_core::eventos::{self, EventoJogo};
+use buracao_core::registro::MaoRegistrada;
+use buracao_core::EstadoJogo;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// O observador é do processo: este arquivo tem um teste só para a contagem não
+// misturar eventos de outros testes.
+
+static EVENTOS: AtomicUsize = AtomicUsize::new(0);
+
+fn contar(_: &EventoJogo) {
+    EVENTOS.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn mao_refeita_do_registro_nao_repete_eventos() {
+    assert!(eventos::definir_observador(contar));
+
+    let mut estado = EstadoJogo::new();
+    estado.dar_cartas_com_semente(3);
+    while !estado.partida_encerrada {
+        let vez = estado.turno_atual;
+        estado.jogada_automatica(vez).expect("jogada automática");
+    }
+    assert_eq!(EVENTOS.load(Ordering::SeqCst), 1);
+
+    let estados = MaoRegistrada::de_estado(&estado)
+        .reproduzir(&estado.regras)
+        .expect("a mão se refaz");
+    assert!(estados.last().unwrap().partida_encerrada);
+    assert!(estado.verificar_invariantes().is_empty());
+    assert_eq!(EVENTOS.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,46 @@
+use buracao_core::{AcaoJogador, EstadoJogo};
+
+#[test]
+fn mao_normal_nao_tem_problemas_em_nenhum_momento() {
+    for semente in [3, 11, 42] {
+        let mut estado = EstadoJogo::new();
+        estado.dar_cartas_com_semente(semente);
+        assert_eq!(estado.verificar_invariantes(), Vec::<String>::new());
+
+        while !estado.partida_encerrada {
+            let vez = estado.turno_atual;
+            estado.jogada_automatica(vez).unwrap();
+            let problemas = estado.verificar_invariantes();
+            assert!(
+                problemas.is_empty(),
+                "semente {}, rodada {}: {:?}",
+                semente,
+                estado.rodada,
+                problemas
+            );
+        }
+    }
+}
+
+#[test]
+fn recusas_comuns_nao_tiram_a_mesa_do_lugar() {
+    let mut estado = EstadoJogo::new();
+    estado.dar_cartas_com_semente(5);
+
+    // Fora da vez, descarte antes de comprar, compra repetida
+    let carta = estado.maos[0][0].clone();
+    assert!(estado
+        .realizar_acao(1, AcaoJogador::ComprarBaralho)
+        .is_err());
+    assert!(estado
+        .realizar_acao(0, AcaoJogador::Descartar { carta })
+        .is_err());
+    estado
+        .realizar_acao(0, AcaoJogador::ComprarBaralho)
+        .unwrap();
+    assert!(estado
+        .realizar_acao(0, AcaoJogador::ComprarBaralho)
+        .is_err());
+
+    assert_eq!(estado.verificar_invariantes(), Vec::<String>::new());
+}
@@ -0,0 +1,71 @@
+use buracao_core::estado::ComposicaoPlacar;
+use buracao_core::{AcaoJogador, Carta, EstadoJogo, Naipe, Valor, Verso};
+
+fn carta(valor: Valor, naipe: Naipe) -> Carta {
+    Carta {
+        naipe,
+        valor,
+        verso: Verso::Red,
+    }
+}
+
+fn jogo(valores: &[Valor], naipe: Naipe) -> Vec<Carta> {
+    valores.iter().map(|v| carta(*v, naipe)).collect()
+}
+
+#[test]
+fn batida_soma_no_placar_a_composicao_de_cada_time() {
+    let rei = carta(Valor::Rei, Naipe::Espadas);
+    let mut estado = EstadoJogo::new();
+    let quatro = carta(Valor::Quatro, Naipe::Paus);
+    estado.maos = vec![
+        vec![rei.clone()],
+        vec![quatro.clone()],
+        vec![quatro.clone(), quatro.clone()],
+        vec![quatro],
+    ];
+    estado.jogos_time_a.insert(
+        0,
+        jogo(
+            &[
+                Valor::Cinco,
+                Valor::Seis,
+                Valor::Sete,
+                Valor::Oito,
+                Valor::Nove,
+                Valor::Dez,
+                Valor::Valete,
+            ],
+            Naipe::Copas,
+        ),
+    );
+    estado.jogos_time_b.insert(
+        1,
+        jogo(&[Valor::Cinco, Valor::Seis, Valor::Sete], Naipe::Paus),
+    );
+    estado
+        .tres_vermelhos_time_a
+        .push(carta(Valor::Tres, Naipe::Ouros));
+    estado.comprou_nesta_rodada = true;
+
+    estado
+        .realizar_acao(0, AcaoJogador::Descartar { carta: rei })
+        .expect("bate com a Real");
+
+    assert_eq!(estado.bateu, Some(0));
+    let time_a = estado.composicao_placar(true);
+    assert_eq!(
+        time_a,
+        ComposicaoPlacar {
+            cartas_mesa: 70,
+            canastras_limpas: 300,
+            canastras_sujas: 0,
+            tres_vermelhos: 100,
+            batida: 100,
+            cartas_na_mao: -20,
+        }
+    );
+    assert_eq!(estado.pontuacao_a, time_a.total());
+    assert_eq!(estado.pontuacao_b, estado.composicao_placar(false).total());
+    assert_eq!(estado.pontuacao_b, 30 - 20);
+}
@@ -0,0 +1,87 @@
+use buracao_core::registro::{JogadorRegistro, Lance, RegistroPartida};
+use buracao_core::{AcaoJogador, EstadoJogo, TipoRecusa};
+
+/// Mão distribuída com a semente e jogada só pelo relógio (compra e descarta) até o
+/// monte acabar.
+fn mao_ate_o_monte_acabar(semente: u64) -> EstadoJogo {
+    let mut estado = EstadoJogo::new();
+    estado.dar_cartas_com_semente(semente);
+    while !estado.partida_encerrada {
+        let vez = estado.turno_atual;
+        estado
+            .jogada_automatica(vez)
+            .unwrap_or_else(|e| panic!("semente {}: {}", semente, e));
+    }
+    estado
+}
+
+fn jogadores() -> Vec<JogadorRegistro> {
+    ["Ana", "Bia", "Caio", "Duda"]
+        .iter()
+        .enumerate()
+        .map(|(assento, nome)| JogadorRegistro {
+            assento: assento as u32,
+            nome: nome.to_string(),
+            id_perfil: None,
+        })
+        .collect()
+}
+
+#[test]
+fn mao_que_esgota_o_monte_exporta_um_registro_valido() {
+    let mut viu_monte_vazio = false;
+    for semente in 0..20 {
+        let estado = mao_ate_o_monte_acabar(semente);
+        assert_eq!(estado.baralho.restantes(), 0, "semente {}", semente);
+        assert_eq!(estado.bateu, None);
+        viu_monte_vazio |= estado
+            .lances
+            .iter()
+            .any(|l| matches!(l, Lance::MonteVazio { .. }));
+
+        let registro = RegistroPartida::novo("Mesa".to_string(), 0, &estado, jogadores());
+        registro
+            .validar()
+            .unwrap_or_else(|e| panic!("semente {}: {}", semente, e));
+    }
+    // Ao menos uma das mãos chegou ao fim com o lixo ainda disponível
+    assert!(viu_monte_vazio);
+}
+
+#[test]
+fn compra_com_o_monte_vazio_vira_lance() {
+    let mut estado = EstadoJogo::new();
+    estado.dar_cartas_com_semente(7);
+    estado.baralho.cartas.clear();
+    let livre = estado.maos[3]
+        .iter()
+        .position(|c| !c.trava_o_lixo())
+        .unwrap();
+    estado.lixo = vec![estado.maos[3].remove(livre)];
+    let versao = estado.versao;
+
+    // Lixo disponível: recusa, mas a mão agora acaba no descarte
+    let recusa = estado
+        .realizar_acao(0, AcaoJogador::ComprarBaralho)
+        .unwrap_err();
+    assert_eq!(recusa.tipo, TipoRecusa::MonteEsgotado);
+    assert!(estado.versao > versao);
+    assert!(!estado.partida_encerrada);
+    assert!(matches!(
+        estado.lances.last(),
+        Some(Lance::MonteVazio { jogador: 0 })
+    ));
+
+    // Lixo vazio: a compra encerra a mão
+    let mut estado = EstadoJogo::new();
+    estado.dar_cartas_com_semente(7);
+    estado.baralho.cartas.clear();
+    assert!(estado
+        .realizar_acao(0, AcaoJogador::ComprarBaralho)
+        .is_err());
+    assert!(estado.partida_encerrada);
+    assert!(matches!(
+        estado.lances.last(),
+        Some(Lance::Esgotamento { jogador: 0 })
+    ));
+}
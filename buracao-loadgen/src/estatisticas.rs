@@ -0,0 +1,72 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+// --- ESTATÍSTICAS DA CARGA ---
+// Contadores somados por todos os jogadores simulados. A latência é medida da ação
+// enviada até o `Delta` que ela gerou chegar a cada um dos quatro assentos da mesa.
+
+#[derive(Default)]
+pub struct Estatisticas {
+    latencias_us: Mutex<Vec<u64>>,
+    pub mesas_abertas: AtomicU64,
+    /// Criação de sala recusada pela API (limite de salas, por exemplo)
+    pub salas_recusadas: AtomicU64,
+    pub acoes_enviadas: AtomicU64,
+    pub acoes_recusadas: AtomicU64,
+    /// Buracos na `seq` dos envelopes: mensagens que o servidor mandou e não chegaram
+    pub mensagens_perdidas: AtomicU64,
+    /// `Delta` que não encaixou na versão local (o jogador pede `Resync`)
+    pub deltas_fora_de_ordem: AtomicU64,
+    /// Ação sem resposta (nem `Delta` nem `Erro`) dentro do prazo
+    pub acoes_sem_resposta: AtomicU64,
+    pub mensagens_rejeitadas: AtomicU64,
+    pub logins_recusados: AtomicU64,
+    pub conexoes_caidas: AtomicU64,
+    pub partidas_terminadas: AtomicU64,
+    /// Turno em que o jogador não achou lance aceito (só o relógio da sala destrava)
+    pub turnos_sem_lance: AtomicU64,
+}
+
+impl Estatisticas {
+    pub fn registrar_latencia(&self, latencia: Duration) {
+        self.latencias_us
+            .lock()
+            .unwrap()
+            .push(latencia.as_micros() as u64);
+    }
+
+    pub fn contar(contador: &AtomicU64) {
+        contador.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn ler(contador: &AtomicU64) -> u64 {
+        contador.load(Ordering::Relaxed)
+    }
+
+    /// Percentis pedidos (0 a 100) das latências medidas até agora, e quantas são.
+    pub fn percentis(&self, pedidos: &[f64]) -> (usize, Vec<Duration>) {
+        let mut amostras = self.latencias_us.lock().unwrap().clone();
+        amostras.sort_unstable();
+        let valores = pedidos
+            .iter()
+            .map(|p| {
+                if amostras.is_empty() {
+                    return Duration::ZERO;
+                }
+                // Método do posto mais próximo
+                let posto = ((p / 100.0) * amostras.len() as f64).ceil() as usize;
+                Duration::from_micros(amostras[posto.clamp(1, amostras.len()) - 1])
+            })
+            .collect();
+        (amostras.len(), valores)
+    }
+}
+
+pub fn megabytes(bytes: f64) -> String {
+    format!("{:.1} MB", bytes / (1024.0 * 1024.0))
+}
+
+pub fn milissegundos(duracao: Duration) -> String {
+    format!("{:.1} ms", duracao.as_secs_f64() * 1000.0)
+}
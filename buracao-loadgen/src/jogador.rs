@@ -0,0 +1,422 @@
+use crate::estatisticas::Estatisticas;
+use buracao_core::acoes::{
+    AcaoJogador, DadosLogin, DetalheJogo, Envelope, MsgCliente, MsgServidor, VERSAO_PROTOCOLO,
+    VisaoJogador, VotoFimDeJogo,
+};
+use buracao_core::baralho::Carta;
+use buracao_core::regras::validar_jogo;
+use futures::{SinkExt, StreamExt};
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+// --- JOGADOR SIMULADO ---
+// Entra na sala pelo código, como o navegador, e joga só lances legais pela visão que
+// recebe: na sua vez compra do monte, às vezes ajunta uma carta num jogo do próprio time
+// e descarta uma carta qualquer. Com o monte vazio, pega o lixo se o topo encaixa em algum
+// jogo. No fim da partida vota revanche, então a mesa joga até a carga acabar.
+//
+// Quando nada é aceito (monte vazio e topo do lixo sem uso, ou recusas demais), o jogador
+// espera o turno passar: numa sala com relógio o servidor joga por ele.
+
+/// Sem `Delta` nem `Erro` depois disso, a ação conta como sem resposta
+const PRAZO_RESPOSTA: Duration = Duration::from_secs(10);
+/// Recusas seguidas no mesmo turno antes de desistir
+const MAX_FALHAS_TURNO: u32 = 6;
+
+/// O que as quatro conexões de uma sala compartilham.
+pub struct Mesa {
+    pub codigo: String,
+    /// Última ação enviada na mesa: número de ordem e quando saiu
+    ultima_acao: Mutex<(u64, Option<Instant>)>,
+}
+
+impl Mesa {
+    pub fn new(codigo: String) -> Self {
+        Self {
+            codigo,
+            ultima_acao: Mutex::new((0, None)),
+        }
+    }
+
+    fn marcar_acao(&self) {
+        let mut ultima = self.ultima_acao.lock().unwrap();
+        *ultima = (ultima.0 + 1, Some(Instant::now()));
+    }
+
+    /// A ação foi recusada e não vai gerar `Delta`: o próximo não é dela.
+    fn descartar_acao(&self) {
+        self.ultima_acao.lock().unwrap().1 = None;
+    }
+
+    fn ultima_acao(&self) -> (u64, Option<Instant>) {
+        *self.ultima_acao.lock().unwrap()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Pendente {
+    Compra,
+    CompraLixo,
+    Ajuntar,
+    Descarte,
+}
+
+pub struct Jogador {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    mesa: Arc<Mesa>,
+    estatisticas: Arc<Estatisticas>,
+    ritmo: Duration,
+    rng: StdRng,
+
+    meu_id: Option<u32>,
+    visao: Option<VisaoJogador>,
+    ultimo_seq: Option<u64>,
+    /// Número da última ação da mesa cuja latência este jogador já mediu
+    medida: u64,
+
+    comprei: bool,
+    tentar_lixo: bool,
+    sem_ajuntar: bool,
+    falhas_no_turno: u32,
+    votar: bool,
+    travado: bool,
+    pendente: Option<(Pendente, Instant)>,
+    proxima: Option<Instant>,
+}
+
+impl Jogador {
+    /// Conecta e manda o login; `None` se o servidor nem atendeu.
+    pub async fn entrar(
+        endereco: &str,
+        mesa: Arc<Mesa>,
+        assento: usize,
+        estatisticas: Arc<Estatisticas>,
+        ritmo: Duration,
+    ) -> Option<Self> {
+        let url = format!("ws://{}/buraco", endereco);
+        let Ok((ws, _)) = tokio_tungstenite::connect_async(url).await else {
+            Estatisticas::contar(&estatisticas.conexoes_caidas);
+            return None;
+        };
+        let mut jogador = Self {
+            ws,
+            mesa,
+            estatisticas,
+            ritmo,
+            rng: StdRng::from_os_rng(),
+            meu_id: None,
+            visao: None,
+            ultimo_seq: None,
+            medida: 0,
+            comprei: false,
+            tentar_lixo: false,
+            sem_ajuntar: false,
+            falhas_no_turno: 0,
+            votar: false,
+            travado: false,
+            pendente: None,
+            proxima: None,
+        };
+        let login = DadosLogin {
+            versao: VERSAO_PROTOCOLO,
+            device_id: format!("carga-{}-{}", jogador.mesa.codigo, assento),
+            nome: format!("Robô {}", assento + 1),
+            sala: jogador.mesa.codigo.clone(),
+            senha: None,
+            token: None,
+            ultimo_seq: None,
+            espectador: None,
+            identidade: None,
+            token_anfitriao: None,
+        };
+        jogador.enviar(MsgCliente::Login(login)).await;
+        Some(jogador)
+    }
+
+    /// Joga até `parar` mudar ou a conexão cair.
+    pub async fn jogar(mut self, mut parar: watch::Receiver<bool>) {
+        loop {
+            let sem_resposta = self.pendente.map(|(_, desde)| desde + PRAZO_RESPOSTA);
+            tokio::select! {
+                _ = parar.changed() => break,
+                _ = tokio::time::sleep_until(self.proxima.unwrap_or_else(Instant::now)),
+                    if self.proxima.is_some() => {
+                    self.proxima = None;
+                    self.agir().await;
+                }
+                _ = tokio::time::sleep_until(sem_resposta.unwrap_or_else(Instant::now)),
+                    if sem_resposta.is_some() => {
+                    Estatisticas::contar(&self.estatisticas.acoes_sem_resposta);
+                    self.mesa.descartar_acao();
+                    self.pendente = None;
+                    self.agendar();
+                }
+                frame = self.ws.next() => match frame {
+                    Some(Ok(Message::Text(texto))) => {
+                        if !self.tratar(&texto).await {
+                            return;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => {
+                        if !*parar.borrow() {
+                            Estatisticas::contar(&self.estatisticas.conexoes_caidas);
+                        }
+                        return;
+                    }
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+        let _ = self.ws.close(None).await;
+    }
+
+    async fn enviar(&mut self, msg: MsgCliente) {
+        let json = serde_json::to_string(&msg).expect("mensagem do cliente serializa");
+        // Falha de envio aparece como conexão caída na próxima leitura
+        let _ = self.ws.send(Message::text(json)).await;
+    }
+
+    /// Trata uma mensagem do servidor; `false` quando não há mais o que fazer na conexão.
+    async fn tratar(&mut self, texto: &str) -> bool {
+        let Ok(envelope) = serde_json::from_str::<Envelope>(texto) else {
+            return true;
+        };
+        if let Some(seq) = envelope.seq {
+            if let Some(anterior) = self.ultimo_seq
+                && seq > anterior + 1
+            {
+                self.estatisticas
+                    .mensagens_perdidas
+                    .fetch_add(seq - anterior - 1, std::sync::atomic::Ordering::Relaxed);
+            }
+            self.ultimo_seq = Some(seq);
+        }
+
+        match envelope.msg {
+            MsgServidor::BoasVindas { id_jogador, .. } => self.meu_id = Some(id_jogador),
+            MsgServidor::LoginRecusado(_) => {
+                Estatisticas::contar(&self.estatisticas.logins_recusados);
+                return false;
+            }
+            MsgServidor::Expulso { .. } => return false,
+            MsgServidor::Estado(visao) => {
+                self.visao = Some(visao);
+                self.atualizar_turno();
+                self.agendar();
+            }
+            MsgServidor::Delta(delta) => {
+                let Some(visao) = self.visao.as_mut() else {
+                    return true;
+                };
+                if visao.aplicar_delta(&delta).is_err() {
+                    Estatisticas::contar(&self.estatisticas.deltas_fora_de_ordem);
+                    self.enviar(MsgCliente::Resync).await;
+                    return true;
+                }
+                let (numero, enviada) = self.mesa.ultima_acao();
+                if numero > self.medida
+                    && let Some(enviada) = enviada
+                {
+                    self.estatisticas.registrar_latencia(enviada.elapsed());
+                    self.medida = numero;
+                }
+                if let Some((acao, _)) = self.pendente.take()
+                    && matches!(acao, Pendente::Compra | Pendente::CompraLixo)
+                {
+                    self.comprei = true;
+                }
+                self.atualizar_turno();
+                self.agendar();
+            }
+            MsgServidor::Erro(_) => {
+                if let Some((acao, _)) = self.pendente.take() {
+                    self.mesa.descartar_acao();
+                    self.recusada(acao);
+                }
+                self.agendar();
+            }
+            MsgServidor::MensagemRejeitada(_) => {
+                // Não chegou ao jogo (ritmo, tamanho...): tenta de novo no próximo passo
+                Estatisticas::contar(&self.estatisticas.mensagens_rejeitadas);
+                if self.pendente.take().is_some() {
+                    self.mesa.descartar_acao();
+                }
+                self.agendar();
+            }
+            MsgServidor::FimDeJogo { .. } => {
+                if self.meu_id == Some(0) {
+                    Estatisticas::contar(&self.estatisticas.partidas_terminadas);
+                }
+                self.pendente = None;
+                self.novo_turno();
+                self.votar = true;
+                self.agendar();
+            }
+            MsgServidor::NovaPartida { .. } => {
+                self.votar = false;
+                self.pendente = None;
+                self.novo_turno();
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn recusada(&mut self, acao: Pendente) {
+        Estatisticas::contar(&self.estatisticas.acoes_recusadas);
+        self.falhas_no_turno += 1;
+        match acao {
+            // Monte vazio (ou lixo que não pode ser pego): tenta a outra pilha uma vez
+            Pendente::Compra => self.tentar_lixo = true,
+            Pendente::CompraLixo => self.falhas_no_turno = MAX_FALHAS_TURNO,
+            Pendente::Ajuntar => self.sem_ajuntar = true,
+            Pendente::Descarte => {}
+        }
+        if self.falhas_no_turno >= MAX_FALHAS_TURNO {
+            self.desistir_do_turno();
+        }
+    }
+
+    fn desistir_do_turno(&mut self) {
+        if !self.travado {
+            Estatisticas::contar(&self.estatisticas.turnos_sem_lance);
+            self.travado = true;
+        }
+    }
+
+    fn minha_vez(&self) -> bool {
+        match (&self.visao, self.meu_id) {
+            (Some(visao), Some(id)) => visao.posso_jogar && visao.turno_atual == id,
+            _ => false,
+        }
+    }
+
+    fn novo_turno(&mut self) {
+        self.comprei = false;
+        self.tentar_lixo = false;
+        self.sem_ajuntar = false;
+        self.falhas_no_turno = 0;
+        self.travado = false;
+    }
+
+    /// Quando a vez sai deste jogador, o próximo turno dele começa do zero.
+    fn atualizar_turno(&mut self) {
+        if !self.minha_vez() {
+            self.novo_turno();
+        }
+    }
+
+    /// Marca o próximo lance, com o ritmo variando entre metade e uma vez e meia.
+    fn agendar(&mut self) {
+        if self.proxima.is_some() || self.pendente.is_some() || self.travado {
+            return;
+        }
+        if self.votar || self.minha_vez() {
+            let fator = self.rng.random_range(0.5..1.5);
+            self.proxima = Some(Instant::now() + self.ritmo.mul_f64(fator));
+        }
+    }
+
+    async fn agir(&mut self) {
+        if self.votar {
+            self.votar = false;
+            self.enviar(MsgCliente::VotoFimDeJogo(VotoFimDeJogo::Revanche))
+                .await;
+            return;
+        }
+        if self.pendente.is_some() || !self.minha_vez() {
+            return;
+        }
+        let Some((tipo, acao)) = self.escolher_lance() else {
+            self.desistir_do_turno();
+            return;
+        };
+        self.mesa.marcar_acao();
+        self.pendente = Some((tipo, Instant::now()));
+        Estatisticas::contar(&self.estatisticas.acoes_enviadas);
+        self.enviar(MsgCliente::Acao(acao)).await;
+    }
+
+    fn escolher_lance(&mut self) -> Option<(Pendente, AcaoJogador)> {
+        let visao = self.visao.as_ref()?;
+        let meu_id = self.meu_id?;
+        let meus_jogos = if meu_id.is_multiple_of(2) {
+            &visao.mesa_time_a
+        } else {
+            &visao.mesa_time_b
+        };
+
+        if !self.comprei {
+            if self.tentar_lixo || visao.qtd_monte == 0 {
+                return pegar_lixo(visao, meus_jogos);
+            }
+            return Some((Pendente::Compra, AcaoJogador::ComprarBaralho));
+        }
+
+        // Ajuntar deixando pelo menos duas cartas: bater exige canastra e isso a visão
+        // não garante
+        if !self.sem_ajuntar && visao.minha_mao.len() > 2 && self.rng.random_bool(0.5) {
+            let encaixe = meus_jogos.iter().find_map(|jogo| {
+                visao.minha_mao.iter().find_map(|carta| {
+                    let mut teste = jogo.cartas.clone();
+                    teste.push(carta.clone());
+                    validar_jogo(&teste).then(|| (jogo.id, carta.clone()))
+                })
+            });
+            if let Some((indice_jogo, carta)) = encaixe {
+                return Some((
+                    Pendente::Ajuntar,
+                    AcaoJogador::Ajuntar {
+                        indice_jogo,
+                        cartas: vec![carta],
+                    },
+                ));
+            }
+        }
+
+        let carta = visao.minha_mao.choose(&mut self.rng)?.clone();
+        Some((Pendente::Descarte, AcaoJogador::Descartar { carta }))
+    }
+}
+
+/// Pega o lixo usando o topo: num jogo do time ou num jogo novo com duas cartas da mão.
+/// A primeira descida ainda pode ser recusada pelos pontos.
+fn pegar_lixo(visao: &VisaoJogador, meus_jogos: &[DetalheJogo]) -> Option<(Pendente, AcaoJogador)> {
+    let topo = visao.lixo.as_ref()?;
+    let com_topo = |cartas: &[Carta]| {
+        let mut teste = cartas.to_vec();
+        teste.push(topo.clone());
+        validar_jogo(&teste)
+    };
+
+    if let Some(jogo) = meus_jogos.iter().find(|jogo| com_topo(&jogo.cartas)) {
+        return Some((
+            Pendente::CompraLixo,
+            AcaoJogador::ComprarLixo {
+                novos_jogos: Vec::new(),
+                cartas_em_jogos_existentes: vec![(jogo.id, Vec::new())],
+            },
+        ));
+    }
+    let mao = &visao.minha_mao;
+    (0..mao.len())
+        .flat_map(|i| (i + 1..mao.len()).map(move |j| [mao[i].clone(), mao[j].clone()]))
+        .find(|par| com_topo(par))
+        .map(|par| {
+            (
+                Pendente::CompraLixo,
+                AcaoJogador::ComprarLixo {
+                    novos_jogos: vec![par.to_vec()],
+                    cartas_em_jogos_existentes: Vec::new(),
+                },
+            )
+        })
+}
@@ -0,0 +1,333 @@
+mod estatisticas;
+mod jogador;
+mod servidor;
+
+use estatisticas::{Estatisticas, megabytes, milissegundos};
+use jogador::{Jogador, Mesa};
+use servidor::{criar_sala, ler_metricas};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+// --- GERADOR DE CARGA ---
+// Abre muitas salas no servidor, cada uma com quatro jogadores simulados por WebSocket,
+// e mede quanto a mesa demora para ver o resultado de cada lance. Serve para saber
+// quantas mesas simultâneas uma máquina aguenta antes de um torneio.
+//
+// As salas são criadas pela API com relógio de turno: quando o monte acaba e ninguém tem
+// lance, o servidor joga pela mesa e a partida termina. O servidor precisa aceitar as
+// salas (`--max-salas`); com `--banco :memory:` os perfis dos robôs não ficam no banco.
+
+const AJUDA: &str = "\
+Uso: buracao-loadgen [--opcao VALOR ...]
+
+  --endereco HOST:PORTA   servidor a testar (padrão 127.0.0.1:8080)
+  --salas N               salas simultâneas, quatro jogadores cada (padrão 100)
+  --ritmo-ms MS           pausa média antes de cada lance (padrão 500)
+  --duracao-s S           quanto tempo jogar depois de abrir a última sala (padrão 60)
+  --abertura-ms MS        intervalo entre abrir uma sala e a próxima (padrão 20)
+  --relogio-s S           segundos por turno das salas, 10 a 600 (padrão 10); com 0
+                          as salas abrem pelo código, sem relógio, e a mesa que ficar
+                          sem lance para de jogar
+  --prefixo TEXTO         início do nome das salas (padrão CARGA-<pid>)
+";
+
+/// A cada quanto tempo sai uma linha de progresso
+const INTERVALO_PROGRESSO: Duration = Duration::from_secs(10);
+const PERCENTIS: [f64; 5] = [50.0, 90.0, 99.0, 99.9, 100.0];
+const MEMORIA: &str = "buracao_memoria_residente_bytes";
+
+struct Opcoes {
+    endereco: String,
+    salas: usize,
+    ritmo: Duration,
+    duracao: Duration,
+    abertura: Duration,
+    /// Segundos por turno (0 = sem relógio)
+    relogio: u32,
+    prefixo: String,
+}
+
+/// Flags no mesmo formato do servidor: `--opcao valor` ou `--opcao=valor`.
+fn ler_opcoes(args: &[String]) -> Result<Opcoes, String> {
+    let mut opcoes = Opcoes {
+        endereco: "127.0.0.1:8080".to_string(),
+        salas: 100,
+        ritmo: Duration::from_millis(500),
+        duracao: Duration::from_secs(60),
+        abertura: Duration::from_millis(20),
+        relogio: 10,
+        prefixo: format!("CARGA-{}", std::process::id()),
+    };
+    let numero = |nome: &str, valor: &str| -> Result<u64, String> {
+        valor
+            .parse()
+            .map_err(|_| format!("--{}: '{}' não é um número", nome, valor))
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(flag) = arg.strip_prefix("--") else {
+            return Err(format!("argumento inesperado '{}' (veja --ajuda)", arg));
+        };
+        let (nome, valor) = match flag.split_once('=') {
+            Some((nome, valor)) => (nome, valor.to_string()),
+            None => {
+                let valor = args
+                    .next()
+                    .ok_or_else(|| format!("--{}: falta o valor", flag))?;
+                (flag, valor.clone())
+            }
+        };
+        match nome {
+            "endereco" => opcoes.endereco = valor,
+            "salas" => opcoes.salas = numero(nome, &valor)? as usize,
+            "ritmo-ms" => opcoes.ritmo = Duration::from_millis(numero(nome, &valor)?),
+            "duracao-s" => opcoes.duracao = Duration::from_secs(numero(nome, &valor)?),
+            "abertura-ms" => opcoes.abertura = Duration::from_millis(numero(nome, &valor)?),
+            "relogio-s" => opcoes.relogio = numero(nome, &valor)? as u32,
+            "prefixo" => opcoes.prefixo = valor,
+            _ => return Err(format!("--{}: opção desconhecida (veja --ajuda)", nome)),
+        }
+    }
+    if opcoes.salas == 0 {
+        return Err("--salas: precisa de pelo menos uma sala".to_string());
+    }
+    if opcoes.relogio != 0 && !(10..=600).contains(&opcoes.relogio) {
+        return Err("--relogio-s: de 10 a 600 segundos, ou 0 para salas sem relógio".to_string());
+    }
+    Ok(opcoes)
+}
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args
+        .iter()
+        .any(|a| a == "--ajuda" || a == "--help" || a == "-h")
+    {
+        print!("{}", AJUDA);
+        return;
+    }
+    let opcoes = match ler_opcoes(&args) {
+        Ok(opcoes) => opcoes,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Sem /metrics não há memória para comparar, mas a carga roda do mesmo jeito
+    let antes = match ler_metricas(&opcoes.endereco).await {
+        Ok(metricas) => metricas,
+        Err(e) => {
+            eprintln!("⚠️  {} (sem medição de memória)", e);
+            HashMap::new()
+        }
+    };
+    let memoria_inicial = antes.get(MEMORIA).copied();
+    let mut memoria_pico = memoria_inicial;
+
+    println!(
+        "{} salas em {} (ritmo {} ms, {} s de jogo, {})",
+        opcoes.salas,
+        opcoes.endereco,
+        opcoes.ritmo.as_millis(),
+        opcoes.duracao.as_secs(),
+        match opcoes.relogio {
+            0 => "sem relógio".to_string(),
+            s => format!("{} s por turno", s),
+        }
+    );
+    let estatisticas = Arc::new(Estatisticas::default());
+    let (parar, parado) = watch::channel(false);
+    let inicio = Instant::now();
+
+    // As salas abrem aos poucos; o progresso já sai durante a abertura
+    let abertura = {
+        let estatisticas = estatisticas.clone();
+        let endereco = opcoes.endereco.clone();
+        let (prefixo, salas, ritmo, intervalo, relogio) = (
+            opcoes.prefixo.clone(),
+            opcoes.salas,
+            opcoes.ritmo,
+            opcoes.abertura,
+            opcoes.relogio,
+        );
+        tokio::spawn(async move {
+            let mut jogadores = Vec::new();
+            for n in 0..salas {
+                let nome = format!("{}-{}", prefixo, n);
+                // Sem relógio, o primeiro login abre a sala com o próprio nome como código
+                let codigo = if relogio == 0 {
+                    nome
+                } else {
+                    match criar_sala(&endereco, &nome, relogio).await {
+                        Ok(codigo) => codigo,
+                        Err(e) => {
+                            eprintln!("⚠️  sala {} não abriu: {}", nome, e);
+                            Estatisticas::contar(&estatisticas.salas_recusadas);
+                            tokio::time::sleep(intervalo).await;
+                            continue;
+                        }
+                    }
+                };
+                let mesa = Arc::new(Mesa::new(codigo));
+                for assento in 0..4 {
+                    let (mesa, estatisticas, endereco, parado) = (
+                        mesa.clone(),
+                        estatisticas.clone(),
+                        endereco.clone(),
+                        parado.clone(),
+                    );
+                    jogadores.push(tokio::spawn(async move {
+                        if let Some(jogador) =
+                            Jogador::entrar(&endereco, mesa, assento, estatisticas, ritmo).await
+                        {
+                            jogador.jogar(parado).await;
+                        }
+                    }));
+                }
+                Estatisticas::contar(&estatisticas.mesas_abertas);
+                tokio::time::sleep(intervalo).await;
+            }
+            jogadores
+        })
+    };
+
+    let fim = inicio + opcoes.abertura * opcoes.salas as u32 + opcoes.duracao;
+    let mut progresso = tokio::time::interval_at(inicio + INTERVALO_PROGRESSO, INTERVALO_PROGRESSO);
+    let mut acoes_anteriores = 0;
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep_until(fim) => break,
+            _ = tokio::signal::ctrl_c() => {
+                println!("interrompido, fechando as mesas");
+                break;
+            }
+            _ = progresso.tick() => {
+                let memoria = ler_metricas(&opcoes.endereco).await.ok().and_then(|m| m.get(MEMORIA).copied());
+                if let Some(m) = memoria {
+                    memoria_pico = Some(memoria_pico.map_or(m, |pico| pico.max(m)));
+                }
+                let acoes = Estatisticas::ler(&estatisticas.acoes_enviadas);
+                let (_, p) = estatisticas.percentis(&[50.0, 99.0]);
+                println!(
+                    "[{:>4}s] salas {:>4}  ações/s {:>6.1}  p50 {}  p99 {}  memória {}",
+                    inicio.elapsed().as_secs(),
+                    Estatisticas::ler(&estatisticas.mesas_abertas),
+                    (acoes - acoes_anteriores) as f64 / INTERVALO_PROGRESSO.as_secs_f64(),
+                    milissegundos(p[0]),
+                    milissegundos(p[1]),
+                    memoria.map_or("?".to_string(), megabytes),
+                );
+                acoes_anteriores = acoes;
+            }
+        }
+    }
+
+    // Memória com todas as mesas ainda abertas, antes de os robôs saírem
+    let depois = ler_metricas(&opcoes.endereco).await.unwrap_or_default();
+    let _ = parar.send(true);
+    if abertura.is_finished() {
+        // Dá um tempo para os robôs fecharem os WebSockets direito
+        let jogadores = abertura.await.unwrap_or_default();
+        let _ = tokio::time::timeout(Duration::from_secs(5), futures::future::join_all(jogadores))
+            .await;
+    } else {
+        abertura.abort();
+    }
+
+    relatorio(
+        &estatisticas,
+        inicio.elapsed(),
+        &antes,
+        &depois,
+        memoria_inicial,
+        memoria_pico,
+    );
+}
+
+fn relatorio(
+    estatisticas: &Estatisticas,
+    duracao: Duration,
+    antes: &HashMap<String, f64>,
+    depois: &HashMap<String, f64>,
+    memoria_inicial: Option<f64>,
+    memoria_pico: Option<f64>,
+) {
+    let e = estatisticas;
+    let (amostras, valores) = e.percentis(&PERCENTIS);
+    let acoes = Estatisticas::ler(&e.acoes_enviadas);
+
+    println!("\n=== Resultado ({} s) ===", duracao.as_secs());
+    println!(
+        "Salas abertas: {} ({} recusadas)  partidas terminadas: {}  turnos sem lance: {}",
+        Estatisticas::ler(&e.mesas_abertas),
+        Estatisticas::ler(&e.salas_recusadas),
+        Estatisticas::ler(&e.partidas_terminadas),
+        Estatisticas::ler(&e.turnos_sem_lance),
+    );
+    println!(
+        "Ações: {} enviadas ({:.1}/s), {} recusadas pelo jogo, {} sem resposta",
+        acoes,
+        acoes as f64 / duracao.as_secs_f64(),
+        Estatisticas::ler(&e.acoes_recusadas),
+        Estatisticas::ler(&e.acoes_sem_resposta),
+    );
+
+    println!(
+        "\nLatência ação → Delta em cada assento ({} medidas):",
+        amostras
+    );
+    for (p, valor) in PERCENTIS.iter().zip(valores) {
+        let nome = if *p == 100.0 {
+            "máx".to_string()
+        } else {
+            format!("p{}", p)
+        };
+        println!("  {:<6} {}", nome, milissegundos(valor));
+    }
+
+    println!("\nMensagens:");
+    println!(
+        "  perdidas (buraco na seq): {}",
+        Estatisticas::ler(&e.mensagens_perdidas)
+    );
+    println!(
+        "  deltas fora de ordem:     {}",
+        Estatisticas::ler(&e.deltas_fora_de_ordem)
+    );
+    println!(
+        "  rejeitadas pelo servidor: {}",
+        Estatisticas::ler(&e.mensagens_rejeitadas)
+    );
+    let falhas_envio = |m: &HashMap<String, f64>| m.get("buracao_falhas_envio_total").copied();
+    if let (Some(a), Some(d)) = (falhas_envio(antes), falhas_envio(depois)) {
+        println!("  falhas de envio (servidor): {}", d - a);
+    }
+    println!(
+        "  logins recusados: {}  conexões caídas: {}",
+        Estatisticas::ler(&e.logins_recusados),
+        Estatisticas::ler(&e.conexoes_caidas),
+    );
+
+    println!("\nMemória do servidor:");
+    match (memoria_inicial, depois.get(MEMORIA).copied()) {
+        (Some(inicial), Some(final_)) => {
+            let pico = memoria_pico.unwrap_or(final_).max(final_);
+            let salas = Estatisticas::ler(&e.mesas_abertas).max(1) as f64;
+            println!("  início {}", megabytes(inicial));
+            println!("  pico   {}", megabytes(pico));
+            println!(
+                "  fim    {} (+{}, {:.0} KB por sala)",
+                megabytes(final_),
+                megabytes(final_ - inicial),
+                (final_ - inicial) / 1024.0 / salas
+            );
+        }
+        _ => println!("  indisponível (/metrics sem {})", MEMORIA),
+    }
+}
@@ -0,0 +1,84 @@
+use buracao_core::lobby::{ConfigRelogio, NovaSala, ResumoSala};
+use std::collections::HashMap;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+// --- HTTP DO SERVIDOR ---
+// HTTP/1.0 à mão para as poucas chamadas REST da carga (métricas e criação de sala):
+// não vale uma dependência de cliente HTTP.
+
+/// Faz o pedido e devolve o status e o corpo da resposta.
+async fn pedir(
+    endereco: &str,
+    metodo: &str,
+    caminho: &str,
+    corpo: &str,
+) -> Result<(u16, String), String> {
+    let erro = |e: std::io::Error| format!("{} {}: {}", metodo, caminho, e);
+    let mut conexao = TcpStream::connect(endereco).await.map_err(erro)?;
+    let pedido = format!(
+        "{} {} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        metodo,
+        caminho,
+        endereco,
+        corpo.len(),
+        corpo
+    );
+    conexao.write_all(pedido.as_bytes()).await.map_err(erro)?;
+    let mut resposta = String::new();
+    conexao.read_to_string(&mut resposta).await.map_err(erro)?;
+
+    let (cabecalho, corpo) = resposta
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| format!("{} {}: resposta sem corpo", metodo, caminho))?;
+    let status = cabecalho
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| format!("{} {}: resposta sem status", metodo, caminho))?;
+    Ok((status, corpo.to_string()))
+}
+
+/// Métricas do servidor (`GET /metrics`), indexadas pelo nome com os rótulos.
+pub async fn ler_metricas(endereco: &str) -> Result<HashMap<String, f64>, String> {
+    let (status, corpo) = pedir(endereco, "GET", "/metrics", "").await?;
+    if status != 200 {
+        return Err(format!("GET /metrics: status {}", status));
+    }
+    Ok(corpo
+        .lines()
+        .filter(|linha| !linha.starts_with('#'))
+        .filter_map(|linha| {
+            let (nome, valor) = linha.rsplit_once(' ')?;
+            Some((nome.to_string(), valor.parse().ok()?))
+        })
+        .collect())
+}
+
+/// Cria uma sala fora do lobby pela API, com relógio de turno, e devolve o código dela.
+pub async fn criar_sala(
+    endereco: &str,
+    nome: &str,
+    segundos_por_turno: u32,
+) -> Result<String, String> {
+    let pedido = NovaSala {
+        nome: nome.to_string(),
+        publica: false,
+        senha: None,
+        regras: None,
+        relogio: Some(ConfigRelogio {
+            segundos_por_turno,
+            banco_segundos: 0,
+        }),
+        espectadores: Default::default(),
+    };
+    let corpo = serde_json::to_string(&pedido).expect("pedido de sala serializa");
+    let (status, resposta) = pedir(endereco, "POST", "/api/salas", &corpo).await?;
+    if status != 201 {
+        return Err(format!("POST /api/salas: status {} {}", status, resposta));
+    }
+    serde_json::from_str::<ResumoSala>(&resposta)
+        .map(|resumo| resumo.codigo)
+        .map_err(|e| format!("POST /api/salas: {}", e))
+}
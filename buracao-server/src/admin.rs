@@ -0,0 +1,390 @@
+use crate::relogio::RelogioTurno;
+use crate::sala::Sala;
+use crate::sessao;
+use crate::state::{DeviceId, GlobalState, MAX_JOGADORES, PlayerId, Room, Saida};
+use buracao_core::acoes::MsgServidor;
+use buracao_core::estado::EstadoJogo;
+use buracao_core::lobby::ResumoSala;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use warp::http::StatusCode;
+use warp::ws::Message;
+use warp::{Filter, Rejection, Reply};
+
+// --- API DE ADMINISTRAÇÃO ---
+// Para destravar uma mesa sem reiniciar o servidor. Só existe com `token_admin`
+// configurado (sem ele as rotas nem existem) e toda chamada precisa do cabeçalho
+// `x-token-admin`. Cada ação fica no log com a sala e o que foi feito.
+//
+// GET    /api/admin/salas                          -> todas as salas, com o estado completo
+// GET    /api/admin/salas/{codigo}                 -> uma sala, com o estado completo
+// GET    /api/admin/salas/{codigo}/invariantes     -> confere a mesa (cartas, jogos, lances)
+// POST   /api/admin/salas/{codigo}/avancar         -> joga pelo jogador da vez (compra e descarta)
+// POST   /api/admin/salas/{codigo}/reiniciar       -> dá a mão atual de novo, do zero
+// POST   /api/admin/salas/{codigo}/assentos/{n}    -> passa o assento para outro device_id
+// DELETE /api/admin/salas/{codigo}                 -> fecha a sala e desconecta todo mundo
+
+/// Tudo o que a sala sabe, para diagnóstico (inclui as mãos de todos).
+#[derive(Serialize)]
+struct DumpSala<'a> {
+    resumo: ResumoSala,
+    sessoes: &'a HashMap<DeviceId, PlayerId>,
+    conectados: Vec<PlayerId>,
+    espectadores: usize,
+    host: Option<PlayerId>,
+    trancada: bool,
+    mesa_de_torneio: bool,
+    fim_de_jogo: bool,
+    estado: &'a EstadoJogo,
+}
+
+#[derive(Deserialize)]
+struct NovoDevice {
+    device_id: String,
+}
+
+fn resposta_erro(status: StatusCode, mensagem: &str) -> warp::reply::Response {
+    warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({ "erro": mensagem })),
+        status,
+    )
+    .into_response()
+}
+
+fn dump(codigo: &str, room: &Room) -> serde_json::Value {
+    let mut conectados: Vec<PlayerId> = room.clients.keys().copied().collect();
+    conectados.sort();
+    serde_json::to_value(DumpSala {
+        resumo: room.resumo(codigo),
+        sessoes: &room.sessions,
+        conectados,
+        espectadores: room.espectadores.len(),
+        host: room.host,
+        trancada: room.trancada,
+        mesa_de_torneio: room.torneio.is_some(),
+        fim_de_jogo: room.fim_de_jogo.is_some(),
+        estado: &room.game_state,
+    })
+    .unwrap_or_default()
+}
+
+pub fn rotas(
+    state: GlobalState,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let state_filter = warp::any().map(move || state.clone());
+    // Confere o token antes de qualquer coisa; devolve o estado só para quem passou
+    let admin = warp::header::optional::<String>("x-token-admin")
+        .and(state_filter)
+        .and_then(autorizar);
+
+    let listar = warp::path!("api" / "admin" / "salas")
+        .and(warp::get())
+        .and(admin.clone())
+        .and_then(listar_salas);
+
+    let detalhe = warp::path!("api" / "admin" / "salas" / String)
+        .and(warp::get())
+        .and(admin.clone())
+        .and_then(detalhe_sala);
+
+    let invariantes = warp::path!("api" / "admin" / "salas" / String / "invariantes")
+        .and(warp::get())
+        .and(admin.clone())
+        .and_then(verificar_invariantes);
+
+    let avancar = warp::path!("api" / "admin" / "salas" / String / "avancar")
+        .and(warp::post())
+        .and(admin.clone())
+        .and_then(avancar_turno);
+
+    let reiniciar = warp::path!("api" / "admin" / "salas" / String / "reiniciar")
+        .and(warp::post())
+        .and(admin.clone())
+        .and_then(reiniciar_mao);
+
+    let reatribuir = warp::path!("api" / "admin" / "salas" / String / "assentos" / u32)
+        .and(warp::post())
+        .and(warp::body::content_length_limit(1024))
+        .and(warp::body::json())
+        .and(admin.clone())
+        .and_then(reatribuir_assento);
+
+    let fechar = warp::path!("api" / "admin" / "salas" / String)
+        .and(warp::delete())
+        .and(admin)
+        .and_then(fechar_sala);
+
+    listar
+        .or(detalhe)
+        .or(invariantes)
+        .or(avancar)
+        .or(reiniciar)
+        .or(reatribuir)
+        .or(fechar)
+}
+
+/// Recusa quem não tem o token. Sem `token_admin` configurado a API não existe.
+async fn autorizar(
+    token: Option<String>,
+    global_state: GlobalState,
+) -> Result<GlobalState, Rejection> {
+    let configurado = global_state.config.token_admin.clone();
+    let Some(configurado) = configurado else {
+        return Err(warp::reject::not_found());
+    };
+    if token.is_some_and(|t| sessao::senha_confere(&configurado, &t)) {
+        Ok(global_state)
+    } else {
+        tracing::warn!("chamada à API de administração com token inválido");
+        Err(warp::reject::custom(TokenInvalido))
+    }
+}
+
+#[derive(Debug)]
+pub struct TokenInvalido;
+impl warp::reject::Reject for TokenInvalido {}
+
+/// Transforma a recusa do token em 401 com `{"erro": ...}` (o resto segue adiante).
+pub async fn tratar_recusa(recusa: Rejection) -> Result<warp::reply::Response, Rejection> {
+    if recusa.find::<TokenInvalido>().is_some() {
+        Ok(resposta_erro(
+            StatusCode::UNAUTHORIZED,
+            "Token de administração ausente ou inválido.",
+        ))
+    } else {
+        Err(recusa)
+    }
+}
+
+fn sala(global_state: &GlobalState, codigo: &str) -> Option<Sala> {
+    global_state.rooms.get(codigo).map(|sala| sala.clone())
+}
+
+fn sala_nao_encontrada() -> warp::reply::Response {
+    resposta_erro(StatusCode::NOT_FOUND, "Sala não encontrada.")
+}
+
+async fn listar_salas(global_state: GlobalState) -> Result<impl Reply, Infallible> {
+    let salas: Vec<(String, Sala)> = global_state
+        .rooms
+        .iter()
+        .map(|par| (par.key().clone(), par.value().clone()))
+        .collect();
+    let mut dumps = Vec::new();
+    for (codigo, sala) in salas {
+        if let Some(d) = sala.executar(move |room| dump(&codigo, room)).await {
+            dumps.push(d);
+        }
+    }
+    tracing::info!(admin = "listar_salas", salas = dumps.len(), "administração");
+    Ok(warp::reply::json(&dumps))
+}
+
+async fn detalhe_sala(
+    codigo: String,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let Some(sala) = sala(&global_state, &codigo) else {
+        return Ok(sala_nao_encontrada());
+    };
+    tracing::info!(admin = "detalhe_sala", sala = %codigo, "administração");
+    let Some(d) = sala.executar(move |room| dump(&codigo, room)).await else {
+        return Ok(sala_nao_encontrada());
+    };
+    Ok(warp::reply::json(&d).into_response())
+}
+
+async fn verificar_invariantes(
+    codigo: String,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let Some(sala) = sala(&global_state, &codigo) else {
+        return Ok(sala_nao_encontrada());
+    };
+    let Some(problemas) = sala
+        .executar(|room| room.game_state.verificar_invariantes())
+        .await
+    else {
+        return Ok(sala_nao_encontrada());
+    };
+    tracing::info!(
+        admin = "invariantes",
+        sala = %codigo,
+        problemas = problemas.len(),
+        "administração"
+    );
+    Ok(warp::reply::json(
+        &serde_json::json!({ "ok": problemas.is_empty(), "problemas": problemas }),
+    )
+    .into_response())
+}
+
+/// Depois de mexer no jogo por fora das ações: todos recebem a mesa nova.
+fn anunciar_mudanca(room: &mut Room, aviso: &str) {
+    room.inicio_turno = None;
+    room.propagar_estado();
+    room.broadcast(&MsgServidor::Notificacao(aviso.to_string()));
+}
+
+async fn avancar_turno(
+    codigo: String,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let Some(sala) = sala(&global_state, &codigo) else {
+        return Ok(sala_nao_encontrada());
+    };
+    let resposta = sala.executar(move |room| {
+        let vez = room.game_state.turno_atual;
+        let versao = room.game_state.versao;
+        // A jogada automática do relógio mantém os lances válidos (a mão continua reproduzível)
+        match room.game_state.jogada_automatica(vez) {
+            Ok(resumo) => {
+                tracing::warn!(admin = "avancar_turno", sala = %codigo, assento = vez, %resumo, "administração");
+                anunciar_mudanca(
+                    room,
+                    &format!("O administrador jogou pelo jogador {}.", vez),
+                );
+                warp::reply::json(&dump(&codigo, room)).into_response()
+            }
+            Err(e) => {
+                tracing::warn!(admin = "avancar_turno", sala = %codigo, assento = vez, erro = %e, "administração falhou");
+                if room.game_state.versao != versao {
+                    room.propagar_estado();
+                }
+                resposta_erro(
+                    StatusCode::CONFLICT,
+                    &format!(
+                        "Não foi possível jogar pelo jogador {}: {} Tente reiniciar a mão.",
+                        vez, e
+                    ),
+                )
+            }
+        }
+    });
+    Ok(resposta.await.unwrap_or_else(sala_nao_encontrada))
+}
+
+async fn reiniciar_mao(
+    codigo: String,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let Some(sala) = sala(&global_state, &codigo) else {
+        return Ok(sala_nao_encontrada());
+    };
+    let resposta = sala.executar(move |room| {
+        room.game_state = room.game_state.reiniciar_mao();
+        // Mão nova: some o fim de jogo (se havia) e os votos; a série não muda
+        room.fim_de_jogo = None;
+        room.votos_fim.clear();
+        if let Some(relogio) = &room.relogio {
+            room.relogio = Some(RelogioTurno::new(relogio.config));
+        }
+        let serie = room.serie;
+        room.broadcast(&MsgServidor::NovaPartida {
+            serie,
+            duplas_trocadas: false,
+        });
+        tracing::warn!(
+            admin = "reiniciar_mao",
+            sala = %codigo,
+            numero_partida = room.game_state.numero_partida,
+            "administração"
+        );
+        anunciar_mudanca(room, "O administrador deu as cartas de novo.");
+        warp::reply::json(&dump(&codigo, room)).into_response()
+    });
+    Ok(resposta.await.unwrap_or_else(sala_nao_encontrada))
+}
+
+async fn reatribuir_assento(
+    codigo: String,
+    assento: u32,
+    pedido: NovoDevice,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let device = pedido.device_id.trim().to_string();
+    if assento >= MAX_JOGADORES {
+        return Ok(resposta_erro(
+            StatusCode::BAD_REQUEST,
+            "Assento inexistente (0 a 3).",
+        ));
+    }
+    if device.is_empty() {
+        return Ok(resposta_erro(
+            StatusCode::BAD_REQUEST,
+            "Informe o device_id.",
+        ));
+    }
+    let Some(sala) = sala(&global_state, &codigo) else {
+        return Ok(sala_nao_encontrada());
+    };
+    let resposta = sala.executar(move |room| {
+        if let Some(&outro) = room.sessions.get(&device)
+            && outro != assento
+        {
+            return resposta_erro(
+                StatusCode::CONFLICT,
+                &format!("Esse device já ocupa o assento {}.", outro),
+            );
+        }
+
+        // Quem estava no assento perde a conexão; a mão, o nome e o perfil ficam
+        if let Some(tx) = room.clients.remove(&assento) {
+            let aviso = MsgServidor::Expulso {
+                motivo: "O administrador passou o seu assento para outro aparelho.".to_string(),
+            };
+            let _ = tx.send(Saida::msg(&aviso));
+            let _ = tx.send(Saida::Ws(Message::close()));
+        }
+        let anterior: Vec<DeviceId> = room
+            .sessions
+            .iter()
+            .filter(|(_, pid)| **pid == assento)
+            .map(|(d, _)| d.clone())
+            .collect();
+        for d in &anterior {
+            room.sessions.remove(d);
+            room.assentos_transferidos.remove(d);
+        }
+        room.sessions.insert(device.clone(), assento);
+        // O aparelho novo ainda não tem token: o primeiro login dele entra sem
+        room.assentos_transferidos.insert(device.clone());
+        room.banidos.remove(&device);
+
+        tracing::warn!(
+            admin = "reatribuir_assento",
+            sala = %codigo,
+            assento,
+            device_anterior = ?anterior,
+            device_novo = %device,
+            "administração"
+        );
+        warp::reply::json(&dump(&codigo, room)).into_response()
+    });
+    Ok(resposta.await.unwrap_or_else(sala_nao_encontrada))
+}
+
+async fn fechar_sala(
+    codigo: String,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let Some((_, sala)) = global_state.rooms.remove(&codigo) else {
+        return Ok(sala_nao_encontrada());
+    };
+    sala.executar(|room| {
+        // Quem ainda tiver esta `Sala` em mãos (um login no meio do caminho) procura de novo
+        room.fechada = true;
+        let aviso = MsgServidor::Expulso {
+            motivo: "A sala foi fechada pelo administrador.".to_string(),
+        };
+        room.broadcast(&aviso);
+        for (_, tx) in room.clients.drain() {
+            let _ = tx.send(Saida::Ws(Message::close()));
+        }
+        room.desconectar_espectadores();
+    })
+    .await;
+    tracing::warn!(admin = "fechar_sala", sala = %codigo, "administração");
+    Ok(warp::reply::json(&serde_json::json!({ "fechada": codigo })).into_response())
+}
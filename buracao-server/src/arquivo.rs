@@ -0,0 +1,253 @@
+use crate::ranking::abrir_banco;
+use crate::state::{MAX_JOGADORES, Room};
+use buracao_core::registro::{JogadorRegistro, RegistroPartida, ResumoRegistro};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+
+// --- ARQUIVO DE PARTIDAS ---
+// Toda partida que termina vira um registro (ver buracao-core/src/registro.rs) guardado
+// no banco. Dá para baixar o registro e importar registros de outros servidores; a
+// importação refaz a partida inteira antes de aceitar.
+
+const LIMITE_PADRAO: u32 = 50;
+const LIMITE_MAXIMO: u32 = 500;
+// Uma mão longa tem algumas centenas de lances: sobra bastante
+const TAMANHO_MAX_REGISTRO: u64 = 2 * 1024 * 1024;
+
+const ESQUEMA: &str = "
+CREATE TABLE IF NOT EXISTS arquivo_partidas (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    sala        TEXT    NOT NULL,
+    terminou_em INTEGER NOT NULL,
+    jogadores   TEXT    NOT NULL,
+    pontos_a    INTEGER NOT NULL,
+    pontos_b    INTEGER NOT NULL,
+    importada   INTEGER NOT NULL,
+    registro    TEXT    NOT NULL
+);
+";
+
+pub struct Arquivo {
+    conn: Mutex<Connection>,
+}
+
+impl Arquivo {
+    pub fn abrir(caminho: &str) -> Result<Arc<Self>, String> {
+        let conn = abrir_banco(caminho, "Arquivo de partidas")?;
+        conn.execute_batch(ESQUEMA).map_err(|e| {
+            format!(
+                "Arquivo de partidas: não foi possível criar a tabela ({})",
+                e
+            )
+        })?;
+        Ok(Arc::new(Self {
+            conn: Mutex::new(conn),
+        }))
+    }
+
+    pub fn guardar(&self, registro: &RegistroPartida, importada: bool) -> rusqlite::Result<i64> {
+        let nomes: Vec<&str> = (0..MAX_JOGADORES).map(|a| registro.nome(a)).collect();
+        let json = serde_json::to_string(registro).expect("registro serializável");
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO arquivo_partidas
+                (sala, terminou_em, jogadores, pontos_a, pontos_b, importada, registro)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                registro.sala,
+                registro.terminou_em,
+                serde_json::to_string(&nomes).unwrap_or_default(),
+                registro.pontos_a,
+                registro.pontos_b,
+                importada,
+                json
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Partidas mais recentes primeiro.
+    pub fn listar(&self, limite: u32) -> rusqlite::Result<Vec<ResumoRegistro>> {
+        let conn = self.conn.lock().unwrap();
+        let mut consulta = conn.prepare(
+            "SELECT id, sala, terminou_em, jogadores, pontos_a, pontos_b, importada
+             FROM arquivo_partidas ORDER BY terminou_em DESC, id DESC LIMIT ?1",
+        )?;
+        let linhas = consulta.query_map(params![limite], |l| {
+            let jogadores: String = l.get(3)?;
+            Ok(ResumoRegistro {
+                id: l.get(0)?,
+                sala: l.get(1)?,
+                terminou_em: l.get(2)?,
+                jogadores: serde_json::from_str(&jogadores).unwrap_or_default(),
+                pontos_a: l.get(4)?,
+                pontos_b: l.get(5)?,
+                importada: l.get(6)?,
+            })
+        })?;
+        linhas.collect()
+    }
+
+    /// O registro como foi guardado (JSON).
+    pub fn buscar(&self, id: i64) -> rusqlite::Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT registro FROM arquivo_partidas WHERE id = ?1",
+            params![id],
+            |l| l.get(0),
+        )
+        .optional()
+    }
+}
+
+impl Room {
+    /// Guarda a partida que acabou de terminar.
+    pub fn arquivar_partida(&self) {
+        let Some(arquivo) = self.arquivo.clone() else {
+            return;
+        };
+        let jogadores = (0..MAX_JOGADORES)
+            .map(|assento| JogadorRegistro {
+                assento,
+                nome: self
+                    .player_names
+                    .get(&assento)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Jogador {}", assento)),
+                id_perfil: self.perfis.get(&assento).copied(),
+            })
+            .collect();
+        let terminou_em = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let registro =
+            RegistroPartida::novo(self.nome.clone(), terminou_em, &self.game_state, jogadores);
+
+        let span = tracing::Span::current();
+        tokio::task::spawn_blocking(move || {
+            let _span = span.enter();
+            match arquivo.guardar(&registro, false) {
+                Ok(id) => tracing::info!(partida = id, "partida arquivada"),
+                Err(e) => tracing::warn!(erro = %e, "não foi possível arquivar a partida"),
+            }
+        });
+    }
+}
+
+// --- ROTAS REST ---
+// GET  /api/partidas?limite=N -> partidas arquivadas, mais recentes primeiro
+// GET  /api/partidas/{id}     -> baixa o registro da partida
+// POST /api/partidas          -> importa um registro (refeito e conferido antes de guardar)
+pub fn rotas(
+    arquivo: Arc<Arquivo>,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let arquivo_filter = warp::any().map(move || arquivo.clone());
+
+    let listar = warp::path!("api" / "partidas")
+        .and(warp::get())
+        .and(warp::query::<FiltroLista>())
+        .and(arquivo_filter.clone())
+        .and_then(listar_partidas);
+
+    let baixar = warp::path!("api" / "partidas" / i64)
+        .and(warp::get())
+        .and(arquivo_filter.clone())
+        .and_then(baixar_partida);
+
+    let importar = warp::path!("api" / "partidas")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(TAMANHO_MAX_REGISTRO))
+        .and(warp::body::json())
+        .and(arquivo_filter)
+        .and_then(importar_partida);
+
+    listar.or(baixar).or(importar)
+}
+
+#[derive(Deserialize)]
+struct FiltroLista {
+    limite: Option<u32>,
+}
+
+fn resposta_erro(status: StatusCode, mensagem: &str) -> warp::reply::Response {
+    warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({ "erro": mensagem })),
+        status,
+    )
+    .into_response()
+}
+
+async fn listar_partidas(
+    filtro: FiltroLista,
+    arquivo: Arc<Arquivo>,
+) -> Result<warp::reply::Response, Infallible> {
+    let limite = filtro
+        .limite
+        .unwrap_or(LIMITE_PADRAO)
+        .clamp(1, LIMITE_MAXIMO);
+    let resultado = tokio::task::spawn_blocking(move || arquivo.listar(limite)).await;
+    Ok(match resultado {
+        Ok(Ok(lista)) => warp::reply::json(&lista).into_response(),
+        _ => resposta_erro(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Arquivo de partidas indisponível.",
+        ),
+    })
+}
+
+async fn baixar_partida(
+    id: i64,
+    arquivo: Arc<Arquivo>,
+) -> Result<warp::reply::Response, Infallible> {
+    let resultado = tokio::task::spawn_blocking(move || arquivo.buscar(id)).await;
+    Ok(match resultado {
+        Ok(Ok(Some(json))) => warp::http::Response::builder()
+            .header("content-type", "application/json")
+            .header(
+                "content-disposition",
+                format!("attachment; filename=\"partida-{}.buracao.json\"", id),
+            )
+            .body(json.into())
+            .unwrap_or_default(),
+        Ok(Ok(None)) => resposta_erro(StatusCode::NOT_FOUND, "Partida não encontrada."),
+        _ => resposta_erro(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Arquivo de partidas indisponível.",
+        ),
+    })
+}
+
+async fn importar_partida(
+    registro: RegistroPartida,
+    arquivo: Arc<Arquivo>,
+) -> Result<warp::reply::Response, Infallible> {
+    // Refazer a partida custa CPU: fora das threads do runtime
+    let resultado = tokio::task::spawn_blocking(move || {
+        registro.validar()?;
+        arquivo
+            .guardar(&registro, true)
+            .map_err(|e| format!("Não foi possível guardar: {}", e))
+    })
+    .await;
+    Ok(match resultado {
+        Ok(Ok(id)) => {
+            tracing::info!(partida = id, "partida importada");
+            warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({ "id": id })),
+                StatusCode::CREATED,
+            )
+            .into_response()
+        }
+        Ok(Err(e)) => resposta_erro(StatusCode::BAD_REQUEST, &e),
+        Err(_) => resposta_erro(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Falha ao importar a partida.",
+        ),
+    })
+}
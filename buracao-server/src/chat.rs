@@ -0,0 +1,138 @@
+use crate::state::{PlayerId, Room};
+use buracao_core::acoes::{MensagemChat, MsgServidor, Reacao};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// --- CHAT DA MESA ---
+
+pub const TAMANHO_MAX_MENSAGEM: usize = 300;
+pub const HISTORICO_MAX: usize = 50;
+
+// Limite por jogador: no máximo 5 mensagens a cada 10 segundos
+const RAJADA_MAX: usize = 5;
+const JANELA_RAJADA: Duration = Duration::from_secs(10);
+
+// Intervalo mínimo entre duas reações do mesmo jogador
+const INTERVALO_REACAO: Duration = Duration::from_millis(1500);
+
+fn mesmo_time(a: PlayerId, b: PlayerId) -> bool {
+    a % 2 == b % 2
+}
+
+/// Quem pode ler a mensagem: todos, ou só a dupla do autor.
+pub fn visivel_para(msg: &MensagemChat, leitor: PlayerId) -> bool {
+    !msg.apenas_time || mesmo_time(msg.autor_id, leitor)
+}
+
+fn agora_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Valida, registra no histórico e entrega a mensagem para quem pode ler.
+pub fn processar(
+    room: &mut Room,
+    autor: PlayerId,
+    texto: &str,
+    apenas_time: bool,
+) -> Result<(), String> {
+    let texto = texto.trim();
+    if texto.is_empty() {
+        return Err("Mensagem vazia.".to_string());
+    }
+    if texto.chars().count() > TAMANHO_MAX_MENSAGEM {
+        return Err(format!(
+            "Mensagem muito longa (máximo {} caracteres).",
+            TAMANHO_MAX_MENSAGEM
+        ));
+    }
+
+    // Janela deslizante: descarta envios antigos e conta os recentes
+    let agora = Instant::now();
+    let envios = room.limite_chat.entry(autor).or_default();
+    while envios
+        .front()
+        .is_some_and(|t| agora.duration_since(*t) > JANELA_RAJADA)
+    {
+        envios.pop_front();
+    }
+    if envios.len() >= RAJADA_MAX {
+        return Err("Calma! Você está mandando mensagens rápido demais.".to_string());
+    }
+    envios.push_back(agora);
+
+    let msg = MensagemChat {
+        autor_id: autor,
+        autor_nome: match room.nome_espectador(autor) {
+            Some(nome) => format!("👀 {}", nome),
+            None => room
+                .player_names
+                .get(&autor)
+                .cloned()
+                .unwrap_or(format!("Jogador {}", autor)),
+        },
+        texto: texto.to_string(),
+        timestamp_ms: agora_ms(),
+        apenas_time,
+    };
+
+    room.chat.push_back(msg.clone());
+    while room.chat.len() > HISTORICO_MAX {
+        room.chat.pop_front();
+    }
+
+    let envelope = MsgServidor::Chat(msg.clone());
+    for pid in room.jogadores_sentados() {
+        if visivel_para(&msg, pid) {
+            room.enviar(pid, &envelope);
+        }
+    }
+    // Quem assiste nunca lê o chat de time
+    if !msg.apenas_time {
+        room.enviar_espectadores(&envelope);
+    }
+
+    Ok(())
+}
+
+/// Histórico que o jogador pode ver (usado no login/reconexão).
+pub fn historico_para(room: &Room, leitor: PlayerId) -> MsgServidor {
+    MsgServidor::HistoricoChat(
+        room.chat
+            .iter()
+            .filter(|m| visivel_para(m, leitor))
+            .cloned()
+            .collect(),
+    )
+}
+
+/// Histórico sem as mensagens de time (para espectadores).
+pub fn historico_publico(room: &Room) -> MsgServidor {
+    MsgServidor::HistoricoChat(
+        room.chat
+            .iter()
+            .filter(|m| !m.apenas_time)
+            .cloned()
+            .collect(),
+    )
+}
+
+/// Repassa a reação para a mesa. Reações em excesso são descartadas em silêncio
+/// (não vale um toast de erro para cada clique repetido).
+pub fn processar_reacao(room: &mut Room, autor: PlayerId, reacao: Reacao) {
+    let agora = Instant::now();
+    if room
+        .ultima_reacao
+        .get(&autor)
+        .is_some_and(|t| agora.duration_since(*t) < INTERVALO_REACAO)
+    {
+        return;
+    }
+    room.ultima_reacao.insert(autor, agora);
+
+    room.broadcast(&MsgServidor::Reacao {
+        jogador: autor,
+        reacao,
+    });
+}
@@ -0,0 +1,483 @@
+use crate::presenca::ConfigHeartbeat;
+use crate::protecao::ConfigLimites;
+use buracao_core::regras::{ConfigRegras, REGRAS_PREDEFINIDAS};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// --- CONFIGURAÇÃO DO SERVIDOR ---
+// Precedência: valores padrão < arquivo TOML < variáveis de ambiente < flags da linha
+// de comando. O arquivo vem de `--config`, de `BURACAO_CONFIG` ou de ./buracao.toml
+// (se existir). Qualquer valor inválido impede o servidor de subir, com uma mensagem
+// dizendo de onde veio o valor; é melhor não subir do que subir servindo nada.
+
+const ARQUIVO_PADRAO: &str = "buracao.toml";
+const SITES_PROVAVEIS: [&str; 3] = ["./dist", "../buracao-web/dist", "./buracao-web/dist"];
+pub const NIVEIS_LOG: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+pub const FORMATOS_LOG: [&str; 2] = ["texto", "json"];
+// Banco só em memória: ranking e arquivo de partidas somem quando o servidor para
+pub const BANCO_EM_MEMORIA: &str = ":memory:";
+const TAMANHO_MIN_TOKEN_ADMIN: usize = 16;
+// Menos que isso não cabe nem um login com todos os campos no máximo
+const MAX_MENSAGEM_MIN: usize = 1024;
+
+/// Cada opção: chave no TOML, variável de ambiente e descrição para o `--ajuda`.
+/// A flag é a chave com hífens (`max_salas` -> `--max-salas`).
+const OPCOES: [(&str, &str, &str); 14] = [
+    (
+        "endereco",
+        "BURACAO_ENDERECO",
+        "IP:PORTA onde o servidor escuta (0.0.0.0:8080)",
+    ),
+    (
+        "site",
+        "BURACAO_SITE",
+        "pasta do site compilado, com o index.html (procura ./dist)",
+    ),
+    (
+        "banco",
+        "BURACAO_DB",
+        "arquivo SQLite do ranking e das partidas (buracao.db; :memory: = sem disco)",
+    ),
+    (
+        "max_salas",
+        "BURACAO_MAX_SALAS",
+        "número máximo de salas abertas ao mesmo tempo (500)",
+    ),
+    (
+        "sala_ociosa_min",
+        "BURACAO_SALA_OCIOSA_MIN",
+        "minutos sem ninguém conectado até fechar a sala (30; 0 = nunca)",
+    ),
+    (
+        "regras",
+        "BURACAO_REGRAS",
+        "regras das salas criadas sem regras próprias (padrao)",
+    ),
+    (
+        "log",
+        "BURACAO_LOG",
+        "nível de log: error, warn, info, debug ou trace (info)",
+    ),
+    (
+        "log_formato",
+        "BURACAO_LOG_FORMATO",
+        "texto (para ler no terminal) ou json (uma linha por evento) (texto)",
+    ),
+    (
+        "token_admin",
+        "BURACAO_TOKEN_ADMIN",
+        "token da API de administração (desligada sem token)",
+    ),
+    (
+        "segredo",
+        "BURACAO_SECRET",
+        "segredo dos tokens de sessão (sorteado a cada início)",
+    ),
+    (
+        "ping_intervalo_s",
+        "BURACAO_PING_INTERVALO_S",
+        "segundos entre pings do heartbeat (15)",
+    ),
+    (
+        "ping_timeout_s",
+        "BURACAO_PING_TIMEOUT_S",
+        "segundos sem resposta até derrubar a conexão (45)",
+    ),
+    (
+        "max_mensagem",
+        "BURACAO_MAX_MENSAGEM",
+        "tamanho máximo de uma mensagem do cliente, em bytes (16384)",
+    ),
+    (
+        "acoes_por_segundo",
+        "BURACAO_ACOES_POR_SEGUNDO",
+        "mensagens por segundo aceitas de cada conexão, com rajada do dobro (10)",
+    ),
+];
+
+/// Configuração efetiva, já validada.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub endereco: SocketAddr,
+    pub site: PathBuf,
+    pub banco: String,
+    pub max_salas: usize,
+    /// Sala sem ninguém conectado por esse tempo é fechada (None = nunca)
+    pub sala_ociosa: Option<Duration>,
+    /// Nome da predefinição e as regras correspondentes
+    pub nome_regras: String,
+    pub regras: ConfigRegras,
+    pub log: String,
+    /// Uma linha JSON por evento em vez de texto legível
+    pub log_json: bool,
+    pub token_admin: Option<String>,
+    pub segredo: Option<String>,
+    pub heartbeat: ConfigHeartbeat,
+    pub limites: ConfigLimites,
+}
+
+/// O que veio de cada fonte, antes de validar. Campo ausente = mantém o anterior.
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+struct ConfigParcial {
+    endereco: Option<String>,
+    site: Option<PathBuf>,
+    banco: Option<String>,
+    max_salas: Option<usize>,
+    sala_ociosa_min: Option<u64>,
+    regras: Option<String>,
+    log: Option<String>,
+    log_formato: Option<String>,
+    token_admin: Option<String>,
+    segredo: Option<String>,
+    ping_intervalo_s: Option<u64>,
+    ping_timeout_s: Option<u64>,
+    max_mensagem: Option<usize>,
+    acoes_por_segundo: Option<u32>,
+}
+
+fn numero<T: std::str::FromStr>(origem: &str, texto: &str) -> Result<T, String> {
+    texto
+        .trim()
+        .parse()
+        .map_err(|_| format!("{}: '{}' não é um número válido", origem, texto))
+}
+
+impl ConfigParcial {
+    /// Valor em texto vindo do ambiente ou da linha de comando.
+    fn definir(&mut self, chave: &str, texto: &str, origem: &str) -> Result<(), String> {
+        let valor = || Some(texto.to_string());
+        match chave {
+            "endereco" => self.endereco = valor(),
+            "site" => self.site = Some(PathBuf::from(texto)),
+            "banco" => self.banco = valor(),
+            "max_salas" => self.max_salas = Some(numero(origem, texto)?),
+            "sala_ociosa_min" => self.sala_ociosa_min = Some(numero(origem, texto)?),
+            "regras" => self.regras = valor(),
+            "log" => self.log = valor(),
+            "log_formato" => self.log_formato = valor(),
+            "token_admin" => self.token_admin = valor(),
+            "segredo" => self.segredo = valor(),
+            "ping_intervalo_s" => self.ping_intervalo_s = Some(numero(origem, texto)?),
+            "ping_timeout_s" => self.ping_timeout_s = Some(numero(origem, texto)?),
+            "max_mensagem" => self.max_mensagem = Some(numero(origem, texto)?),
+            "acoes_por_segundo" => self.acoes_por_segundo = Some(numero(origem, texto)?),
+            _ => return Err(format!("{}: opção desconhecida", origem)),
+        }
+        Ok(())
+    }
+
+    /// Valores de `outra` por cima destes.
+    fn sobrepor(&mut self, outra: ConfigParcial) {
+        macro_rules! sobrepor {
+            ($($campo:ident),*) => { $( if outra.$campo.is_some() { self.$campo = outra.$campo; } )* };
+        }
+        sobrepor!(
+            endereco,
+            site,
+            banco,
+            max_salas,
+            sala_ociosa_min,
+            regras,
+            log,
+            log_formato,
+            token_admin,
+            segredo,
+            ping_intervalo_s,
+            ping_timeout_s,
+            max_mensagem,
+            acoes_por_segundo
+        );
+    }
+
+    /// Aplica os padrões e confere tudo. Junta todos os problemas numa mensagem só,
+    /// para não obrigar a corrigir um por vez.
+    fn validar(self) -> Result<Config, String> {
+        let mut erros = Vec::new();
+
+        let texto_endereco = self.endereco.unwrap_or_else(|| "0.0.0.0:8080".to_string());
+        let endereco = texto_endereco.parse::<SocketAddr>().unwrap_or_else(|_| {
+            erros.push(format!(
+                "endereco: '{}' não é um IP:PORTA válido (ex.: 0.0.0.0:8080)",
+                texto_endereco
+            ));
+            ([0, 0, 0, 0], 8080).into()
+        });
+
+        let site = match self.site {
+            Some(site) if site.join("index.html").is_file() => site,
+            Some(site) => {
+                erros.push(format!(
+                    "site: '{}' não tem um index.html (compile o buracao-web com `trunk build`)",
+                    site.display()
+                ));
+                site
+            }
+            None => match SITES_PROVAVEIS
+                .iter()
+                .map(PathBuf::from)
+                .find(|p| p.join("index.html").is_file())
+            {
+                Some(site) => site,
+                None => {
+                    erros.push(format!(
+                        "site: não achei o index.html em {}; compile o buracao-web com `trunk build` ou indique a pasta com --site",
+                        SITES_PROVAVEIS.join(", ")
+                    ));
+                    PathBuf::new()
+                }
+            },
+        };
+
+        let banco = self.banco.unwrap_or_else(|| "buracao.db".to_string());
+        if banco.trim().is_empty() {
+            erros.push(format!(
+                "banco: caminho vazio (use {} para não gravar em disco)",
+                BANCO_EM_MEMORIA
+            ));
+        } else if banco != BANCO_EM_MEMORIA {
+            let pasta = Path::new(&banco)
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty());
+            if let Some(pasta) = pasta.filter(|p| !p.is_dir()) {
+                erros.push(format!("banco: a pasta '{}' não existe", pasta.display()));
+            }
+        }
+
+        let max_salas = self.max_salas.unwrap_or(500);
+        if max_salas == 0 {
+            erros.push("max_salas: precisa ser pelo menos 1".to_string());
+        }
+
+        let sala_ociosa = match self.sala_ociosa_min.unwrap_or(30) {
+            0 => None,
+            minutos => Some(Duration::from_secs(minutos * 60)),
+        };
+
+        let nome_regras = self.regras.unwrap_or_else(|| "padrao".to_string());
+        let regras = ConfigRegras::predefinida(&nome_regras).unwrap_or_else(|| {
+            erros.push(format!(
+                "regras: '{}' não existe (opções: {})",
+                nome_regras,
+                REGRAS_PREDEFINIDAS.join(", ")
+            ));
+            ConfigRegras::default()
+        });
+
+        let log = self
+            .log
+            .unwrap_or_else(|| "info".to_string())
+            .to_lowercase();
+        if !NIVEIS_LOG.contains(&log.as_str()) {
+            erros.push(format!(
+                "log: '{}' não é um nível válido ({})",
+                log,
+                NIVEIS_LOG.join(", ")
+            ));
+        }
+
+        let log_formato = self
+            .log_formato
+            .unwrap_or_else(|| "texto".to_string())
+            .to_lowercase();
+        if !FORMATOS_LOG.contains(&log_formato.as_str()) {
+            erros.push(format!(
+                "log_formato: '{}' não é um formato válido ({})",
+                log_formato,
+                FORMATOS_LOG.join(", ")
+            ));
+        }
+
+        // Vazio é o mesmo que não configurado
+        let token_admin = self.token_admin.filter(|t| !t.is_empty());
+        if token_admin
+            .as_ref()
+            .is_some_and(|t| t.len() < TAMANHO_MIN_TOKEN_ADMIN)
+        {
+            erros.push(format!(
+                "token_admin: precisa ter pelo menos {} caracteres",
+                TAMANHO_MIN_TOKEN_ADMIN
+            ));
+        }
+        let segredo = self.segredo.filter(|s| !s.is_empty());
+
+        let padrao = ConfigHeartbeat::default();
+        let heartbeat = ConfigHeartbeat {
+            intervalo: self
+                .ping_intervalo_s
+                .map_or(padrao.intervalo, Duration::from_secs),
+            timeout: self
+                .ping_timeout_s
+                .map_or(padrao.timeout, Duration::from_secs),
+        };
+        if heartbeat.intervalo.is_zero() {
+            erros.push("ping_intervalo_s: precisa ser maior que zero".to_string());
+        }
+        // Com timeout menor que o intervalo, toda conexão cairia antes do primeiro pong
+        if heartbeat.timeout <= heartbeat.intervalo {
+            erros.push(format!(
+                "ping_timeout_s: precisa ser maior que o intervalo ({}s)",
+                heartbeat.intervalo.as_secs()
+            ));
+        }
+
+        let padrao = ConfigLimites::default();
+        let limites = ConfigLimites {
+            max_mensagem: self.max_mensagem.unwrap_or(padrao.max_mensagem),
+            acoes_por_segundo: self.acoes_por_segundo.unwrap_or(padrao.acoes_por_segundo),
+        };
+        if limites.max_mensagem < MAX_MENSAGEM_MIN {
+            erros.push(format!(
+                "max_mensagem: precisa ser pelo menos {} bytes",
+                MAX_MENSAGEM_MIN
+            ));
+        }
+        if limites.acoes_por_segundo == 0 {
+            erros.push("acoes_por_segundo: precisa ser maior que zero".to_string());
+        }
+
+        if !erros.is_empty() {
+            return Err(erros.join("\n  "));
+        }
+        Ok(Config {
+            endereco,
+            site,
+            banco,
+            max_salas,
+            sala_ociosa,
+            nome_regras,
+            regras,
+            log,
+            log_json: log_formato == "json",
+            token_admin,
+            segredo,
+            heartbeat,
+            limites,
+        })
+    }
+}
+
+fn texto_ajuda() -> String {
+    let mut texto = String::from(
+        "Uso: buracao-server [--config ARQUIVO] [--opcao VALOR ...]\n\n\
+         Cada opção pode vir do arquivo TOML (chave), do ambiente (variável) ou da linha de\n\
+         comando (flag), nessa ordem de prioridade crescente.\n\n",
+    );
+    for (chave, variavel, descricao) in OPCOES {
+        texto.push_str(&format!(
+            "  --{:<18} {:<26} {}\n",
+            chave.replace('_', "-"),
+            variavel,
+            descricao
+        ));
+    }
+    texto.push_str(&format!(
+        "\n  --config ARQUIVO      BURACAO_CONFIG             arquivo TOML ({} se existir)\n",
+        ARQUIVO_PADRAO
+    ));
+    texto
+}
+
+/// Flags da linha de comando: `--opcao valor` ou `--opcao=valor`.
+fn ler_flags(args: &[String]) -> Result<(Option<PathBuf>, ConfigParcial), String> {
+    let mut arquivo = None;
+    let mut parcial = ConfigParcial::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(flag) = arg.strip_prefix("--") else {
+            return Err(format!("argumento inesperado '{}' (veja --ajuda)", arg));
+        };
+        let (nome, valor) = match flag.split_once('=') {
+            Some((nome, valor)) => (nome, valor.to_string()),
+            None => {
+                let valor = args
+                    .next()
+                    .ok_or_else(|| format!("--{}: falta o valor", flag))?;
+                (flag, valor.clone())
+            }
+        };
+        if nome == "config" {
+            arquivo = Some(PathBuf::from(valor));
+            continue;
+        }
+        let chave = nome.replace('-', "_");
+        if !OPCOES.iter().any(|(c, _, _)| *c == chave) {
+            return Err(format!("--{}: opção desconhecida (veja --ajuda)", nome));
+        }
+        parcial.definir(&chave, &valor, &format!("--{}", nome))?;
+    }
+    Ok((arquivo, parcial))
+}
+
+fn ler_arquivo(caminho: &Path) -> Result<ConfigParcial, String> {
+    let texto = std::fs::read_to_string(caminho)
+        .map_err(|e| format!("{}: não foi possível ler ({})", caminho.display(), e))?;
+    toml::from_str(&texto).map_err(|e| format!("{}: {}", caminho.display(), e))
+}
+
+impl Config {
+    /// Monta a configuração a partir dos argumentos e do ambiente do processo.
+    /// `--ajuda` imprime as opções e encerra.
+    pub fn carregar() -> Result<Self, String> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        if args
+            .iter()
+            .any(|a| a == "--ajuda" || a == "--help" || a == "-h")
+        {
+            print!("{}", texto_ajuda());
+            std::process::exit(0);
+        }
+        Self::de_fontes(&args, |nome| std::env::var(nome).ok())
+    }
+
+    /// Mesma coisa com argumentos e ambiente dados (os testes sobem o servidor assim).
+    pub fn de_fontes(
+        args: &[String],
+        ambiente: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, String> {
+        let (arquivo_flag, flags) = ler_flags(args)?;
+
+        let mut config = match arquivo_flag
+            .or_else(|| ambiente("BURACAO_CONFIG").map(PathBuf::from))
+        {
+            // Arquivo pedido explicitamente precisa existir
+            Some(caminho) => ler_arquivo(&caminho)?,
+            None if Path::new(ARQUIVO_PADRAO).is_file() => ler_arquivo(Path::new(ARQUIVO_PADRAO))?,
+            None => ConfigParcial::default(),
+        };
+
+        let mut do_ambiente = ConfigParcial::default();
+        for (chave, variavel, _) in OPCOES {
+            if let Some(valor) = ambiente(variavel) {
+                do_ambiente.definir(chave, &valor, variavel)?;
+            }
+        }
+        config.sobrepor(do_ambiente);
+        config.sobrepor(flags);
+        config.validar()
+    }
+
+    /// Resumo para o log de inicialização (sem segredos).
+    pub fn resumo(&self) -> String {
+        format!(
+            "endereço {}, site '{}', banco '{}', até {} salas, sala ociosa {}, regras '{}', log {} ({}), admin {}",
+            self.endereco,
+            self.site.display(),
+            self.banco,
+            self.max_salas,
+            self.sala_ociosa
+                .map(|d| format!("{} min", d.as_secs() / 60))
+                .unwrap_or_else(|| "nunca fecha".to_string()),
+            self.nome_regras,
+            self.log,
+            if self.log_json { "json" } else { "texto" },
+            if self.token_admin.is_some() {
+                "ligado"
+            } else {
+                "desligado"
+            }
+        )
+    }
+}
@@ -0,0 +1,360 @@
+use crate::ranking::abrir_banco;
+use crate::relogio::RelogioTurno;
+use crate::sala::Sala;
+use crate::state::{DeviceId, GlobalState, PlayerId, Room, Saida, ServerState};
+use crate::torneio::{Reserva, Torneios, TorneiosCompartilhados, VinculoTorneio};
+use buracao_core::acoes::{MensagemChat, MsgServidor, PlacarSerie, VotoFimDeJogo};
+use buracao_core::estado::EstadoJogo;
+use buracao_core::lobby::{ConfigEspectadores, ConfigRelogio};
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use warp::ws::Message;
+
+// --- DESLIGAMENTO ---
+// Com SIGTERM ou Ctrl+C o servidor para de aceitar conexões, avisa todas as mesas que
+// vai reiniciar, fecha os WebSockets (sem `Expulso`, então o cliente reconecta sozinho)
+// e guarda cada sala no banco. Na subida seguinte as salas voltam como estavam e o
+// segredo dos tokens também, para a reconexão cair no mesmo assento. Para um deploy
+// o jogador só vê o aviso e alguns segundos de "reconectando". Os torneios vão junto,
+// e as mesas de torneio voltam ligadas a eles.
+//
+// Com `banco = ":memory:"` não há onde guardar: as salas se perdem como antes.
+
+const AVISO: &str = "🔄 O servidor está reiniciando. Você será reconectado em instantes.";
+// Tempo para as mensagens de aviso e os closes saírem antes do processo acabar
+const TEMPO_PARA_ENVIAR: Duration = Duration::from_millis(500);
+// Quanto esperar as requisições HTTP em curso terminarem depois do sinal
+pub const PRAZO_CONEXOES: Duration = Duration::from_secs(5);
+
+const ESQUEMA: &str = "
+CREATE TABLE IF NOT EXISTS salas_salvas (
+    codigo   TEXT    PRIMARY KEY,
+    salva_em INTEGER NOT NULL,
+    sala     TEXT    NOT NULL
+);
+CREATE TABLE IF NOT EXISTS segredo_salvo (
+    id      INTEGER PRIMARY KEY CHECK (id = 1),
+    segredo TEXT    NOT NULL
+);
+CREATE TABLE IF NOT EXISTS torneios_salvos (
+    id       INTEGER PRIMARY KEY CHECK (id = 1),
+    torneios TEXT    NOT NULL
+);
+";
+
+/// O que volta de uma sala depois do reinício. Conexões, buffers de retomada e o
+/// estado do relógio ficam de fora: todo mundo reconecta e recebe a foto completa.
+#[derive(Serialize, Deserialize)]
+struct SalaSalva {
+    nome: String,
+    publica: bool,
+    senha: Option<String>,
+    game_state: EstadoJogo,
+    sessions: HashMap<DeviceId, PlayerId>,
+    player_names: HashMap<PlayerId, String>,
+    perfis: HashMap<PlayerId, i64>,
+    // A numeração continua de onde parou para o cliente não achar que voltou no tempo
+    seq_jogadores: HashMap<PlayerId, u64>,
+    host: Option<PlayerId>,
+    #[serde(default)]
+    token_anfitriao: Option<String>,
+    trancada: bool,
+    banidos: HashSet<DeviceId>,
+    assentos_transferidos: HashSet<DeviceId>,
+    #[serde(default)]
+    robos: HashSet<PlayerId>,
+    relogio: Option<ConfigRelogio>,
+    config_espectadores: ConfigEspectadores,
+    // Sem isso a partida já encerrada seria contada de novo (série, ranking, arquivo)
+    fim_de_jogo: Option<MsgServidor>,
+    votos_fim: HashMap<PlayerId, VotoFimDeJogo>,
+    serie: PlacarSerie,
+    reservas: HashMap<PlayerId, Reserva>,
+    #[serde(default)]
+    torneio: Option<VinculoTorneio>,
+    chat: VecDeque<MensagemChat>,
+}
+
+impl SalaSalva {
+    fn de(room: &Room) -> Self {
+        Self {
+            nome: room.nome.clone(),
+            publica: room.publica,
+            senha: room.senha.clone(),
+            game_state: room.game_state.clone(),
+            sessions: room.sessions.clone(),
+            player_names: room.player_names.clone(),
+            perfis: room.perfis.clone(),
+            seq_jogadores: room.seq_jogadores.clone(),
+            host: room.host,
+            token_anfitriao: room.token_anfitriao.clone(),
+            trancada: room.trancada,
+            banidos: room.banidos.clone(),
+            assentos_transferidos: room.assentos_transferidos.clone(),
+            robos: room.robos.clone(),
+            relogio: room.relogio.as_ref().map(|r| r.config),
+            config_espectadores: room.config_espectadores,
+            fim_de_jogo: room.fim_de_jogo.clone(),
+            votos_fim: room.votos_fim.clone(),
+            serie: room.serie,
+            reservas: room.reservas.clone(),
+            torneio: room.torneio.clone(),
+            chat: room.chat.clone(),
+        }
+    }
+
+    /// A mesa de torneio volta ligada ao torneio; se ele não voltou (registro ilegível),
+    /// vira sala comum, sem assentos reservados.
+    fn em_sala(self, torneios: &TorneiosCompartilhados) -> Room {
+        let mut room = Room::new();
+        room.nome = self.nome;
+        room.publica = self.publica;
+        room.senha = self.senha;
+        room.game_state = self.game_state;
+        room.sessions = self.sessions;
+        room.player_names = self.player_names;
+        room.perfis = self.perfis;
+        room.seq_jogadores = self.seq_jogadores;
+        room.host = self.host;
+        room.token_anfitriao = self.token_anfitriao;
+        room.trancada = self.trancada;
+        room.banidos = self.banidos;
+        room.assentos_transferidos = self.assentos_transferidos;
+        room.robos = self.robos;
+        room.relogio = self.relogio.map(RelogioTurno::new);
+        room.config_espectadores = self.config_espectadores;
+        room.fim_de_jogo = self.fim_de_jogo;
+        room.votos_fim = self.votos_fim;
+        room.serie = self.serie;
+        room.torneio = self.torneio.and_then(|v| v.religar(torneios));
+        if room.torneio.is_some() {
+            room.reservas = self.reservas;
+        }
+        room.chat = self.chat;
+        room
+    }
+}
+
+/// Código da sala e o JSON de `SalaSalva`, como fica na tabela.
+pub type SalaGuardada = (String, String);
+
+/// O que o último desligamento deixou no banco.
+pub struct Guardado {
+    pub salas: Vec<SalaGuardada>,
+    pub segredo: Option<Vec<u8>>,
+    /// JSON da lista de torneios
+    pub torneios: Option<String>,
+}
+
+pub struct SalasSalvas {
+    conn: Mutex<Connection>,
+}
+
+impl SalasSalvas {
+    pub fn abrir(caminho: &str) -> Result<Arc<Self>, String> {
+        let conn = abrir_banco(caminho, "Salas salvas")?;
+        conn.execute_batch(ESQUEMA)
+            .map_err(|e| format!("Salas salvas: não foi possível criar a tabela ({})", e))?;
+        Ok(Arc::new(Self {
+            conn: Mutex::new(conn),
+        }))
+    }
+
+    /// Troca o que estava guardado pelas salas e torneios de agora (e o segredo, se foi
+    /// sorteado).
+    fn guardar(
+        &self,
+        salas: &[SalaGuardada],
+        segredo: Option<&[u8]>,
+        torneios: Option<&str>,
+    ) -> rusqlite::Result<()> {
+        let agora = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let mut conn = self.conn.lock().unwrap();
+        let transacao = conn.transaction()?;
+        transacao.execute("DELETE FROM salas_salvas", [])?;
+        transacao.execute("DELETE FROM segredo_salvo", [])?;
+        transacao.execute("DELETE FROM torneios_salvos", [])?;
+        for (codigo, sala) in salas {
+            transacao.execute(
+                "INSERT INTO salas_salvas (codigo, salva_em, sala) VALUES (?1, ?2, ?3)",
+                params![codigo, agora, sala],
+            )?;
+        }
+        if let Some(segredo) = segredo {
+            transacao.execute(
+                "INSERT INTO segredo_salvo (id, segredo) VALUES (1, ?1)",
+                params![hex::encode(segredo)],
+            )?;
+        }
+        if let Some(torneios) = torneios {
+            transacao.execute(
+                "INSERT INTO torneios_salvos (id, torneios) VALUES (1, ?1)",
+                params![torneios],
+            )?;
+        }
+        transacao.commit()
+    }
+
+    /// Lê e apaga o que foi guardado: cada sala volta uma vez só.
+    pub fn retirar(&self) -> rusqlite::Result<Guardado> {
+        let mut conn = self.conn.lock().unwrap();
+        let transacao = conn.transaction()?;
+        let salas = {
+            let mut consulta = transacao.prepare("SELECT codigo, sala FROM salas_salvas")?;
+            consulta
+                .query_map([], |linha| Ok((linha.get(0)?, linha.get(1)?)))?
+                .collect::<rusqlite::Result<Vec<SalaGuardada>>>()?
+        };
+        let segredo: Option<String> = transacao
+            .query_row("SELECT segredo FROM segredo_salvo WHERE id = 1", [], |l| {
+                l.get(0)
+            })
+            .ok();
+        let torneios: Option<String> = transacao
+            .query_row(
+                "SELECT torneios FROM torneios_salvos WHERE id = 1",
+                [],
+                |l| l.get(0),
+            )
+            .ok();
+        transacao.execute("DELETE FROM salas_salvas", [])?;
+        transacao.execute("DELETE FROM segredo_salvo", [])?;
+        transacao.execute("DELETE FROM torneios_salvos", [])?;
+        transacao.commit()?;
+        Ok(Guardado {
+            salas,
+            segredo: segredo.and_then(|s| hex::decode(s).ok()),
+            torneios,
+        })
+    }
+}
+
+/// Coloca de volta no ar os torneios e as salas guardados no último desligamento.
+/// Os torneios vêm antes, para as mesas de torneio se religarem a eles.
+pub fn restaurar(server: &mut ServerState, salas: Vec<SalaGuardada>, torneios: Option<String>) {
+    if let Some(json) = torneios {
+        match serde_json::from_str::<Torneios>(&json) {
+            Ok(torneios) => *server.torneios.lock().unwrap() = torneios,
+            Err(e) => tracing::warn!(erro = %e, "torneios salvos ilegíveis, descartados"),
+        }
+    }
+
+    let mut restauradas = 0;
+    for (codigo, json) in salas {
+        let salva: SalaSalva = match serde_json::from_str(&json) {
+            Ok(salva) => salva,
+            Err(e) => {
+                tracing::warn!(sala = %codigo, erro = %e, "sala salva ilegível, descartada");
+                continue;
+            }
+        };
+        let mut room = salva.em_sala(&server.torneios);
+        room.ranking = Some(server.ranking.clone());
+        room.arquivo = Some(server.arquivo.clone());
+        // Ninguém conectado ainda: a limpeza conta a ociosidade a partir de agora
+        room.vazia_desde = Some(std::time::Instant::now());
+        server
+            .rooms
+            .insert(codigo.clone(), Sala::abrir(room, &codigo));
+        restauradas += 1;
+    }
+    if restauradas > 0 {
+        tracing::info!(
+            salas = restauradas,
+            "salas restauradas do último desligamento"
+        );
+    }
+}
+
+/// Resolve no primeiro SIGTERM ou Ctrl+C.
+pub async fn sinal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    #[cfg(unix)]
+    let term = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sinal) => {
+                sinal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let term = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => tracing::info!("Ctrl+C recebido, desligando"),
+        _ = term => tracing::info!("SIGTERM recebido, desligando"),
+    }
+}
+
+/// Avisa e desconecta todo mundo e guarda as salas. O servidor já parou de aceitar
+/// conexões; cada sala fica marcada como fechada, então nada muda depois da foto.
+pub async fn encerrar(server: &GlobalState) {
+    let aviso = Arc::new(MsgServidor::Notificacao(AVISO.to_string()));
+
+    let abertas: Vec<(String, Sala)> = server
+        .rooms
+        .iter()
+        .map(|par| (par.key().clone(), par.value().clone()))
+        .collect();
+    let mut salas = Vec::new();
+    for (codigo, sala) in abertas {
+        let aviso = aviso.clone();
+        let foto = sala
+            .executar(move |room| {
+                room.fechada = true;
+                room.broadcast(&aviso);
+                for tx in room.clients.values() {
+                    let _ = tx.send(Saida::Ws(Message::close()));
+                }
+                room.desconectar_espectadores();
+                serde_json::to_string(&SalaSalva::de(room))
+            })
+            .await;
+        match foto {
+            Some(Ok(json)) => salas.push((codigo, json)),
+            Some(Err(e)) => tracing::warn!(sala = %codigo, erro = %e, "sala não pôde ser salva"),
+            None => tracing::warn!(sala = %codigo, "sala não respondeu, não será salva"),
+        }
+    }
+
+    // Depois das salas: todas fechadas, nenhuma lança mais resultado no torneio
+    let torneios = match serde_json::to_string(&*server.torneios.lock().unwrap()) {
+        Ok(json) => Some(json),
+        Err(e) => {
+            tracing::warn!(erro = %e, "torneios não puderam ser salvos");
+            None
+        }
+    };
+
+    if server.config.banco == crate::config::BANCO_EM_MEMORIA {
+        tracing::info!(
+            salas = salas.len(),
+            "banco em memória: as salas não serão guardadas"
+        );
+    } else {
+        // Segredo configurado volta sozinho; o sorteado precisa ser guardado
+        let segredo = server
+            .config
+            .segredo
+            .is_none()
+            .then_some(server.segredo.as_slice());
+        match server
+            .salas_salvas
+            .guardar(&salas, segredo, torneios.as_deref())
+        {
+            Ok(()) => tracing::info!(salas = salas.len(), "salas guardadas para o reinício"),
+            Err(e) => tracing::error!(erro = %e, "não foi possível guardar as salas"),
+        }
+    }
+
+    tokio::time::sleep(TEMPO_PARA_ENVIAR).await;
+}
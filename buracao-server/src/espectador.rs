@@ -0,0 +1,298 @@
+use crate::chat;
+use crate::presenca::ConfigHeartbeat;
+use crate::protecao::{self, LimiteConexao};
+use crate::sala::Sala;
+use crate::sessao;
+use crate::state::{PlayerId, Room, Saida, Sender};
+use buracao_core::acoes::{
+    DadosLogin, ModoEspectador, MotivoRecusa, MsgCliente, MsgServidor, VERSAO_PROTOCOLO,
+};
+use futures::StreamExt;
+use futures::stream::SplitStream;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use warp::ws::{Message, WebSocket};
+
+// --- ESPECTADORES ---
+// Quem assiste não ocupa assento: recebe a visão pública da mesa (ou, como treinador,
+// a mão de um jogador), sem seq nem replay. Ao reconectar, recebe a foto de novo.
+// Os ids ficam fora da faixa dos assentos para não se confundirem com jogadores.
+
+pub const PRIMEIRO_ESPECTADOR: u32 = 100;
+
+pub struct Espectador {
+    pub nome: String,
+    pub treinando: Option<PlayerId>,
+    // Canal para o cliente, já passando pelo atraso da sala (se houver)
+    pub(crate) tx: Sender,
+}
+
+/// Canal que segura cada mensagem por `atraso` antes de entregar, na ordem em que chegou.
+fn canal_com_atraso(destino: Sender, atraso: Duration) -> Sender {
+    if atraso.is_zero() {
+        return destino;
+    }
+    let (tx, mut rx) = mpsc::unbounded_channel::<Saida>();
+    tokio::spawn(async move {
+        let mut fila: VecDeque<(Instant, Saida)> = VecDeque::new();
+        loop {
+            let prazo = fila.front().map(|(chegada, _)| *chegada + atraso);
+            tokio::select! {
+                recebida = rx.recv() => match recebida {
+                    Some(msg) => fila.push_back((Instant::now(), msg)),
+                    None => break,
+                },
+                _ = tokio::time::sleep_until(prazo.unwrap_or_else(Instant::now).into()), if prazo.is_some() => {
+                    if let Some((_, msg)) = fila.pop_front()
+                        && destino.send(msg).is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    tx
+}
+
+impl Room {
+    pub fn enviar_espectador(&self, id: u32, msg: &MsgServidor) {
+        if let Some(espectador) = self.espectadores.get(&id) {
+            let _ = espectador.tx.send(Saida::msg(msg));
+        }
+    }
+
+    /// Fecha a conexão de todos que assistem (a sala vai fechar ou o servidor vai
+    /// reiniciar). O aviso sai antes pelo `broadcast`, que já chega a eles.
+    pub fn desconectar_espectadores(&mut self) {
+        for (_, espectador) in self.espectadores.drain() {
+            let _ = espectador.tx.send(Saida::Ws(Message::close()));
+        }
+    }
+
+    /// Mesma mensagem para todos que assistem (uma cópia só, compartilhada).
+    pub fn enviar_espectadores(&self, msg: &MsgServidor) {
+        if self.espectadores.is_empty() {
+            return;
+        }
+        let msg = Arc::new(msg.clone());
+        for espectador in self.espectadores.values() {
+            let _ = espectador.tx.send(Saida::Msg {
+                seq: None,
+                msg: msg.clone(),
+            });
+        }
+    }
+
+    /// Foto completa da mesa para quem assiste (entrada ou Resync).
+    pub fn enviar_estado_espectador(&mut self, id: u32) {
+        if self.espectadores.contains_key(&id) {
+            let tempo = self.tempo_turno();
+            self.reenviar_visao(id, tempo);
+        }
+    }
+
+    pub fn nome_espectador(&self, id: u32) -> Option<String> {
+        self.espectadores.get(&id).map(|e| e.nome.clone())
+    }
+}
+
+/// Confere se a sala aceita o espectador e registra ele. Devolve o id dado a ele.
+fn entrar(
+    room: &mut Room,
+    login: &DadosLogin,
+    modo: ModoEspectador,
+    tx: &Sender,
+) -> Result<u32, MotivoRecusa> {
+    // Fechou entre o login achar a sala e chegar aqui
+    if room.fechada {
+        return Err(MotivoRecusa::SalaFechada);
+    }
+    let config = room.config_espectadores;
+    if !config.permitidos {
+        return Err(MotivoRecusa::SemEspectadores);
+    }
+    if room.banidos.contains(&login.device_id) {
+        return Err(MotivoRecusa::Banido);
+    }
+    if let Some(senha_sala) = &room.senha {
+        let senha_informada = login.senha.as_deref().unwrap_or("");
+        if !sessao::senha_confere(senha_sala, senha_informada) {
+            return Err(MotivoRecusa::SenhaIncorreta);
+        }
+    }
+
+    let treinando = match modo {
+        ModoEspectador::Publico => None,
+        ModoEspectador::Treinador(pid) => {
+            if !config.treinador || !room.assento_ocupado(pid) {
+                return Err(MotivoRecusa::TreinadorNaoPermitido);
+            }
+            Some(pid)
+        }
+    };
+
+    // O treinador precisa ver a mão na hora; o atraso vale para a transmissão pública
+    let atraso = match treinando {
+        Some(_) => Duration::ZERO,
+        None => Duration::from_secs(config.atraso_segundos as u64),
+    };
+
+    let id = room.proximo_espectador;
+    room.proximo_espectador += 1;
+    room.espectadores.insert(
+        id,
+        Espectador {
+            nome: login.nome.clone(),
+            treinando,
+            tx: canal_com_atraso(tx.clone(), atraso),
+        },
+    );
+    Ok(id)
+}
+
+/// Atende a conexão de um espectador até ela fechar.
+pub async fn atender(
+    sala: Sala,
+    login: DadosLogin,
+    modo: ModoEspectador,
+    tx: Sender,
+    mut ws_rx: SplitStream<WebSocket>,
+    heartbeat: ConfigHeartbeat,
+    mut limite: LimiteConexao,
+) {
+    let entrada = {
+        let tx = tx.clone();
+        sala.executar(move |room| {
+            let id = entrar(room, &login, modo, &tx)?;
+
+            let treinando = room.espectadores.get(&id).and_then(|e| e.treinando);
+            tracing::info!(
+                nome = %login.nome,
+                espectador = id,
+                ?treinando,
+                "espectador entrou"
+            );
+
+            // Boas-vindas sem atraso: o cliente já sabe que é espectador enquanto espera a mesa
+            let _ = tx.send(Saida::msg(&MsgServidor::BoasVindasEspectador {
+                versao_protocolo: VERSAO_PROTOCOLO,
+                treinando,
+                atraso_segundos: if treinando.is_some() {
+                    0
+                } else {
+                    room.config_espectadores.atraso_segundos
+                },
+            }));
+
+            room.enviar_espectador(id, &MsgServidor::Nomes(room.player_names.clone()));
+            room.enviar_estado_espectador(id);
+            let historico = chat::historico_publico(room);
+            room.enviar_espectador(id, &historico);
+            for msg in room.mensagens_fim_de_jogo() {
+                room.enviar_espectador(id, &msg);
+            }
+
+            if let Some(pid) = treinando {
+                room.enviar(
+                    pid,
+                    &MsgServidor::Notificacao(format!(
+                        "🎓 {} está acompanhando a sua mão como treinador.",
+                        login.nome
+                    )),
+                );
+            }
+            let info = room.info_sala();
+            room.broadcast(&info);
+            Ok(id)
+        })
+        .await
+        .unwrap_or(Err(MotivoRecusa::SalaFechada))
+    };
+    let meu_id = match entrada {
+        Ok(id) => id,
+        Err(motivo) => {
+            tracing::info!(?motivo, "espectador recusado");
+            let _ = tx.send(Saida::msg(&MsgServidor::LoginRecusado(motivo)));
+            let _ = tx.send(Saida::Ws(Message::close()));
+            return;
+        }
+    };
+
+    let mut ping = tokio::time::interval(heartbeat.intervalo);
+    ping.tick().await;
+    let mut ultimo_sinal = Instant::now();
+
+    loop {
+        let msg = tokio::select! {
+            recebida = ws_rx.next() => match recebida {
+                Some(Ok(msg)) => msg,
+                _ => break,
+            },
+            _ = ping.tick() => {
+                if ultimo_sinal.elapsed() > heartbeat.timeout {
+                    break;
+                }
+                let _ = tx.send(Saida::Ws(Message::ping(Vec::new())));
+                continue;
+            }
+        };
+        ultimo_sinal = Instant::now();
+        if msg.is_close() {
+            break;
+        }
+        if msg.is_ping() || msg.is_pong() {
+            continue;
+        }
+        let recebida = match protecao::ler(&mut limite, &msg) {
+            Ok(recebida) => recebida,
+            Err(erro) => {
+                if protecao::recusar(&mut limite, &tx, erro) {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        let resposta = sala
+            .executar(move |room| match recebida {
+                MsgCliente::Resync => {
+                    room.enviar_estado_espectador(meu_id);
+                    None
+                }
+                MsgCliente::Chat { texto, apenas_time } => {
+                    if !room.config_espectadores.chat {
+                        Some("Espectadores não podem falar no chat desta sala.".to_string())
+                    } else if apenas_time {
+                        Some("Espectadores não têm chat de time.".to_string())
+                    } else {
+                        chat::processar(room, meu_id, &texto, false).err()
+                    }
+                }
+                // Reações aparecem no assento de quem reagiu: espectador não tem assento
+                MsgCliente::Reagir { .. } => None,
+                MsgCliente::Acao(_) | MsgCliente::Sala(_) | MsgCliente::VotoFimDeJogo(_) => {
+                    Some("Espectadores não podem jogar nem moderar a sala.".to_string())
+                }
+                MsgCliente::Login(_) => Some("Você já está conectado nesta sala.".to_string()),
+            })
+            .await
+            .flatten();
+        // Resposta ao próprio espectador não passa pelo atraso da transmissão
+        if let Some(erro) = resposta {
+            let _ = tx.send(Saida::msg(&MsgServidor::Erro(erro)));
+        }
+    }
+
+    sala.executar(move |room| {
+        room.espectadores.remove(&meu_id);
+        room.esquecer_visao(meu_id);
+        room.limite_chat.remove(&meu_id);
+        let info = room.info_sala();
+        room.broadcast(&info);
+    })
+    .await;
+    tracing::info!(espectador = meu_id, "espectador saiu");
+}
@@ -1,178 +1,578 @@
-use crate::state::{GlobalState, Room};
-use buracao_core::acoes::{AcaoJogador, MsgServidor};
+use crate::chat;
+use crate::espectador;
+use crate::logs;
+use crate::metricas::METRICAS;
+use crate::moderacao;
+use crate::presenca;
+use crate::protecao::{self, LimiteConexao};
+use crate::revanche;
+use crate::sala::Sala;
+use crate::sessao;
+use crate::state::{GlobalState, PlayerId, Room, Saida, Sender, ServerState};
+use buracao_core::acoes::{DadosLogin, MotivoRecusa, MsgCliente, MsgServidor, VERSAO_PROTOCOLO};
 use futures::{SinkExt, StreamExt};
-use serde::{Deserialize, Serialize}; // Adicionado Serialize
 use std::sync::Arc;
-use tokio::sync::{RwLock, mpsc};
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tracing::Instrument;
 use warp::ws::{Message, WebSocket};
 
-#[derive(Deserialize, Debug)]
-struct MensagemLogin {
-    // tipo: String,
-    device_id: String,
-    nome: String,
-    sala: String,
+// Quantas vezes o login procura a sala de novo se ela fechar bem na hora
+const TENTATIVAS_LOGIN: usize = 3;
+
+/// Avisa a sala inteira quem é quem, quem é o anfitrião e se a sala está trancada.
+fn anunciar_sala(room: &mut Room) {
+    // Isso permite que o frontend saiba que o ID 0 é "Vitor", o ID 1 é "João", etc.
+    room.broadcast(&MsgServidor::Nomes(room.player_names.clone()));
+    let info = room.info_sala();
+    room.broadcast(&info);
+    presenca::anunciar(room);
+}
+
+/// Envio direto pelo canal, sem seq: só para o que acontece antes de o jogador ter assento.
+fn enviar(tx: &Sender, msg: &MsgServidor) {
+    let _ = tx.send(Saida::msg(msg));
 }
 
-// --- NOVO: Struct para enviar a lista de nomes ao Frontend ---
-// Isso permite que o frontend saiba que o ID 0 é "Vitor", o ID 1 é "João", etc.
-#[derive(Serialize)]
-struct EventoNomes {
-    tipo: String, // Será sempre "NomesJogadores"
-    mapa: std::collections::HashMap<u32, String>,
+/// Recusa o login com um motivo que o cliente entende e fecha a conexão.
+fn recusar(tx: &Sender, motivo: MotivoRecusa) {
+    enviar(tx, &MsgServidor::LoginRecusado(motivo));
+    let _ = tx.send(Saida::Ws(Message::close()));
 }
 
+/// Atende uma conexão do começo ao fim, dentro do span dela (ver logs.rs).
 pub async fn handle_connection(ws: WebSocket, global_state: GlobalState) {
-    let (mut ws_tx, mut ws_rx) = ws.split();
-    let (tx, mut rx) = mpsc::unbounded_channel();
+    atender(ws, global_state)
+        .instrument(logs::span_conexao())
+        .await
+}
 
-    // Tarefa para encaminhar mensagens do servidor -> cliente
-    tokio::task::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            if ws_tx.send(msg).await.is_err() {
-                break;
+/// Acha a sala do código ou abre uma nova com ele (só para quem vai jogar: espectador
+/// não abre sala).
+fn sala_do_login(server: &ServerState, login: &DadosLogin) -> Result<Sala, MotivoRecusa> {
+    if let Some(sala) = server.rooms.get(&login.sala) {
+        return Ok(sala.clone());
+    }
+    if login.espectador.is_some() {
+        return Err(MotivoRecusa::SalaInexistente);
+    }
+    // Conferido antes do `entry`: contar as salas lê o mapa inteiro
+    if server.cabem_salas(1).is_err() {
+        tracing::warn!("limite de salas atingido, sala nova recusada");
+        return Err(MotivoRecusa::LimiteDeSalas);
+    }
+    let sala = server
+        .rooms
+        .entry(login.sala.clone())
+        .or_insert_with(|| {
+            tracing::info!("sala nova criada pelo código");
+            let mut r = Room::new();
+            r.nome = login.sala.clone();
+            r.game_state.regras = server.config.regras.clone();
+            // Quem cria a sala pelo código já define a senha dela
+            r.senha = login.senha.clone().filter(|s| !s.is_empty());
+            r.game_state.dar_cartas();
+            r.ranking = Some(server.ranking.clone());
+            r.arquivo = Some(server.arquivo.clone());
+            Sala::abrir(r, &login.sala)
+        })
+        .clone();
+    Ok(sala)
+}
+
+/// Senta o jogador (novo ou voltando) e manda tudo o que ele precisa para jogar.
+/// Roda dentro da sala; devolve o assento. `perfil` é o perfil já existente da
+/// identidade do login, se houver.
+fn registrar(
+    room: &mut Room,
+    login: &DadosLogin,
+    perfil: Option<i64>,
+    tx: &Sender,
+    segredo: &[u8],
+) -> Result<PlayerId, MotivoRecusa> {
+    let my_player_id: PlayerId;
+    let mut reconexao = false;
+
+    if let Some(&id) = room.sessions.get(&login.device_id) {
+        // O device_id sozinho não prova nada: volta ao assento quem traz o token assinado
+        // dele ou quem recebeu o assento da administração (ainda sem token)
+        let transferido = room.assentos_transferidos.remove(&login.device_id);
+        let token_ok = transferido
+            || login.token.as_deref().is_some_and(|token| {
+                sessao::validar_token(segredo, &login.sala, &login.device_id, id, token)
+            });
+        if !token_ok {
+            tracing::warn!(assento = id, "reconexão recusada: token inválido");
+            return Err(MotivoRecusa::SessaoInvalida);
+        }
+        tracing::info!(assento = id, "reconexão");
+        my_player_id = id;
+        reconexao = true;
+    } else {
+        // Novo jogador
+        if room.banidos.contains(&login.device_id) {
+            return Err(MotivoRecusa::Banido);
+        }
+        if room.trancada {
+            return Err(MotivoRecusa::SalaTrancada);
+        }
+        if let Some(senha_sala) = &room.senha {
+            let senha_informada = login.senha.as_deref().unwrap_or("");
+            if !sessao::senha_confere(senha_sala, senha_informada) {
+                return Err(MotivoRecusa::SenhaIncorreta);
             }
         }
-    });
 
-    println!("⏳ Nova conexão... aguardando Login.");
+        // Um perfil, um assento: senão a mesma pessoa joga por dois (e soma ranking dobrado)
+        if perfil.is_some_and(|perfil| room.perfis.values().any(|&p| p == perfil)) {
+            return Err(MotivoRecusa::PerfilJaSentado);
+        }
 
-    let login_data: MensagemLogin = match ws_rx.next().await {
-        Some(Ok(msg)) => match msg.to_str() {
-            Ok(texto) => match serde_json::from_str::<MensagemLogin>(texto) {
-                Ok(dados) => dados,
-                Err(_) => {
-                    println!("❌ JSON inválido recebido.");
-                    return;
-                }
-            },
-            Err(_) => return,
-        },
-        _ => return,
-    };
+        // Mesa de torneio: cada assento espera o jogador escalado para ele, que prova
+        // quem é com a chave de assento no lugar da senha (a sala não tem senha)
+        let assento = if room.reservas.is_empty() {
+            room.proximo_assento_livre()
+        } else {
+            room.assento_reservado(&login.nome, login.senha.as_deref().unwrap_or(""))
+        };
+        let Some(next_id) = assento else {
+            return Err(if room.reservas.is_empty() {
+                MotivoRecusa::SalaCheia
+            } else {
+                MotivoRecusa::AssentoReservado
+            });
+        };
+        my_player_id = next_id;
+        // Salva na sessão para o futuro
+        room.sessions.insert(login.device_id.clone(), my_player_id);
+        // Assento que o robô segurava volta a ter dono
+        room.robos.remove(&my_player_id);
+        // Já reserva o perfil: outro login com ele chega antes da identificação abaixo
+        if let Some(perfil) = perfil {
+            room.perfis.insert(my_player_id, perfil);
+        }
+
+        // Anfitrião: quem criou a sala pelo lobby (o token vale uma vez) ou o primeiro a
+        // sentar numa sala sem anfitrião. Só em assento novo: reconectar não devolve o
+        // posto a quem o passou adiante
+        let criador = room.token_anfitriao.as_deref().is_some_and(|esperado| {
+            let informado = login.token_anfitriao.as_deref().unwrap_or("");
+            sessao::senha_confere(esperado, informado)
+        });
+        if criador {
+            room.token_anfitriao = None;
+            tracing::info!(
+                assento = my_player_id,
+                "criador da sala entrou como anfitrião"
+            );
+        }
+        if criador || room.host.is_none() {
+            room.host = Some(my_player_id);
+        }
+    }
+
+    // --- NOVO: SALVAR O NOME DO JOGADOR ---
+    // Sempre atualiza o nome (caso ele tenha mudado no login)
+    room.player_names.insert(my_player_id, login.nome.clone());
 
-    println!(
-        "🔑 Login na sala '{}': {} ({})",
-        login_data.sala, login_data.nome, login_data.device_id
+    // Retomada: o cliente diz até onde recebeu e, se o buffer ainda cobre o resto,
+    // recebe só o que perdeu em vez da foto completa
+    let retomar_de = login
+        .ultimo_seq
+        .filter(|&seq| reconexao && room.pode_retomar(my_player_id, seq));
+
+    enviar(
+        tx,
+        &MsgServidor::BoasVindas {
+            id_jogador: my_player_id,
+            versao_protocolo: VERSAO_PROTOCOLO,
+            retomada: retomar_de.is_some(),
+        },
     );
 
-    // 2. ENCONTRA OU CRIA A SALA
-    let room_ref: Arc<RwLock<Room>>;
+    // SEMPRE atualiza o canal de comunicação (para novos e reconexões)
+    room.clients.insert(my_player_id, tx.clone());
 
-    {
-        let mut server = global_state.write().await;
+    // Token (re)emitido a cada login: o cliente guarda e apresenta na próxima reconexão
+    let token = sessao::gerar_token(segredo, &login.sala, &login.device_id, my_player_id);
+    if let Some(ultimo_seq) = retomar_de {
+        let reenviadas = room.reenviar_desde(my_player_id, ultimo_seq);
+        tracing::info!(ultimo_seq, reenviadas, "retomada da conexão");
+    }
 
-        // Verifica se a sala já existe ou cria nova
-        if let Some(existing_room) = server.rooms.get(&login_data.sala) {
-            room_ref = existing_room.clone();
-        } else {
-            println!("🏠 Criando SALA NOVA: {}", login_data.sala);
-            let mut r = Room::new();
-            r.game_state.dar_cartas();
-            let new_room = Arc::new(RwLock::new(r));
+    room.enviar(my_player_id, &MsgServidor::Sessao { token });
 
-            server
-                .rooms
-                .insert(login_data.sala.clone(), new_room.clone());
-            room_ref = new_room;
+    if retomar_de.is_none() {
+        // Foto completa, montada na tarefa de visões (os deltas seguintes partem dela)
+        room.enviar_estado(my_player_id);
+
+        // Mensagens anteriores do chat (respeitando o canal do time)
+        let historico = chat::historico_para(room, my_player_id);
+        room.enviar(my_player_id, &historico);
+
+        // Partida já acabou: mostra o resumo e a votação em andamento
+        for msg in room.mensagens_fim_de_jogo() {
+            room.enviar(my_player_id, &msg);
         }
     }
 
-    let my_player_id: u32;
+    // --- ENVIAR LISTA DE NOMES PARA TODOS ---
+    // Como entrou gente (ou reconectou), avisamos a sala inteira quem é quem.
+    anunciar_sala(room);
+    Ok(my_player_id)
+}
 
-    // 3. REGISTRA O JOGADOR NA SALA
-    {
-        let mut room = room_ref.write().await;
+/// Uma mensagem do jogador já sentado, dentro da sala. Devolve o assento atual dele, ou
+/// `None` se a conexão não fala mais por assento nenhum.
+fn tratar_mensagem(
+    room: &mut Room,
+    login: &DadosLogin,
+    segredo: &[u8],
+    recebida: MsgCliente,
+) -> Option<PlayerId> {
+    // Se o anfitrião liberou/expulsou este assento, a conexão não fala mais por ele.
+    // O número do assento pode mudar (troca de duplas na revanche), então é relido aqui.
+    let my_player_id = *room.sessions.get(&login.device_id)?;
+    room.jogador_ativo(my_player_id);
 
-        if let Some(&id) = room.sessions.get(&login_data.device_id) {
-            println!("🔄 Reconexão detectada: ID {}", id);
-            my_player_id = id;
-        } else {
-            // Novo jogador
-            let next_id = room.sessions.len() as u32;
-            if next_id >= 4 {
-                let _ = tx.send(Message::text(r#"{"erro": "Sala cheia!"}"#));
-                return;
+    let acao = match recebida {
+        MsgCliente::Acao(acao) => acao,
+
+        // Comandos de moderação valem a qualquer momento, fora do controle de turno
+        MsgCliente::Sala(comando) => {
+            match moderacao::executar(room, my_player_id, comando) {
+                Ok(msg_sucesso) => {
+                    anunciar_sala(room);
+                    room.enviar(my_player_id, &MsgServidor::Notificacao(msg_sucesso));
+                }
+                Err(erro) => room.enviar(my_player_id, &MsgServidor::Erro(erro)),
+            }
+            return Some(my_player_id);
+        }
+
+        // Chat e reações não dependem de quem está na vez
+        MsgCliente::Chat { texto, apenas_time } => {
+            if let Err(erro) = chat::processar(room, my_player_id, &texto, apenas_time) {
+                room.enviar(my_player_id, &MsgServidor::Erro(erro));
             }
-            my_player_id = next_id;
-            // Salva na sessão para o futuro
-            room.sessions
-                .insert(login_data.device_id.clone(), my_player_id);
+            return Some(my_player_id);
+        }
+        MsgCliente::Reagir { reacao } => {
+            chat::processar_reacao(room, my_player_id, reacao);
+            return Some(my_player_id);
         }
 
-        // --- NOVO: SALVAR O NOME DO JOGADOR ---
-        // Sempre atualiza o nome (caso ele tenha mudado no login)
-        room.player_names
-            .insert(my_player_id, login_data.nome.clone());
+        MsgCliente::VotoFimDeJogo(voto) => {
+            match revanche::votar(room, my_player_id, voto, segredo, &login.sala) {
+                // Quem saiu pode ter deixado o anfitrião ou um assento vago
+                Ok(()) => anunciar_sala(room),
+                Err(erro) => room.enviar(my_player_id, &MsgServidor::Erro(erro)),
+            }
+            return Some(my_player_id);
+        }
 
-        // SEMPRE atualiza o canal de comunicação (para novos e reconexões)
-        room.clients.insert(my_player_id, tx.clone());
+        MsgCliente::Resync => {
+            room.enviar_estado(my_player_id);
+            return Some(my_player_id);
+        }
 
-        // Envia estado inicial imediato
-        let visao = room.game_state.gerar_visao_para_jogador(my_player_id);
-        if let Ok(msg) = serde_json::to_string(&MsgServidor::Estado(visao)) {
-            let _ = tx.send(Message::text(msg));
+        MsgCliente::Login(_) => {
+            room.enviar(
+                my_player_id,
+                &MsgServidor::Erro("Você já está conectado nesta sala.".to_string()),
+            );
+            return Some(my_player_id);
         }
+    };
 
-        // --- NOVO: ENVIAR LISTA DE NOMES PARA TODOS ---
-        // Como entrou gente (ou reconectou), avisamos a sala inteira quem é quem.
-        let evento_nomes = EventoNomes {
-            tipo: "NomesJogadores".to_string(),
-            mapa: room.player_names.clone(),
-        };
+    let versao = room.game_state.versao;
+    let resultado = tracing::info_span!("acao", tipo = acao.nome()).in_scope(|| {
+        tracing::debug!(?acao, "ação recebida");
+        let tipo = acao.nome();
+        let resultado = room.game_state.realizar_acao(my_player_id, acao);
+        METRICAS.acao(tipo, &resultado);
+        match &resultado {
+            Ok(_) => tracing::info!(versao = room.game_state.versao, "ação aceita"),
+            Err(erro) => tracing::info!(%erro, "ação recusada"),
+        }
+        resultado
+    });
+
+    match resultado {
+        Ok(msg_sucesso) => {
+            room.medir_turno();
 
-        if let Ok(json_nomes) = serde_json::to_string(&evento_nomes) {
-            // Manda para todos os clientes conectados na sala
-            for client_tx in room.clients.values() {
-                let _ = client_tx.send(Message::text(json_nomes.clone()));
+            // 1. Cada jogador recebe o delta da própria visão
+            room.propagar_estado();
+
+            // 2. Notificação de sucesso só para quem jogou
+            room.enviar(my_player_id, &MsgServidor::Notificacao(msg_sucesso));
+        }
+        Err(erro) => {
+            // A compra com o monte vazio é recusada mas muda a mesa (pode até encerrar a mão)
+            if room.game_state.versao != versao {
+                room.propagar_estado();
             }
+            // Erro só para quem jogou
+            room.enviar(my_player_id, &MsgServidor::Erro(erro.mensagem));
         }
     }
+    Some(my_player_id)
+}
 
-    // 4. LOOP DO JOGO
-    while let Some(Ok(msg)) = ws_rx.next().await {
-        let texto = match msg.to_str() {
-            Ok(t) => t,
-            Err(_) => continue, // ignora mensagens não-texto / inválidas
-        };
+async fn atender(ws: WebSocket, global_state: GlobalState) {
+    let (mut ws_tx, mut ws_rx) = ws.split();
+    let (tx, mut rx) = mpsc::unbounded_channel::<Saida>();
 
-        let acao: AcaoJogador = match serde_json::from_str(texto) {
-            Ok(a) => a,
-            Err(_) => continue, // ignora JSON inválido
-        };
+    // Tarefa para encaminhar mensagens do servidor -> cliente (e montar o JSON, fora da sala)
+    tokio::task::spawn(async move {
+        while let Some(saida) = rx.recv().await {
+            let Some(frame) = saida.em_frame() else {
+                continue;
+            };
+            if ws_tx.send(frame).await.is_err() {
+                METRICAS.falha_envio();
+                break;
+            }
+        }
+    });
 
-        let mut room = room_ref.write().await;
+    tracing::debug!("nova conexão, aguardando login");
+    let mut limite = LimiteConexao::new(global_state.config.limites);
 
-        let resultado = room.game_state.realizar_acao(my_player_id, acao);
+    let mut login_data: DadosLogin = match ws_rx.next().await {
+        Some(Ok(msg)) => match protecao::ler(&mut limite, &msg) {
+            Ok(MsgCliente::Login(dados)) => dados,
+            recebida => {
+                tracing::info!(erro = ?recebida.err(), "primeira mensagem não é um login válido");
+                recusar(&tx, MotivoRecusa::MensagemInvalida);
+                return;
+            }
+        },
+        _ => return,
+    };
+
+    // Build antiga (ou nova demais) do cliente: melhor recusar do que falhar em silêncio
+    if login_data.versao != VERSAO_PROTOCOLO {
+        tracing::info!(
+            cliente = login_data.versao,
+            servidor = VERSAO_PROTOCOLO,
+            "protocolo incompatível"
+        );
+        recusar(
+            &tx,
+            MotivoRecusa::VersaoIncompativel {
+                servidor: VERSAO_PROTOCOLO,
+                cliente: login_data.versao,
+            },
+        );
+        return;
+    }
 
-        match resultado {
-            Ok(msg_sucesso) => {
-                // 1. Broadcast do Estado para TODOS
-                for (pid, client_tx) in room.clients.iter() {
-                    let visao = room.game_state.gerar_visao_para_jogador(*pid);
-                    let envelope = MsgServidor::Estado(visao);
+    // Código de sala não diferencia maiúsculas: o lobby sorteia e procura em maiúsculas
+    login_data.sala = login_data.sala.to_uppercase();
 
-                    if let Ok(json) = serde_json::to_string(&envelope) {
-                        let _ = client_tx.send(Message::text(json));
-                    }
+    let span = tracing::Span::current();
+    span.record("sala", login_data.sala.as_str());
+    span.record("device", login_data.device_id.as_str());
+    tracing::info!(nome = %login_data.nome, "login");
+
+    let segredo = Arc::new(global_state.segredo.clone());
+    let heartbeat = global_state.heartbeat;
+    let ranking = global_state.ranking.clone();
+    let login = Arc::new(login_data);
+
+    // Perfil que a identidade já tem (sem criar nenhum): o mesmo perfil não senta duas vezes
+    let perfil_existente = match login.identidade.clone().filter(|c| !c.is_empty()) {
+        None => None,
+        Some(chave) => {
+            let ranking = ranking.clone();
+            match tokio::task::spawn_blocking(move || ranking.buscar(&chave)).await {
+                Ok(Ok(perfil)) => perfil,
+                Ok(Err(e)) => {
+                    tracing::warn!(erro = %e, "não foi possível consultar o perfil");
+                    None
                 }
+                Err(e) => {
+                    tracing::warn!(erro = %e, "não foi possível consultar o perfil");
+                    None
+                }
+            }
+        }
+    };
+
+    // 2. ENCONTRA OU CRIA A SALA E 3. REGISTRA O JOGADOR NELA
+    // A sala pode fechar (limpeza, administração) entre achar e entrar: procura de novo
+    let mut entrada = None;
+    for _ in 0..TENTATIVAS_LOGIN {
+        let sala = match sala_do_login(&global_state, &login) {
+            Ok(sala) => sala,
+            Err(motivo) => {
+                recusar(&tx, motivo);
+                return;
+            }
+        };
+
+        // Quem já tem assento volta como jogador mesmo que peça para assistir
+        if let Some(modo) = login.espectador {
+            let device = login.device_id.clone();
+            let sentado = sala
+                .executar(move |room| (!room.fechada).then(|| room.sessions.contains_key(&device)))
+                .await
+                .flatten();
+            let Some(sentado) = sentado else {
+                tracing::debug!("sala fechou durante o login, procurando de novo");
+                continue;
+            };
+            if !sentado {
+                let login = Arc::unwrap_or_clone(login);
+                espectador::atender(sala, login, modo, tx, ws_rx, heartbeat, limite).await;
+                return;
+            }
+        }
 
-                // 2. Notificação de sucesso só para quem jogou
-                if let Ok(json) = serde_json::to_string(&MsgServidor::Notificacao(msg_sucesso)) {
-                    let _ = tx.send(Message::text(json));
+        let registro = {
+            let (login, tx, segredo) = (login.clone(), tx.clone(), segredo.clone());
+            sala.executar(move |room| {
+                (!room.fechada).then(|| registrar(room, &login, perfil_existente, &tx, &segredo))
+            })
+            .await
+            .flatten()
+        };
+        match registro {
+            Some(Ok(id)) => {
+                entrada = Some((sala, id));
+                break;
+            }
+            Some(Err(motivo)) => {
+                recusar(&tx, motivo);
+                return;
+            }
+            None => tracing::debug!("sala fechou durante o login, procurando de novo"),
+        }
+    }
+    let Some((sala, mut my_player_id)) = entrada else {
+        recusar(&tx, MotivoRecusa::SalaFechada);
+        return;
+    };
+    span.record("jogador", my_player_id);
+
+    // Perfil persistente (ranking): fora da sala, o SQLite é síncrono
+    let chave = login.identidade.clone();
+    let nome = login.nome.clone();
+    let perfil =
+        tokio::task::spawn_blocking(move || ranking.identificar(chave.as_deref(), &nome)).await;
+    match perfil {
+        Ok(Ok((id_perfil, chave))) => {
+            sala.executar(move |room| {
+                room.perfis.insert(my_player_id, id_perfil);
+                room.enviar(my_player_id, &MsgServidor::Identidade { id_perfil, chave });
+            })
+            .await;
+        }
+        Ok(Err(e)) => tracing::warn!(erro = %e, "não foi possível identificar o perfil"),
+        Err(e) => tracing::warn!(erro = %e, "não foi possível identificar o perfil"),
+    }
+
+    // 4. LOOP DO JOGO
+    let mut ping = tokio::time::interval(heartbeat.intervalo);
+    ping.tick().await; // o primeiro tick é imediato
+    let mut ultimo_sinal = Instant::now();
+    let mut ping_enviado: Option<Instant> = None;
+
+    loop {
+        let msg = tokio::select! {
+            recebida = ws_rx.next() => match recebida {
+                Some(Ok(msg)) => msg,
+                _ => break,
+            },
+            _ = ping.tick() => {
+                // Sem pong nem mensagem há tempo demais: conexão zumbi
+                if ultimo_sinal.elapsed() > heartbeat.timeout {
+                    tracing::info!(
+                        sem_resposta_ms = ultimo_sinal.elapsed().as_millis() as u64,
+                        "conexão sem resposta, fechando"
+                    );
+                    let _ = tx.send(Saida::Ws(Message::close()));
+                    break;
                 }
+                ping_enviado = Some(Instant::now());
+                let _ = tx.send(Saida::Ws(Message::ping(Vec::new())));
+                continue;
+            }
+        };
+
+        // Qualquer coisa vinda do cliente prova que ele está vivo
+        ultimo_sinal = Instant::now();
+
+        if msg.is_pong() {
+            if let Some(enviado) = ping_enviado.take() {
+                let latencia_ms = enviado.elapsed().as_millis().min(u32::MAX as u128) as u32;
+                let device = login.device_id.clone();
+                sala.executar(move |room| {
+                    if let Some(&pid) = room.sessions.get(&device) {
+                        presenca::registrar_latencia(room, pid, latencia_ms);
+                    }
+                })
+                .await;
             }
+            continue;
+        }
+        if msg.is_close() {
+            break;
+        }
+        if msg.is_ping() {
+            continue; // o pong sai sozinho pelo próprio WebSocket
+        }
+
+        // Formato, tamanho e ritmo antes de incomodar a sala
+        let recebida = match protecao::ler(&mut limite, &msg) {
+            Ok(recebida) => recebida,
             Err(erro) => {
-                // Erro só para quem jogou
-                if let Ok(json) = serde_json::to_string(&MsgServidor::Erro(erro)) {
-                    let _ = tx.send(Message::text(json));
+                if protecao::recusar(&mut limite, &tx, erro) {
+                    break;
                 }
+                continue;
             }
+        };
+
+        let assento = {
+            let (login, segredo) = (login.clone(), segredo.clone());
+            sala.executar(move |room| tratar_mensagem(room, &login, &segredo, recebida))
+                .await
+                .flatten()
+        };
+        let Some(pid) = assento else {
+            break;
+        };
+        if pid != my_player_id {
+            // Troca de duplas na revanche muda o assento
+            span.record("jogador", pid);
         }
+        my_player_id = pid;
     }
 
-    println!(
-        "❌ Conexão encerrada: Sala {}, Jogador {}",
-        login_data.sala, my_player_id
-    );
+    // Tira o canal da sala (se ainda for o nosso) para o lobby saber que o assento está desconectado
+    let saiu = sala
+        .executar(move |room| {
+            let meu_assento = room
+                .clients
+                .iter()
+                .find(|(_, client_tx)| client_tx.same_channel(&tx))
+                .map(|(pid, _)| *pid);
+            if let Some(pid) = meu_assento {
+                room.clients.remove(&pid);
+                room.latencias.remove(&pid);
+                // A mesa vê na hora que ele caiu
+                presenca::anunciar(room);
+            }
+            meu_assento
+        })
+        .await
+        .flatten();
+    if let Some(pid) = saiu {
+        my_player_id = pid;
+    }
+
+    tracing::info!(assento = my_player_id, "conexão encerrada");
 }
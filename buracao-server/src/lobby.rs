@@ -0,0 +1,214 @@
+use crate::sala::Sala;
+use crate::sessao;
+use crate::state::{GlobalState, Room, ServerState};
+use buracao_core::lobby::{NovaSala, ResumoSala, SalaCriada, StatusSala};
+use dashmap::mapref::entry::Entry;
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+
+const TAMANHO_MAX_NOME_SALA: usize = 40;
+const TAMANHO_MAX_SENHA: usize = 64;
+const INTERVALO_LIMPEZA: Duration = Duration::from_secs(60);
+const BYTES_TOKEN_ANFITRIAO: usize = 16;
+
+// --- ROTAS REST DO LOBBY ---
+// GET  /api/salas          -> lista as salas públicas
+// POST /api/salas          -> cria uma sala nova (só quem cria recebe o token de anfitrião)
+// GET  /api/salas/{codigo} -> resumo de uma sala (pública ou não, quem tem o código pode ver)
+pub fn rotas(
+    state: GlobalState,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let state_filter = warp::any().map(move || state.clone());
+
+    let listar = warp::path!("api" / "salas")
+        .and(warp::get())
+        .and(state_filter.clone())
+        .and_then(listar_salas);
+
+    let criar = warp::path!("api" / "salas")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(4 * 1024))
+        .and(warp::body::json())
+        .and(state_filter.clone())
+        .and_then(criar_sala);
+
+    let detalhe = warp::path!("api" / "salas" / String)
+        .and(warp::get())
+        .and(state_filter)
+        .and_then(resumo_sala);
+
+    listar.or(criar).or(detalhe)
+}
+
+fn resposta_erro(status: StatusCode, mensagem: &str) -> warp::reply::Response {
+    warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({ "erro": mensagem })),
+        status,
+    )
+    .into_response()
+}
+
+async fn listar_salas(global_state: GlobalState) -> Result<impl Reply, Infallible> {
+    let mut resumos: Vec<ResumoSala> = Vec::new();
+    for (codigo, sala) in copiar_salas(&global_state) {
+        let resumo = sala
+            .executar(move |room| room.publica.then(|| room.resumo(&codigo)))
+            .await
+            .flatten();
+        resumos.extend(resumo);
+    }
+
+    // Salas com lugar vago primeiro, depois as mais cheias
+    resumos.sort_by_key(|r| {
+        (
+            r.status != StatusSala::AguardandoJogadores,
+            std::cmp::Reverse(r.assentos_ocupados()),
+            r.nome.to_lowercase(),
+        )
+    });
+
+    Ok(warp::reply::json(&resumos))
+}
+
+async fn criar_sala(
+    pedido: NovaSala,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let nome = pedido.nome.trim().to_string();
+    if nome.is_empty() || nome.chars().count() > TAMANHO_MAX_NOME_SALA {
+        return Ok(resposta_erro(
+            StatusCode::BAD_REQUEST,
+            "O nome da sala deve ter entre 1 e 40 caracteres.",
+        ));
+    }
+
+    // Senha vazia é o mesmo que sala sem senha
+    let senha = pedido.senha.filter(|s| !s.is_empty());
+    if senha
+        .as_ref()
+        .is_some_and(|s| s.chars().count() > TAMANHO_MAX_SENHA)
+    {
+        return Ok(resposta_erro(StatusCode::BAD_REQUEST, "Senha muito longa."));
+    }
+
+    if let Some(Err(e)) = pedido.regras.as_ref().map(|r| r.validar()) {
+        return Ok(resposta_erro(StatusCode::BAD_REQUEST, &e));
+    }
+    if let Some(Err(e)) = pedido.relogio.map(|r| r.validar()) {
+        return Ok(resposta_erro(StatusCode::BAD_REQUEST, &e));
+    }
+    if let Err(e) = pedido.espectadores.validar() {
+        return Ok(resposta_erro(StatusCode::BAD_REQUEST, &e));
+    }
+
+    if let Err(e) = global_state.cabem_salas(1) {
+        return Ok(resposta_erro(StatusCode::SERVICE_UNAVAILABLE, &e));
+    }
+    let regras = pedido
+        .regras
+        .clone()
+        .unwrap_or_else(|| global_state.config.regras.clone());
+    let mut room = Room::from_nova_sala(
+        NovaSala {
+            nome,
+            senha,
+            ..pedido
+        },
+        regras,
+    );
+    let token_anfitriao = sessao::chave_aleatoria(BYTES_TOKEN_ANFITRIAO);
+    room.token_anfitriao = Some(token_anfitriao.clone());
+    let resumo = abrir_sala(&global_state, room);
+    tracing::info!(sala = %resumo.codigo, nome = %resumo.nome, "sala criada pelo lobby");
+
+    let criada = SalaCriada {
+        resumo,
+        token_anfitriao,
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&criada), StatusCode::CREATED).into_response())
+}
+
+/// Dá as cartas, sorteia o código e coloca a sala no ar (lobby e torneios).
+pub fn abrir_sala(server: &ServerState, mut room: Room) -> ResumoSala {
+    room.game_state.dar_cartas();
+    room.ranking = Some(server.ranking.clone());
+    room.arquivo = Some(server.arquivo.clone());
+    loop {
+        // Outro login pode ter aberto o mesmo código entre o sorteio e aqui
+        let codigo = server.gerar_codigo_sala();
+        if let Entry::Vacant(vaga) = server.rooms.entry(codigo.clone()) {
+            let resumo = room.resumo(&codigo);
+            vaga.insert(Sala::abrir(room, &codigo));
+            return resumo;
+        }
+    }
+}
+
+/// Cópia dos endereços das salas, para não segurar o mapa enquanto cada uma responde.
+fn copiar_salas(server: &ServerState) -> Vec<(String, Sala)> {
+    server
+        .rooms
+        .iter()
+        .map(|par| (par.key().clone(), par.value().clone()))
+        .collect()
+}
+
+async fn resumo_sala(
+    codigo: String,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let codigo = codigo.to_uppercase();
+    let sala = global_state.rooms.get(&codigo).map(|sala| sala.clone());
+    let resumo = match sala {
+        Some(sala) => sala.executar(move |room| room.resumo(&codigo)).await,
+        None => None,
+    };
+
+    match resumo {
+        Some(resumo) => Ok(warp::reply::json(&resumo).into_response()),
+        None => Ok(resposta_erro(StatusCode::NOT_FOUND, "Sala não encontrada.")),
+    }
+}
+
+// --- SALAS OCIOSAS ---
+// Sala sem ninguém conectado por mais que `sala_ociosa_min` é fechada. Mesa de torneio
+// só fecha depois que a partida acabou, para o confronto não sumir antes de ser jogado.
+
+pub fn iniciar_limpeza(global_state: GlobalState, limite: Duration) {
+    tokio::spawn(async move {
+        let mut intervalo = tokio::time::interval(INTERVALO_LIMPEZA);
+        loop {
+            intervalo.tick().await;
+            fechar_salas_ociosas(&global_state, limite).await;
+        }
+    });
+}
+
+async fn fechar_salas_ociosas(global_state: &GlobalState, limite: Duration) {
+    for (codigo, sala) in copiar_salas(global_state) {
+        // Decidido dentro da sala: ninguém entra entre a verificação e o fechamento
+        let ociosa = sala
+            .executar(move |room| {
+                if room.tem_conexoes() {
+                    room.vazia_desde = None;
+                    return false;
+                }
+                let desde = *room.vazia_desde.get_or_insert_with(Instant::now);
+                let torneio_pendente = room.torneio.is_some() && !room.game_state.partida_encerrada;
+                let ociosa = desde.elapsed() >= limite && !torneio_pendente;
+                // Um login que já tem esta `Sala` em mãos procura de novo (e abre outra)
+                room.fechada |= ociosa;
+                ociosa
+            })
+            .await
+            .unwrap_or(false);
+        if ociosa {
+            global_state
+                .rooms
+                .remove_if(&codigo, |_, atual| atual.mesma(&sala));
+            tracing::info!(sala = %codigo, "sala fechada por inatividade");
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::config::Config;
+use buracao_core::eventos::{self, EventoJogo};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing_subscriber::EnvFilter;
+
+// --- LOGS ESTRUTURADOS ---
+// Tudo passa pelo `tracing`. Cada conexão WebSocket abre um span `conexao` com o código
+// da sala, o device e o assento; cada ação do jogo abre um span `acao` com o tipo dela.
+// Tarefas da sala que não vêm de uma conexão (relógio, limpeza, torneio) usam o span
+// `sala`. Assim qualquer linha de log sai com a sala e o jogador, em texto ou em JSON.
+
+static PROXIMA_CONEXAO: AtomicU64 = AtomicU64::new(1);
+
+/// Liga o log no nível e formato da configuração e passa a receber os eventos do core.
+pub fn iniciar(config: &Config) {
+    // Só o nível do servidor segue a configuração; bibliotecas ficam em warn
+    let filtro = EnvFilter::new(format!("warn,buracao_server={}", config.log));
+    let base = tracing_subscriber::fmt()
+        .with_env_filter(filtro)
+        .with_target(false)
+        // Cor só no terminal; em arquivo ou coletor de logs os códigos ANSI atrapalham
+        .with_ansi(std::io::stdout().is_terminal());
+    if config.log_json {
+        base.json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(true)
+            .init();
+    } else {
+        base.init();
+    }
+    eventos::definir_observador(evento_do_jogo);
+}
+
+/// Observador do core: roda dentro do span de quem chamou (conexão/ação ou sala).
+fn evento_do_jogo(evento: &EventoJogo) {
+    match *evento {
+        EventoJogo::Batida {
+            jogador,
+            pontos_a,
+            pontos_b,
+        } => tracing::info!(jogador, pontos_a, pontos_b, "jogador bateu"),
+        EventoJogo::Esgotamento { pontos_a, pontos_b } => {
+            tracing::info!(pontos_a, pontos_b, "mão encerrada por esgotamento do monte")
+        }
+    }
+}
+
+/// Span de uma conexão WebSocket; `sala`, `device` e `jogador` são preenchidos no login.
+pub fn span_conexao() -> tracing::Span {
+    tracing::info_span!(
+        "conexao",
+        id = PROXIMA_CONEXAO.fetch_add(1, Ordering::Relaxed),
+        sala = tracing::field::Empty,
+        device = tracing::field::Empty,
+        jogador = tracing::field::Empty,
+    )
+}
+
+/// Span do que a sala faz sozinha (relógio de turno, limpeza, rodadas de torneio).
+pub fn span_sala(codigo: &str) -> tracing::Span {
+    tracing::info_span!("sala", sala = %codigo)
+}
@@ -1,68 +1,144 @@
+mod admin;
+mod arquivo;
+mod chat;
+mod config;
+mod desligamento;
+mod espectador;
 mod handler;
+mod lobby;
+mod logs;
+mod metricas;
+mod moderacao;
+mod presenca;
+mod protecao;
+mod ranking;
+mod relogio;
+mod revanche;
+mod sala;
+mod sessao;
 mod state;
+#[cfg(test)]
+mod testes;
+mod torneio;
+mod visoes;
 
-use std::path::Path;
+use config::Config;
+use state::GlobalState;
+use std::sync::Arc;
 use warp::Filter;
 
 #[tokio::main]
 async fn main() {
-    println!("🔥 INICIANDO SERVIDOR BURACO COM LOBBIES (PORTA 8080)...");
+    // 0. CONFIGURAÇÃO (arquivo TOML, ambiente e flags; ver config.rs)
+    // Configuração inválida não sobe: melhor falhar aqui do que servir pela metade
+    let config = match Config::carregar() {
+        Ok(config) => Arc::new(config),
+        Err(e) => {
+            eprintln!("❌ Configuração inválida:\n  {}", e);
+            std::process::exit(1);
+        }
+    };
+    logs::iniciar(&config);
+    tracing::info!("iniciando servidor: {}", config.resumo());
 
     // 1. INICIALIZAÇÃO DO ESTADO GLOBAL (O "HOTEL" DE SALAS)
     // Agora 'global_state' contém um HashMap de salas, não um jogo único.
-    let global_state = state::inicializar_servidor();
+    let global_state = match state::inicializar_servidor(config.clone()) {
+        Ok(state) => state,
+        Err(e) => {
+            tracing::error!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Some(limite) = config.sala_ociosa {
+        lobby::iniciar_limpeza(global_state.clone(), limite);
+    }
+
+    let estado_desligamento = global_state.clone();
+    let routes = rotas(global_state);
+
+    // Porta ocupada ou endereço que não é desta máquina: erro claro em vez de pânico
+    let listener = match tokio::net::TcpListener::bind(config.endereco).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!(endereco = %config.endereco, erro = %e, "não foi possível escutar");
+            std::process::exit(1);
+        }
+    };
+    tracing::info!("servidor rodando em http://{}", config.endereco);
+    let (parar, parou) = tokio::sync::oneshot::channel::<()>();
+    let servidor = tokio::spawn(
+        warp::serve(routes)
+            .incoming(listener)
+            .graceful(async {
+                let _ = parou.await;
+            })
+            .run(),
+    );
+
+    // 5. DESLIGAMENTO: para de aceitar conexões, avisa as mesas e guarda as salas
+    desligamento::sinal().await;
+    let _ = parar.send(());
+    if tokio::time::timeout(desligamento::PRAZO_CONEXOES, servidor)
+        .await
+        .is_err()
+    {
+        tracing::warn!("conexões HTTP ainda abertas, desligando mesmo assim");
+    }
+    desligamento::encerrar(&estado_desligamento).await;
+    tracing::info!("servidor desligado");
+}
+
+/// Todas as rotas do servidor (WebSocket, APIs e o site) sobre o mesmo estado.
+fn rotas(
+    global_state: GlobalState,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let config = global_state.config.clone();
+    // Rotas REST do lobby (listar/criar salas) compartilham o mesmo estado
+    let lobby_routes = lobby::rotas(global_state.clone());
+    let torneio_routes = torneio::rotas(global_state.clone());
+    let ranking_routes = ranking::rotas(global_state.ranking.clone());
+    let arquivo_routes = arquivo::rotas(global_state.arquivo.clone());
+    let metricas_routes = metricas::rotas(global_state.clone());
+    let admin_routes = admin::rotas(global_state.clone()).recover(admin::tratar_recusa);
 
     // Cria um filtro do Warp para injetar esse estado em cada conexão
     let state_filter = warp::any().map(move || global_state.clone());
+    // Limite duro do protocolo; o limite configurado é conferido no handler, com resposta
+    let limite_frame = config.limites.max_mensagem * protecao::FOLGA_FRAME;
 
     // 2. ROTA DO WEBSOCKET
     // Quando alguém acessa ws://localhost:8080/buraco
-    let game_ws_route =
-        warp::path("buraco")
-            .and(warp::ws())
-            .and(state_filter)
-            .map(|ws: warp::ws::Ws, state| {
-                // Passa a conexão e o estado global para o handler gerenciar o login
-                ws.on_upgrade(move |socket| handler::handle_connection(socket, state))
-            });
-
-    // 3. DESCOBRIR ONDE ESTÁ O SITE (FRONTEND)
-    // Mantive sua lógica robusta de procurar a pasta 'dist'
-    let lugares_provaveis = vec!["./dist", "../buracao-web/dist", "./buracao-web/dist"];
-    let mut static_path = "./dist".to_string();
-    let mut encontrou = false;
-
-    for caminho in lugares_provaveis {
-        if Path::new(caminho).join("index.html").exists() {
-            static_path = caminho.to_string();
-            encontrou = true;
-            break;
-        }
-    }
-
-    if encontrou {
-        println!("✅ Site encontrado em: '{}'", static_path);
-    } else {
-        println!("❌ AVISO: 'index.html' não encontrado. O site não vai carregar.");
-    }
+    let game_ws_route = warp::path("buraco").and(warp::ws()).and(state_filter).map(
+        move |ws: warp::ws::Ws, state| {
+            // Passa a conexão e o estado global para o handler gerenciar o login
+            ws.max_message_size(limite_frame)
+                .max_frame_size(limite_frame)
+                .on_upgrade(move |socket| handler::handle_connection(socket, state))
+        },
+    );
 
-    // 4. CONFIGURAÇÃO DE ARQUIVOS ESTÁTICOS (SPA)
+    // 3. CONFIGURAÇÃO DE ARQUIVOS ESTÁTICOS (SPA)
+    // A pasta do site já foi conferida na configuração (tem index.html)
 
     // A. Serve arquivos reais (js, css, imagens)
-    let assets = warp::fs::dir(static_path.clone());
+    let assets = warp::fs::dir(config.site.clone());
 
     // B. Fallback para SPA (Single Page Application)
     // Se a rota não for arquivo nem websocket (ex: /sala/amigos), entrega o index.html
-    let index_file_path = Path::new(&static_path).join("index.html");
-    let spa_fallback = warp::fs::file(index_file_path);
+    let spa_fallback = warp::fs::file(config.site.join("index.html"));
 
     // Combina: Tenta arquivo -> Se falhar, entrega index.html
     let site_route = assets.or(spa_fallback);
 
-    // 5. JUNTAR TUDO E RODAR
-    // Ordem de prioridade: WebSocket > Arquivos do Site
-    let routes = game_ws_route.or(site_route);
-
-    println!("🚀 Server rodando em http://0.0.0.0:8080");
-    warp::serve(routes).run(([0, 0, 0, 0], 8080)).await;
+    // 4. JUNTAR TUDO E RODAR
+    // Ordem de prioridade: WebSocket > API do Lobby > Arquivos do Site
+    game_ws_route
+        .or(lobby_routes)
+        .or(ranking_routes)
+        .or(torneio_routes)
+        .or(arquivo_routes)
+        .or(metricas_routes)
+        .or(admin_routes)
+        .or(site_route)
 }
@@ -0,0 +1,312 @@
+use crate::sala::Sala;
+use crate::state::{GlobalState, Room};
+use buracao_core::lobby::StatusSala;
+use buracao_core::protocolo::ErroMensagem;
+use buracao_core::{Recusa, TipoRecusa};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+
+// --- MÉTRICAS E SAÚDE ---
+// GET /healthz -> 200 se todas as salas respondem em pouco tempo, 503 se alguma travou
+// GET /metrics -> métricas no formato texto do Prometheus
+//
+// Contadores são do processo inteiro (zeram quando o servidor reinicia); salas, conexões
+// e partidas em andamento são lidas na hora da coleta. Durações saem como `_sum`/`_count`,
+// a média fica por conta do Prometheus (`rate(x_sum) / rate(x_count)`).
+
+// Mais que isso esperando uma sala responder e o servidor é considerado travado
+const PRAZO_SAUDE: Duration = Duration::from_secs(2);
+
+pub static METRICAS: LazyLock<Metricas> = LazyLock::new(Metricas::default);
+
+#[derive(Default)]
+struct Soma {
+    total: u64,
+    micros: u64,
+}
+
+#[derive(Default)]
+pub struct Metricas {
+    acoes: Mutex<BTreeMap<&'static str, u64>>,
+    recusas: Mutex<BTreeMap<&'static str, u64>>,
+    turnos: Mutex<Soma>,
+    espera_sala: Mutex<Soma>,
+    falhas_envio: AtomicU64,
+    rejeicoes: Mutex<BTreeMap<&'static str, u64>>,
+    derrubadas: AtomicU64,
+}
+
+/// Rótulo do tipo de uma ação recusada.
+pub fn tipo_recusa(tipo: TipoRecusa) -> &'static str {
+    match tipo {
+        TipoRecusa::ForaDaVez => "fora_da_vez",
+        TipoRecusa::PartidaEncerrada => "partida_encerrada",
+        TipoRecusa::MonteEsgotado => "monte_esgotado",
+        TipoRecusa::Lixo => "lixo",
+        TipoRecusa::Compra => "compra",
+        TipoRecusa::PontosParaDescer => "pontos_para_descer",
+        TipoRecusa::Batida => "batida",
+        TipoRecusa::CartaForaDaMao => "carta_fora_da_mao",
+        TipoRecusa::JogoInvalido => "jogo_invalido",
+    }
+}
+
+impl Metricas {
+    pub fn acao(&self, tipo: &'static str, resultado: &Result<String, Recusa>) {
+        *self.acoes.lock().unwrap().entry(tipo).or_insert(0) += 1;
+        if let Err(erro) = resultado {
+            *self
+                .recusas
+                .lock()
+                .unwrap()
+                .entry(tipo_recusa(erro.tipo))
+                .or_insert(0) += 1;
+        }
+    }
+
+    pub fn turno(&self, duracao: Duration) {
+        let mut turnos = self.turnos.lock().unwrap();
+        turnos.total += 1;
+        turnos.micros += duracao.as_micros() as u64;
+    }
+
+    pub fn falha_envio(&self) {
+        self.falhas_envio.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn mensagem_rejeitada(&self, erro: &ErroMensagem) {
+        let motivo = match erro {
+            ErroMensagem::NaoTexto => "nao_texto",
+            ErroMensagem::MuitoGrande { .. } => "muito_grande",
+            ErroMensagem::JsonInvalido { .. } => "json_invalido",
+            ErroMensagem::CampoInvalido { .. } => "campo_invalido",
+            ErroMensagem::RitmoExcedido { .. } => "ritmo_excedido",
+        };
+        *self.rejeicoes.lock().unwrap().entry(motivo).or_insert(0) += 1;
+    }
+
+    pub fn conexao_derrubada(&self) {
+        self.derrubadas.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Do pedido à resposta de um comando da sala: fila mais execução (ver sala.rs).
+    pub fn espera_sala(&self, duracao: Duration) {
+        let mut soma = self.espera_sala.lock().unwrap();
+        soma.total += 1;
+        soma.micros += duracao.as_micros() as u64;
+    }
+}
+
+impl Room {
+    /// Chamado depois de cada mudança no jogo: se a vez passou, conta a duração do turno
+    /// que acabou. A contagem começa na primeira jogada, não quando a sala abre.
+    pub fn medir_turno(&mut self) {
+        let rodada = self.game_state.rodada;
+        match self.inicio_turno {
+            Some((anterior, inicio)) if rodada > anterior => METRICAS.turno(inicio.elapsed()),
+            // Mesmo turno: continua contando de onde estava
+            Some((anterior, _)) if rodada == anterior => return,
+            _ => {}
+        }
+        self.inicio_turno = Some((rodada, Instant::now()));
+    }
+}
+
+pub fn rotas(
+    state: GlobalState,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let state_filter = warp::any().map(move || state.clone());
+
+    let saude = warp::path!("healthz")
+        .and(warp::get())
+        .and(state_filter.clone())
+        .and_then(saude);
+
+    let metricas = warp::path!("metrics")
+        .and(warp::get())
+        .and(state_filter)
+        .and_then(coletar);
+
+    saude.or(metricas)
+}
+
+async fn saude(global_state: GlobalState) -> Result<warp::reply::Response, Infallible> {
+    let salas: Vec<Sala> = global_state
+        .rooms
+        .iter()
+        .map(|s| s.value().clone())
+        .collect();
+    // Uma sala presa num comando deixa os jogadores dela sem resposta: conta como travado
+    let respostas = futures::future::join_all(salas.iter().map(|sala| sala.executar(|_| ())));
+    let salas = tokio::time::timeout(PRAZO_SAUDE, respostas)
+        .await
+        .map(|_| salas.len());
+    Ok(match salas {
+        Ok(salas) => warp::reply::json(&serde_json::json!({ "status": "ok", "salas": salas }))
+            .into_response(),
+        Err(_) => warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({ "status": "travado" })),
+            StatusCode::SERVICE_UNAVAILABLE,
+        )
+        .into_response(),
+    })
+}
+
+/// Uma métrica no formato do Prometheus: HELP, TYPE e as linhas (sufixo e rótulos, valor).
+fn escrever(saida: &mut String, nome: &str, tipo: &str, ajuda: &str, linhas: &[(String, f64)]) {
+    let _ = writeln!(saida, "# HELP {} {}", nome, ajuda);
+    let _ = writeln!(saida, "# TYPE {} {}", nome, tipo);
+    for (rotulos, valor) in linhas {
+        let _ = writeln!(saida, "{}{} {}", nome, rotulos, valor);
+    }
+}
+
+/// Memória residente do processo (VmRSS). Só existe no Linux; fora dele a métrica some.
+fn memoria_residente() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let linha = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = linha.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+async fn coletar(global_state: GlobalState) -> Result<impl Reply, Infallible> {
+    // Copia os endereços para não segurar o mapa enquanto cada sala responde
+    let salas: Vec<Sala> = global_state
+        .rooms
+        .iter()
+        .map(|s| s.value().clone())
+        .collect();
+    let (mut jogadores, mut espectadores, mut em_andamento) = (0, 0, 0);
+    for sala in &salas {
+        let contagem = sala
+            .executar(|room| {
+                (
+                    room.clients.len(),
+                    room.espectadores.len(),
+                    room.status() == StatusSala::EmAndamento,
+                )
+            })
+            .await;
+        if let Some((j, e, andamento)) = contagem {
+            jogadores += j;
+            espectadores += e;
+            em_andamento += andamento as usize;
+        }
+    }
+
+    let m = &*METRICAS;
+    let por_rotulo = |mapa: &BTreeMap<&'static str, u64>, rotulo: &str| -> Vec<(String, f64)> {
+        mapa.iter()
+            .map(|(valor, total)| (format!("{{{}=\"{}\"}}", rotulo, valor), *total as f64))
+            .collect()
+    };
+    let sem_rotulo = |valor: f64| vec![(String::new(), valor)];
+
+    let mut saida = String::new();
+    escrever(
+        &mut saida,
+        "buracao_salas",
+        "gauge",
+        "Salas abertas.",
+        &sem_rotulo(salas.len() as f64),
+    );
+    escrever(
+        &mut saida,
+        "buracao_conexoes",
+        "gauge",
+        "Clientes conectados.",
+        &[
+            ("{tipo=\"jogador\"}".to_string(), jogadores as f64),
+            ("{tipo=\"espectador\"}".to_string(), espectadores as f64),
+        ],
+    );
+    escrever(
+        &mut saida,
+        "buracao_partidas_em_andamento",
+        "gauge",
+        "Salas com os quatro assentos ocupados e a partida rolando.",
+        &sem_rotulo(em_andamento as f64),
+    );
+    escrever(
+        &mut saida,
+        "buracao_acoes_total",
+        "counter",
+        "Ações de jogo recebidas, por tipo.",
+        &por_rotulo(&m.acoes.lock().unwrap(), "tipo"),
+    );
+    escrever(
+        &mut saida,
+        "buracao_acoes_recusadas_total",
+        "counter",
+        "Ações de jogo recusadas, por tipo de erro.",
+        &por_rotulo(&m.recusas.lock().unwrap(), "erro"),
+    );
+    {
+        let turnos = m.turnos.lock().unwrap();
+        escrever(
+            &mut saida,
+            "buracao_turno_segundos",
+            "summary",
+            "Duração dos turnos jogados.",
+            &[
+                ("_sum".to_string(), turnos.micros as f64 / 1e6),
+                ("_count".to_string(), turnos.total as f64),
+            ],
+        );
+    }
+    escrever(
+        &mut saida,
+        "buracao_falhas_envio_total",
+        "counter",
+        "Mensagens que não chegaram a um WebSocket (conexão já fechada).",
+        &sem_rotulo(m.falhas_envio.load(Ordering::Relaxed) as f64),
+    );
+    escrever(
+        &mut saida,
+        "buracao_mensagens_rejeitadas_total",
+        "counter",
+        "Mensagens do cliente recusadas antes do jogo, por motivo.",
+        &por_rotulo(&m.rejeicoes.lock().unwrap(), "motivo"),
+    );
+    escrever(
+        &mut saida,
+        "buracao_conexoes_derrubadas_total",
+        "counter",
+        "Conexões derrubadas por mensagens rejeitadas demais.",
+        &sem_rotulo(m.derrubadas.load(Ordering::Relaxed) as f64),
+    );
+    if let Some(bytes) = memoria_residente() {
+        escrever(
+            &mut saida,
+            "buracao_memoria_residente_bytes",
+            "gauge",
+            "Memória física ocupada pelo processo.",
+            &sem_rotulo(bytes as f64),
+        );
+    }
+    {
+        let espera = m.espera_sala.lock().unwrap();
+        escrever(
+            &mut saida,
+            "buracao_espera_sala_segundos",
+            "summary",
+            "Tempo até uma sala responder a um comando (fila mais execução).",
+            &[
+                ("_sum".to_string(), espera.micros as f64 / 1e6),
+                ("_count".to_string(), espera.total as f64),
+            ],
+        );
+    }
+
+    Ok(warp::reply::with_header(
+        saida,
+        "content-type",
+        "text/plain; version=0.0.4; charset=utf-8",
+    ))
+}
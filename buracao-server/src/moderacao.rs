@@ -0,0 +1,164 @@
+use crate::state::{PlayerId, Room};
+use buracao_core::acoes::ComandoSala;
+
+// --- COMANDOS DO ANFITRIÃO E VOTO DE EXPULSÃO ---
+// Tudo aqui é validado no servidor: o cliente só mostra os botões.
+// Assento liberado fica vazio para outra pessoa entrar. Liberado para o robô, a jogada
+// automática do relógio (compra e descarta) joga por ele em cada vez até alguém sentar;
+// o robô não vota na revanche, então a próxima partida ainda espera a mesa completa.
+
+const MIN_VOTOS_EXPULSAO: usize = 2;
+
+pub fn executar(room: &mut Room, autor: PlayerId, comando: ComandoSala) -> Result<String, String> {
+    let eh_host = room.host == Some(autor);
+
+    match comando {
+        ComandoSala::Expulsar { jogador } => {
+            exigir_host(eh_host)?;
+            validar_alvo(room, autor, jogador)?;
+
+            if let Some(device) =
+                room.remover_jogador(jogador, "Você foi expulso da sala pelo anfitrião.")
+            {
+                room.banidos.insert(device);
+            }
+            tracing::info!(
+                alvo = jogador,
+                anfitriao = autor,
+                "jogador expulso pelo anfitrião"
+            );
+            Ok(format!("Jogador {} expulso.", jogador))
+        }
+
+        ComandoSala::LiberarAssento { jogador, robo } => {
+            exigir_host(eh_host)?;
+            validar_alvo(room, autor, jogador)?;
+
+            room.remover_jogador(jogador, "O anfitrião liberou o seu assento.");
+            tracing::info!(
+                alvo = jogador,
+                anfitriao = autor,
+                robo,
+                "assento liberado pelo anfitrião"
+            );
+            if robo {
+                room.robos.insert(jogador);
+                // Se já era a vez dele, o robô joga agora
+                room.propagar_estado();
+                Ok(format!(
+                    "Assento {} liberado: o robô joga por ele até alguém sentar.",
+                    jogador
+                ))
+            } else {
+                Ok(format!("Assento {} liberado para outro jogador.", jogador))
+            }
+        }
+
+        ComandoSala::TrancarSala { trancada } => {
+            exigir_host(eh_host)?;
+            room.trancada = trancada;
+            tracing::info!(
+                anfitriao = autor,
+                trancada,
+                "sala trancada/destrancada pelo anfitrião"
+            );
+            Ok(if trancada {
+                "Sala trancada: ninguém novo entra.".to_string()
+            } else {
+                "Sala destrancada.".to_string()
+            })
+        }
+
+        ComandoSala::TransferirHost { jogador } => {
+            exigir_host(eh_host)?;
+            validar_alvo(room, autor, jogador)?;
+            room.host = Some(jogador);
+            tracing::info!(
+                alvo = jogador,
+                anfitriao = autor,
+                "anfitrião transferido pelo anfitrião"
+            );
+            Ok(format!("Jogador {} agora é o anfitrião.", jogador))
+        }
+
+        ComandoSala::VotarExpulsao { jogador } => {
+            validar_alvo(room, autor, jogador)?;
+
+            room.votos_expulsao
+                .entry(jogador)
+                .or_default()
+                .insert(autor);
+
+            // Maioria simples entre os outros jogadores sentados, e nunca um voto só:
+            // com dois na mesa, um não expulsa o outro
+            let eleitores = room.sessions.len().saturating_sub(1);
+            let votos = room.votos_expulsao.get(&jogador).map_or(0, |v| v.len());
+            let necessarios = (eleitores / 2 + 1).max(MIN_VOTOS_EXPULSAO);
+
+            if votos >= necessarios {
+                if let Some(device) =
+                    room.remover_jogador(jogador, "Você foi expulso por votação da mesa.")
+                {
+                    room.banidos.insert(device);
+                }
+                tracing::info!(alvo = jogador, votos, "jogador expulso por votação");
+                Ok(format!("Jogador {} expulso por votação.", jogador))
+            } else {
+                Ok(format!(
+                    "Voto registrado ({}/{} para expulsar o jogador {}).",
+                    votos, necessarios, jogador
+                ))
+            }
+        }
+    }
+}
+
+fn exigir_host(eh_host: bool) -> Result<(), String> {
+    if eh_host {
+        Ok(())
+    } else {
+        Err("Só o anfitrião da sala pode fazer isso.".to_string())
+    }
+}
+
+fn validar_alvo(room: &Room, autor: PlayerId, alvo: PlayerId) -> Result<(), String> {
+    if alvo == autor {
+        return Err("Você não pode usar esse comando em si mesmo.".to_string());
+    }
+    if !room.assento_ocupado(alvo) {
+        return Err(format!("Não há ninguém no assento {}.", alvo));
+    }
+    Ok(())
+}
+
+impl Room {
+    /// Joga pelos assentos do robô enquanto a vez for de um deles. Cada jogada vai para
+    /// a mesa como a de um jogador.
+    pub(crate) fn jogar_pelos_robos(&mut self) {
+        while !self.game_state.partida_encerrada
+            && self.robos.contains(&self.game_state.turno_atual)
+        {
+            let vez = self.game_state.turno_atual;
+            // Alguém ganhou o assento por fora do login (administração): o robô sai
+            if self.assento_ocupado(vez) {
+                self.robos.remove(&vez);
+                break;
+            }
+            let versao = self.game_state.versao;
+            match self.game_state.jogada_automatica(vez) {
+                Ok(resumo) => {
+                    tracing::info!(assento = vez, %resumo, "jogada do robô");
+                    self.medir_turno();
+                    self.enviar_visoes();
+                }
+                Err(e) => {
+                    tracing::warn!(assento = vez, erro = %e, "jogada do robô falhou");
+                    if self.game_state.versao != versao {
+                        self.enviar_visoes();
+                    }
+                    break;
+                }
+            }
+        }
+    }
+}
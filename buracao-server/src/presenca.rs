@@ -0,0 +1,62 @@
+use crate::state::{PlayerId, Room};
+use buracao_core::acoes::{MsgServidor, PresencaJogador};
+use std::time::Duration;
+
+// --- HEARTBEAT E PRESENÇA ---
+// O servidor manda um ping de tempos em tempos. Conexão que não dá sinal de vida
+// (pong ou qualquer mensagem) dentro do timeout é tratada como morta e fechada:
+// é o caso do celular que dormiu com a aba aberta.
+
+// Variação mínima de latência que vale avisar a mesa (evita uma mensagem a cada ping)
+const VARIACAO_MINIMA_MS: u32 = 50;
+
+/// Ajustável por `ping_intervalo_s` e `ping_timeout_s` (ver config.rs).
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigHeartbeat {
+    pub intervalo: Duration,
+    pub timeout: Duration,
+}
+
+impl Default for ConfigHeartbeat {
+    fn default() -> Self {
+        Self {
+            intervalo: Duration::from_secs(15),
+            timeout: Duration::from_secs(45),
+        }
+    }
+}
+
+/// Conexão e latência de cada assento ocupado.
+pub fn mapa(room: &Room) -> MsgServidor {
+    MsgServidor::Presenca(
+        room.jogadores_sentados()
+            .into_iter()
+            .map(|pid| {
+                let conectado = room.clients.contains_key(&pid);
+                let presenca = PresencaJogador {
+                    conectado,
+                    latencia_ms: room.latencias.get(&pid).copied().filter(|_| conectado),
+                    ausente: room
+                        .relogio
+                        .as_ref()
+                        .is_some_and(|r| r.ausentes.contains(&pid)),
+                };
+                (pid, presenca)
+            })
+            .collect(),
+    )
+}
+
+pub fn anunciar(room: &mut Room) {
+    let msg = mapa(room);
+    room.broadcast(&msg);
+}
+
+/// Guarda o tempo de ida e volta do último ping e avisa a mesa se mudou o bastante.
+pub fn registrar_latencia(room: &mut Room, pid: PlayerId, latencia_ms: u32) {
+    let anterior = room.latencias.insert(pid, latencia_ms);
+    let mudou = anterior.is_none_or(|ms| ms.abs_diff(latencia_ms) >= VARIACAO_MINIMA_MS);
+    if mudou {
+        anunciar(room);
+    }
+}
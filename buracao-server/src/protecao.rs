@@ -0,0 +1,148 @@
+use crate::metricas::METRICAS;
+use crate::state::{Saida, Sender};
+use buracao_core::acoes::{MsgCliente, MsgServidor};
+use buracao_core::protocolo::{self, ErroMensagem};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use warp::ws::Message;
+
+// --- PROTEÇÃO DA CONEXÃO ---
+// Cada conexão tem um balde de fichas: `acoes_por_segundo` fichas por segundo, com
+// rajada de até o dobro. Sem ficha, a mensagem é recusada e a conexão fica de castigo
+// por um tempo que dobra a cada estouro seguido (250 ms, 500 ms, 1 s... até 8 s).
+// Mensagens no castigo, grandes demais ou malformadas contam como infração; infrações
+// demais em um minuto derrubam a conexão.
+
+const CASTIGO_INICIAL: Duration = Duration::from_millis(250);
+const CASTIGO_MAX: Duration = Duration::from_secs(8);
+const JANELA_INFRACOES: Duration = Duration::from_secs(60);
+const INFRACOES_MAX: usize = 20;
+// Frames maiores que isso (vezes o limite configurado) derrubam a conexão no protocolo,
+// sem nem chegar ao handler
+pub const FOLGA_FRAME: usize = 4;
+
+/// Ajustável por `max_mensagem` e `acoes_por_segundo` (ver config.rs).
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigLimites {
+    /// Tamanho máximo de uma mensagem de texto, em bytes
+    pub max_mensagem: usize,
+    pub acoes_por_segundo: u32,
+}
+
+impl Default for ConfigLimites {
+    fn default() -> Self {
+        Self {
+            max_mensagem: 16 * 1024,
+            acoes_por_segundo: 10,
+        }
+    }
+}
+
+pub struct LimiteConexao {
+    config: ConfigLimites,
+    fichas: f64,
+    reposto_em: Instant,
+    castigo_ate: Option<Instant>,
+    estouros_seguidos: u32,
+    infracoes: VecDeque<Instant>,
+}
+
+impl LimiteConexao {
+    pub fn new(config: ConfigLimites) -> Self {
+        Self {
+            config,
+            fichas: Self::rajada(config),
+            reposto_em: Instant::now(),
+            castigo_ate: None,
+            estouros_seguidos: 0,
+            infracoes: VecDeque::new(),
+        }
+    }
+
+    fn rajada(config: ConfigLimites) -> f64 {
+        2.0 * config.acoes_por_segundo as f64
+    }
+
+    /// Tamanho e ritmo de uma mensagem recém-chegada, antes de decodificar.
+    pub fn conferir(&mut self, tamanho: usize) -> Result<(), ErroMensagem> {
+        let agora = Instant::now();
+        if let Some(ate) = self.castigo_ate {
+            if agora < ate {
+                return Err(ErroMensagem::RitmoExcedido {
+                    espera_ms: (ate - agora).as_millis() as u64,
+                });
+            }
+            self.castigo_ate = None;
+        }
+
+        let decorrido = agora.duration_since(self.reposto_em).as_secs_f64();
+        self.reposto_em = agora;
+        self.fichas = (self.fichas + decorrido * self.config.acoes_por_segundo as f64)
+            .min(Self::rajada(self.config));
+        if self.fichas < 1.0 {
+            let castigo = CASTIGO_INICIAL
+                .saturating_mul(1 << self.estouros_seguidos.min(16))
+                .min(CASTIGO_MAX);
+            self.estouros_seguidos += 1;
+            self.castigo_ate = Some(agora + castigo);
+            return Err(ErroMensagem::RitmoExcedido {
+                espera_ms: castigo.as_millis() as u64,
+            });
+        }
+        // Balde cheio de novo = o cliente se acalmou: o próximo estouro volta ao castigo inicial
+        if self.fichas >= Self::rajada(self.config) {
+            self.estouros_seguidos = 0;
+        }
+        self.fichas -= 1.0;
+
+        if tamanho > self.config.max_mensagem {
+            return Err(ErroMensagem::MuitoGrande {
+                limite: self.config.max_mensagem,
+            });
+        }
+        Ok(())
+    }
+
+    /// Anota uma mensagem recusada. `true` = passou do limite, hora de desconectar.
+    pub fn infracao(&mut self) -> bool {
+        let agora = Instant::now();
+        while self
+            .infracoes
+            .front()
+            .is_some_and(|t| agora.duration_since(*t) > JANELA_INFRACOES)
+        {
+            self.infracoes.pop_front();
+        }
+        self.infracoes.push_back(agora);
+        self.infracoes.len() > INFRACOES_MAX
+    }
+}
+
+/// Confere e decodifica uma mensagem do cliente (ver protocolo.rs).
+pub fn ler(limite: &mut LimiteConexao, msg: &Message) -> Result<MsgCliente, ErroMensagem> {
+    limite.conferir(msg.as_bytes().len())?;
+    if !msg.is_text() {
+        return Err(ErroMensagem::NaoTexto);
+    }
+    protocolo::decodificar(msg.as_bytes())
+}
+
+/// Responde a mensagem recusada com o motivo; passando do limite de infrações, avisa e
+/// fecha a conexão. Devolve `true` quando a conexão deve ser encerrada.
+pub fn recusar(limite: &mut LimiteConexao, tx: &Sender, erro: ErroMensagem) -> bool {
+    METRICAS.mensagem_rejeitada(&erro);
+    tracing::debug!(?erro, "mensagem rejeitada");
+    let (msg, derrubar) = if limite.infracao() {
+        tracing::warn!("mensagens rejeitadas demais, derrubando a conexão");
+        METRICAS.conexao_derrubada();
+        let motivo = "Conexão encerrada: mensagens inválidas ou rápidas demais.".to_string();
+        (MsgServidor::Expulso { motivo }, true)
+    } else {
+        (MsgServidor::MensagemRejeitada(erro), false)
+    };
+    let _ = tx.send(Saida::msg(&msg));
+    if derrubar {
+        let _ = tx.send(Saida::Ws(Message::close()));
+    }
+    derrubar
+}
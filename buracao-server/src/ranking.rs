@@ -0,0 +1,325 @@
+use crate::config::BANCO_EM_MEMORIA;
+use buracao_core::lobby::EntradaRanking;
+use rand::RngCore;
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use warp::{Filter, Rejection, Reply};
+
+// --- PERFIS E RANKING ---
+// Cada jogador ganha um perfil persistente na primeira vez que entra. O cliente guarda
+// a chave do perfil e apresenta nos próximos logins; o device_id continua servindo só
+// para a sessão na sala. O banco guarda apenas o hash da chave.
+//
+// Rating: Elo por dupla. A força de cada time é a média dos ratings dos dois jogadores,
+// e os dois recebem a mesma variação no fim da partida.
+
+const RATING_INICIAL: f64 = 1500.0;
+// Variação máxima por partida; perfis novos andam mais rápido até se acomodarem
+const K_NOVATO: f64 = 40.0;
+const K_PADRAO: f64 = 24.0;
+const PARTIDAS_NOVATO: u32 = 10;
+
+const LIMITE_PADRAO: u32 = 50;
+const LIMITE_MAXIMO: u32 = 500;
+
+const ESQUEMA: &str = "
+CREATE TABLE IF NOT EXISTS perfis (
+    id               INTEGER PRIMARY KEY AUTOINCREMENT,
+    chave_hash       TEXT    NOT NULL UNIQUE,
+    nome             TEXT    NOT NULL,
+    rating           REAL    NOT NULL,
+    partidas         INTEGER NOT NULL DEFAULT 0,
+    vitorias         INTEGER NOT NULL DEFAULT 0,
+    batidas          INTEGER NOT NULL DEFAULT 0,
+    canastras_limpas INTEGER NOT NULL DEFAULT 0,
+    pontos_total     INTEGER NOT NULL DEFAULT 0,
+    criado_em        INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS partidas (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    terminou_em INTEGER NOT NULL,
+    perfil_a1   INTEGER NOT NULL,
+    perfil_a2   INTEGER NOT NULL,
+    perfil_b1   INTEGER NOT NULL,
+    perfil_b2   INTEGER NOT NULL,
+    pontos_a    INTEGER NOT NULL,
+    pontos_b    INTEGER NOT NULL,
+    motivo      TEXT    NOT NULL
+);
+";
+
+/// Resultado de uma partida com os quatro assentos identificados.
+pub struct ResultadoPartida {
+    /// Perfis do time A (assentos 0 e 2) e do time B (assentos 1 e 3)
+    pub time_a: [i64; 2],
+    pub time_b: [i64; 2],
+    pub pontos_a: i32,
+    pub pontos_b: i32,
+    pub batedor: Option<i64>,
+    pub canastras_limpas_a: u32,
+    pub canastras_limpas_b: u32,
+    pub motivo: String,
+}
+
+pub struct Ranking {
+    // Consultas curtas: um Mutex simples basta (e a Connection não é Sync)
+    conn: Mutex<Connection>,
+}
+
+fn agora_s() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn hash_chave(chave: &str) -> String {
+    hex::encode(Sha256::digest(chave.as_bytes()))
+}
+
+fn perfil_da_chave(conn: &Connection, chave: &str) -> rusqlite::Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM perfis WHERE chave_hash = ?1",
+        params![hash_chave(chave)],
+        |linha| linha.get(0),
+    )
+    .optional()
+}
+
+/// Probabilidade de o time com rating `a` vencer o time com rating `b`.
+fn esperado(a: f64, b: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((b - a) / 400.0))
+}
+
+/// Abre (ou cria) o banco configurado (`banco`, padrão `buracao.db`; `:memory:` não grava
+/// nada em disco). Ranking e arquivo de partidas abrem cada um a sua conexão com o mesmo
+/// arquivo. Banco que não abre impede o servidor de subir.
+pub fn abrir_banco(caminho: &str, uso: &str) -> Result<Connection, String> {
+    let conn = if caminho == BANCO_EM_MEMORIA {
+        Connection::open_in_memory()
+    } else {
+        Connection::open(caminho)
+    }
+    .map_err(|e| {
+        format!(
+            "{}: não foi possível abrir o banco '{}' ({})",
+            uso, caminho, e
+        )
+    })?;
+    tracing::info!(%caminho, "{} aberto", uso);
+    // Duas conexões no mesmo arquivo: espera a outra terminar em vez de falhar
+    let _ = conn.busy_timeout(std::time::Duration::from_secs(5));
+    Ok(conn)
+}
+
+impl Ranking {
+    pub fn abrir(caminho: &str) -> Result<Arc<Self>, String> {
+        let conn = abrir_banco(caminho, "Ranking")?;
+        conn.execute_batch(ESQUEMA)
+            .map_err(|e| format!("Ranking: não foi possível criar as tabelas ({})", e))?;
+        Ok(Arc::new(Self {
+            conn: Mutex::new(conn),
+        }))
+    }
+
+    /// Perfil do jogador pela chave apresentada no login. Chave ausente ou desconhecida
+    /// cria um perfil novo e devolve a chave dele (para o cliente guardar).
+    pub fn identificar(
+        &self,
+        chave: Option<&str>,
+        nome: &str,
+    ) -> rusqlite::Result<(i64, Option<String>)> {
+        let conn = self.conn.lock().unwrap();
+
+        if let Some(chave) = chave.filter(|c| !c.is_empty())
+            && let Some(id) = perfil_da_chave(&conn, chave)?
+        {
+            // O ranking mostra o apelido mais recente
+            conn.execute(
+                "UPDATE perfis SET nome = ?1 WHERE id = ?2",
+                params![nome, id],
+            )?;
+            return Ok((id, None));
+        }
+
+        let mut bytes = [0u8; 16];
+        rand::rng().fill_bytes(&mut bytes);
+        let nova_chave = hex::encode(bytes);
+        conn.execute(
+            "INSERT INTO perfis (chave_hash, nome, rating, criado_em) VALUES (?1, ?2, ?3, ?4)",
+            params![hash_chave(&nova_chave), nome, RATING_INICIAL, agora_s()],
+        )?;
+        Ok((conn.last_insert_rowid(), Some(nova_chave)))
+    }
+
+    /// Perfil já existente com essa chave (não cria nada).
+    pub fn buscar(&self, chave: &str) -> rusqlite::Result<Option<i64>> {
+        perfil_da_chave(&self.conn.lock().unwrap(), chave)
+    }
+
+    /// Atualiza ratings e estatísticas dos quatro jogadores e guarda a partida.
+    pub fn registrar_partida(&self, resultado: &ResultadoPartida) -> rusqlite::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let ler = |id: i64| -> rusqlite::Result<(f64, u32)> {
+            tx.query_row(
+                "SELECT rating, partidas FROM perfis WHERE id = ?1",
+                params![id],
+                |l| Ok((l.get(0)?, l.get(1)?)),
+            )
+        };
+        let a: Vec<(f64, u32)> = resultado
+            .time_a
+            .iter()
+            .map(|&id| ler(id))
+            .collect::<Result<_, _>>()?;
+        let b: Vec<(f64, u32)> = resultado
+            .time_b
+            .iter()
+            .map(|&id| ler(id))
+            .collect::<Result<_, _>>()?;
+
+        let media_a = (a[0].0 + a[1].0) / 2.0;
+        let media_b = (b[0].0 + b[1].0) / 2.0;
+        let placar_a = match resultado.pontos_a.cmp(&resultado.pontos_b) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0,
+        };
+        let esperado_a = esperado(media_a, media_b);
+
+        let times = [
+            (
+                &resultado.time_a,
+                &a,
+                placar_a - esperado_a,
+                resultado.pontos_a,
+                resultado.pontos_a > resultado.pontos_b,
+                resultado.canastras_limpas_a,
+            ),
+            (
+                &resultado.time_b,
+                &b,
+                esperado_a - placar_a,
+                resultado.pontos_b,
+                resultado.pontos_b > resultado.pontos_a,
+                resultado.canastras_limpas_b,
+            ),
+        ];
+        for (perfis, atuais, saldo, pontos, venceu, canastras) in times {
+            for (&id, &(_, partidas)) in perfis.iter().zip(atuais.iter()) {
+                let k = if partidas < PARTIDAS_NOVATO {
+                    K_NOVATO
+                } else {
+                    K_PADRAO
+                };
+                tx.execute(
+                    "UPDATE perfis SET
+                        rating = rating + ?1,
+                        partidas = partidas + 1,
+                        vitorias = vitorias + ?2,
+                        batidas = batidas + ?3,
+                        canastras_limpas = canastras_limpas + ?4,
+                        pontos_total = pontos_total + ?5
+                     WHERE id = ?6",
+                    params![
+                        k * saldo,
+                        venceu as u32,
+                        (resultado.batedor == Some(id)) as u32,
+                        canastras,
+                        pontos,
+                        id
+                    ],
+                )?;
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO partidas
+                (terminou_em, perfil_a1, perfil_a2, perfil_b1, perfil_b2, pontos_a, pontos_b, motivo)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                agora_s(),
+                resultado.time_a[0],
+                resultado.time_a[1],
+                resultado.time_b[0],
+                resultado.time_b[1],
+                resultado.pontos_a,
+                resultado.pontos_b,
+                resultado.motivo
+            ],
+        )?;
+        tx.commit()
+    }
+
+    /// Melhores ratings entre quem já jogou pelo menos uma partida.
+    pub fn classificacao(&self, limite: u32) -> rusqlite::Result<Vec<EntradaRanking>> {
+        let conn = self.conn.lock().unwrap();
+        let mut consulta = conn.prepare(
+            "SELECT id, nome, rating, partidas, vitorias, batidas, canastras_limpas, pontos_total
+             FROM perfis WHERE partidas > 0
+             ORDER BY rating DESC, partidas DESC
+             LIMIT ?1",
+        )?;
+        let linhas = consulta.query_map(params![limite], |l| {
+            let partidas: u32 = l.get(3)?;
+            let pontos_total: i64 = l.get(7)?;
+            Ok(EntradaRanking {
+                id_perfil: l.get(0)?,
+                nome: l.get(1)?,
+                rating: l.get(2)?,
+                partidas,
+                vitorias: l.get(4)?,
+                batidas: l.get(5)?,
+                canastras_limpas: l.get(6)?,
+                media_pontos: pontos_total as f64 / partidas.max(1) as f64,
+            })
+        })?;
+        linhas.collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct FiltroRanking {
+    limite: Option<u32>,
+}
+
+/// GET /api/ranking?limite=N
+pub fn rotas(
+    ranking: Arc<Ranking>,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    warp::path!("api" / "ranking")
+        .and(warp::get())
+        .and(warp::query::<FiltroRanking>())
+        .and(warp::any().map(move || ranking.clone()))
+        .and_then(listar)
+}
+
+async fn listar(
+    filtro: FiltroRanking,
+    ranking: Arc<Ranking>,
+) -> Result<warp::reply::Response, Infallible> {
+    let limite = filtro
+        .limite
+        .unwrap_or(LIMITE_PADRAO)
+        .clamp(1, LIMITE_MAXIMO);
+    // SQLite é síncrono: roda fora das threads do runtime
+    let resultado = tokio::task::spawn_blocking(move || ranking.classificacao(limite)).await;
+    Ok(match resultado {
+        Ok(Ok(lista)) => warp::reply::json(&lista).into_response(),
+        Ok(Err(e)) => {
+            tracing::error!(erro = %e, "erro ao ler o ranking");
+            warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({ "erro": "Ranking indisponível." })),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            )
+            .into_response()
+        }
+        Err(_) => warp::http::StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    })
+}
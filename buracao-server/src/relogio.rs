@@ -0,0 +1,220 @@
+use crate::logs;
+use crate::presenca;
+use crate::sala::SalaFraca;
+use crate::state::{MAX_JOGADORES, PlayerId, Room};
+use buracao_core::acoes::{MsgServidor, TempoTurno};
+use buracao_core::lobby::ConfigRelogio;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+
+// --- RELÓGIO DE TURNO ---
+// Cada jogador tem um tempo por turno e um banco de tempo para a partida inteira.
+// Estourou os dois, o servidor joga por ele (ver automatico.rs no core). Quem estoura
+// seguidas vezes é marcado como ausente e passa a ter só alguns segundos por turno,
+// para a mesa não ficar refém de quem largou o jogo; qualquer mensagem dele desfaz isso.
+
+const INTERVALO_VERIFICACAO: Duration = Duration::from_millis(500);
+const ESTOUROS_PARA_AUSENTE: u32 = 2;
+const TEMPO_AUSENTE: Duration = Duration::from_secs(5);
+
+// Identifica a vez que está sendo cronometrada: muda a cada jogada e a cada mão nova
+type MarcaTurno = (PlayerId, u32, u32);
+
+pub struct RelogioTurno {
+    pub config: ConfigRelogio,
+    marca: Option<MarcaTurno>,
+    inicio: Instant,
+    bancos: HashMap<PlayerId, Duration>,
+    estouros: HashMap<PlayerId, u32>,
+    pub ausentes: HashSet<PlayerId>,
+}
+
+impl RelogioTurno {
+    pub fn new(config: ConfigRelogio) -> Self {
+        Self {
+            config,
+            marca: None,
+            inicio: Instant::now(),
+            bancos: HashMap::new(),
+            estouros: HashMap::new(),
+            ausentes: HashSet::new(),
+        }
+    }
+
+    fn limite_turno(&self, pid: PlayerId) -> Duration {
+        if self.ausentes.contains(&pid) {
+            TEMPO_AUSENTE
+        } else {
+            Duration::from_secs(self.config.segundos_por_turno as u64)
+        }
+    }
+
+    fn banco(&self, pid: PlayerId) -> Duration {
+        if self.ausentes.contains(&pid) {
+            return Duration::ZERO;
+        }
+        self.bancos
+            .get(&pid)
+            .copied()
+            .unwrap_or(Duration::from_secs(self.config.banco_segundos as u64))
+    }
+
+    /// Quanto do banco a vez atual já consumiu.
+    fn banco_usado(&self, pid: PlayerId) -> Duration {
+        self.inicio.elapsed().saturating_sub(self.limite_turno(pid))
+    }
+
+    /// Acompanha o jogo: se a vez mudou, desconta do banco o que o jogador anterior usou
+    /// e zera o cronômetro. Com a mesa incompleta ou a partida encerrada o relógio para.
+    /// Devolve true se a vez mudou.
+    pub fn sincronizar(&mut self, atual: Option<MarcaTurno>) -> bool {
+        if self.marca == atual {
+            return false;
+        }
+        if let Some((anterior, _, _)) = self.marca {
+            let restante = self
+                .banco(anterior)
+                .saturating_sub(self.banco_usado(anterior));
+            if !self.ausentes.contains(&anterior) {
+                self.bancos.insert(anterior, restante);
+            }
+        }
+        self.marca = atual;
+        self.inicio = Instant::now();
+        true
+    }
+
+    /// Tempo da vez atual, para ir junto com a visão.
+    pub fn tempo(&self) -> Option<TempoTurno> {
+        let (jogador, _, _) = self.marca?;
+        let decorrido = self.inicio.elapsed();
+        let restante_turno = self.limite_turno(jogador).saturating_sub(decorrido);
+        let banco = self
+            .banco(jogador)
+            .saturating_sub(self.banco_usado(jogador));
+        Some(TempoTurno {
+            jogador,
+            restante_turno_ms: restante_turno.as_millis() as u64,
+            banco_ms: banco.as_millis() as u64,
+        })
+    }
+
+    /// Jogador da vez, se ele já gastou o turno e o banco.
+    pub fn estourado(&self) -> Option<PlayerId> {
+        let (jogador, _, _) = self.marca?;
+        let limite = self.limite_turno(jogador) + self.banco(jogador);
+        (self.inicio.elapsed() >= limite).then_some(jogador)
+    }
+
+    /// Conta o estouro e recomeça o cronômetro (se a jogada automática falhar, não
+    /// tenta de novo a cada verificação). Devolve true se o jogador acabou de virar ausente.
+    pub fn registrar_estouro(&mut self, pid: PlayerId) -> bool {
+        self.bancos.insert(pid, Duration::ZERO);
+        self.inicio = Instant::now();
+        let estouros = self.estouros.entry(pid).or_insert(0);
+        *estouros += 1;
+        *estouros >= ESTOUROS_PARA_AUSENTE && self.ausentes.insert(pid)
+    }
+
+    /// O jogador deu sinal de vida. Devolve true se ele estava marcado como ausente.
+    pub fn voltou(&mut self, pid: PlayerId) -> bool {
+        self.estouros.remove(&pid);
+        let estava_ausente = self.ausentes.remove(&pid);
+        if estava_ausente && self.marca.is_some_and(|(jogador, _, _)| jogador == pid) {
+            // Ganha o turno cheio a partir de agora (o banco já tinha zerado)
+            self.inicio = Instant::now();
+        }
+        estava_ausente
+    }
+
+    pub fn esquecer(&mut self, pid: PlayerId) {
+        self.estouros.remove(&pid);
+        self.ausentes.remove(&pid);
+    }
+}
+
+impl Room {
+    /// Atualiza o relógio com a vez atual do jogo (se a sala tiver relógio).
+    /// Devolve true se o relógio recomeçou ou parou.
+    pub fn sincronizar_relogio(&mut self) -> bool {
+        let mesa_completa = self.sessions.len() as u32 == MAX_JOGADORES;
+        let ativo = mesa_completa && !self.game_state.partida_encerrada;
+        let marca = ativo.then_some((
+            self.game_state.turno_atual,
+            self.game_state.rodada,
+            self.game_state.numero_partida,
+        ));
+        self.relogio
+            .as_mut()
+            .is_some_and(|relogio| relogio.sincronizar(marca))
+    }
+
+    pub fn tempo_turno(&self) -> Option<TempoTurno> {
+        self.relogio.as_ref().and_then(RelogioTurno::tempo)
+    }
+
+    /// Marca o jogador como presente; avisa a mesa se ele estava ausente.
+    pub fn jogador_ativo(&mut self, pid: PlayerId) {
+        let voltou = self.relogio.as_mut().is_some_and(|r| r.voltou(pid));
+        if voltou {
+            presenca::anunciar(self);
+        }
+    }
+}
+
+/// Verifica o relógio da sala periodicamente e joga por quem estourou o tempo.
+/// A tarefa acaba sozinha quando a sala deixa de existir.
+pub fn iniciar(sala: SalaFraca, codigo: &str) {
+    let span = logs::span_sala(codigo);
+    tokio::spawn(
+        async move {
+            let mut intervalo = tokio::time::interval(INTERVALO_VERIFICACAO);
+            loop {
+                intervalo.tick().await;
+                let Some(sala) = sala.promover() else {
+                    break;
+                };
+                if sala.executar(verificar).await.is_none() {
+                    break;
+                }
+            }
+        }
+        .instrument(span),
+    );
+}
+
+fn verificar(room: &mut Room) {
+    // A vez mudou sem jogada (mesa completou, alguém saiu): todos precisam do relógio novo
+    if room.sincronizar_relogio() {
+        room.propagar_estado();
+    }
+    let Some(relogio) = room.relogio.as_mut() else {
+        return;
+    };
+    let Some(pid) = relogio.estourado() else {
+        return;
+    };
+    let virou_ausente = relogio.registrar_estouro(pid);
+
+    let versao = room.game_state.versao;
+    match room.game_state.jogada_automatica(pid) {
+        Ok(resumo) => {
+            tracing::info!(assento = pid, %resumo, "jogada automática por tempo esgotado");
+            room.medir_turno();
+            room.propagar_estado();
+            room.broadcast(&MsgServidor::Notificacao(resumo));
+        }
+        Err(e) => {
+            tracing::warn!(assento = pid, erro = %e, "jogada automática falhou");
+            // A compra pode ter passado antes de faltar um descarte aceito
+            if room.game_state.versao != versao {
+                room.propagar_estado();
+            }
+        }
+    }
+
+    if virou_ausente {
+        presenca::anunciar(room);
+    }
+}
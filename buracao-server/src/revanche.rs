@@ -0,0 +1,213 @@
+use crate::ranking::ResultadoPartida;
+use crate::relogio::RelogioTurno;
+use crate::sessao;
+use crate::state::{MAX_JOGADORES, PlayerId, Room};
+use buracao_core::acoes::{MsgServidor, PlacarSerie, VERSAO_PROTOCOLO, VotoFimDeJogo};
+use std::collections::HashMap;
+
+// --- FIM DE PARTIDA E REVANCHE ---
+// Quando a partida acaba, a mesa recebe o FimDeJogo e cada jogador vota no que fazer:
+// revanche (mesmas duplas, conta para a série), trocar duplas ou sair. Com os quatro
+// assentos ocupados e todos de acordo, as cartas são dadas de novo na mesma sala.
+
+/// Troca de duplas: o jogador 0 fica e os outros rodam (1→2, 2→3, 3→1), então a cada
+/// troca ele joga com um parceiro diferente.
+fn novo_assento(pid: PlayerId) -> PlayerId {
+    match pid {
+        1 => 2,
+        2 => 3,
+        3 => 1,
+        outro => outro,
+    }
+}
+
+fn remapear<V>(mapa: &mut HashMap<PlayerId, V>) {
+    *mapa = mapa
+        .drain()
+        .map(|(pid, v)| (novo_assento(pid), v))
+        .collect();
+}
+
+impl Room {
+    /// Chamado depois de cada mudança no jogo: na primeira vez que a partida aparece
+    /// encerrada, conta a vitória na série e avisa a mesa.
+    pub fn verificar_fim_de_jogo(&mut self) {
+        if !self.game_state.partida_encerrada || self.fim_de_jogo.is_some() {
+            return;
+        }
+
+        let pontos_a = self.game_state.pontuacao_a;
+        let pontos_b = self.game_state.pontuacao_b;
+        let empate = pontos_a == pontos_b;
+        if pontos_a > pontos_b {
+            self.serie.vitorias_a += 1;
+        } else if pontos_b > pontos_a {
+            self.serie.vitorias_b += 1;
+        }
+
+        let msg = MsgServidor::FimDeJogo {
+            vencedor_time: if pontos_b > pontos_a { 1 } else { 0 },
+            pontos_a,
+            pontos_b,
+            motivo: self.game_state.motivo_fim().to_string(),
+            empate,
+            serie: self.serie,
+        };
+        tracing::info!(pontos_a, pontos_b, serie = ?self.serie, "fim de partida");
+
+        self.registrar_no_ranking();
+        self.registrar_no_torneio();
+        self.arquivar_partida();
+        self.votos_fim.clear();
+        self.fim_de_jogo = Some(msg.clone());
+        self.broadcast(&msg);
+    }
+
+    /// Manda o resultado para o ranking, se os quatro assentos tiverem perfil.
+    fn registrar_no_ranking(&self) {
+        let Some(ranking) = self.ranking.clone() else {
+            return;
+        };
+        let perfil = |pid: PlayerId| self.perfis.get(&pid).copied();
+        let (Some(a1), Some(b1), Some(a2), Some(b2)) = (perfil(0), perfil(1), perfil(2), perfil(3))
+        else {
+            tracing::debug!("partida fora do ranking: nem todos os assentos têm perfil");
+            return;
+        };
+
+        let resultado = ResultadoPartida {
+            time_a: [a1, a2],
+            time_b: [b1, b2],
+            pontos_a: self.game_state.pontuacao_a,
+            pontos_b: self.game_state.pontuacao_b,
+            batedor: self.game_state.bateu.and_then(perfil),
+            canastras_limpas_a: self.game_state.canastras_limpas(true),
+            canastras_limpas_b: self.game_state.canastras_limpas(false),
+            motivo: self.game_state.motivo_fim().to_string(),
+        };
+        let span = tracing::Span::current();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = ranking.registrar_partida(&resultado) {
+                span.in_scope(
+                    || tracing::warn!(erro = %e, "não foi possível registrar a partida no ranking"),
+                );
+            }
+        });
+    }
+
+    /// O resumo do fim da partida e os votos até agora (para quem entra ou reconecta depois).
+    pub fn mensagens_fim_de_jogo(&self) -> Vec<MsgServidor> {
+        match &self.fim_de_jogo {
+            Some(fim) => vec![
+                fim.clone(),
+                MsgServidor::VotosFimDeJogo(self.votos_fim.clone()),
+            ],
+            None => Vec::new(),
+        }
+    }
+
+    /// Roda os parceiros, levando junto tudo que o servidor guarda por assento.
+    /// Cada jogador recebe o novo assento e um token novo (o antigo era do outro assento).
+    fn rodar_duplas(&mut self, segredo: &[u8], codigo: &str) {
+        for pid in self.sessions.values_mut() {
+            *pid = novo_assento(*pid);
+        }
+        remapear(&mut self.player_names);
+        remapear(&mut self.clients);
+        remapear(&mut self.seq_jogadores);
+        remapear(&mut self.latencias);
+        remapear(&mut self.limite_chat);
+        remapear(&mut self.ultima_reacao);
+        remapear(&mut self.perfis);
+        for evento in self.eventos.iter_mut() {
+            evento.para = novo_assento(evento.para);
+        }
+        for espectador in self.espectadores.values_mut() {
+            espectador.treinando = espectador.treinando.map(novo_assento);
+        }
+        self.host = self.host.map(novo_assento);
+        self.votos_expulsao.clear();
+        // Ninguém tem mais a visão do assento novo: a próxima propagação manda a foto completa
+        self.reiniciar_visoes();
+
+        let sessoes: Vec<(String, PlayerId)> = self
+            .sessions
+            .iter()
+            .map(|(device, pid)| (device.clone(), *pid))
+            .collect();
+        for (device, pid) in sessoes {
+            self.enviar(
+                pid,
+                &MsgServidor::BoasVindas {
+                    id_jogador: pid,
+                    versao_protocolo: VERSAO_PROTOCOLO,
+                    retomada: true,
+                },
+            );
+            let token = sessao::gerar_token(segredo, codigo, &device, pid);
+            self.enviar(pid, &MsgServidor::Sessao { token });
+        }
+    }
+
+    fn iniciar_nova_partida(&mut self, trocar_duplas: bool, segredo: &[u8], codigo: &str) {
+        if trocar_duplas {
+            self.rodar_duplas(segredo, codigo);
+            // Times diferentes: a série anterior não vale mais
+            self.serie = PlacarSerie::default();
+        } else if self.serie.decidida() {
+            self.serie = PlacarSerie::default();
+        }
+
+        self.game_state = self.game_state.nova_partida();
+        self.fim_de_jogo = None;
+        self.votos_fim.clear();
+        if let Some(relogio) = &self.relogio {
+            self.relogio = Some(RelogioTurno::new(relogio.config));
+        }
+        tracing::info!(trocar_duplas, serie = ?self.serie, "nova partida");
+
+        self.broadcast(&MsgServidor::NovaPartida {
+            serie: self.serie,
+            duplas_trocadas: trocar_duplas,
+        });
+        self.propagar_estado();
+    }
+}
+
+/// Registra o voto do jogador. Com todos de acordo, começa a próxima partida.
+/// `codigo` e `segredo` servem para reemitir os tokens se as duplas forem trocadas.
+pub fn votar(
+    room: &mut Room,
+    autor: PlayerId,
+    voto: VotoFimDeJogo,
+    segredo: &[u8],
+    codigo: &str,
+) -> Result<(), String> {
+    if room.fim_de_jogo.is_none() {
+        return Err("A partida ainda não acabou.".to_string());
+    }
+
+    // Na mesa de torneio quem decide o próximo confronto é a rodada seguinte
+    if room.torneio.is_some() && voto != VotoFimDeJogo::Sair {
+        return Err("Mesa de torneio: o próximo jogo sai na próxima rodada.".to_string());
+    }
+
+    if voto == VotoFimDeJogo::Sair {
+        room.remover_jogador(autor, "Você saiu da sala.");
+        tracing::info!(assento = autor, "jogador saiu depois da partida");
+    } else {
+        room.votos_fim.insert(autor, voto);
+    }
+    room.broadcast(&MsgServidor::VotosFimDeJogo(room.votos_fim.clone()));
+
+    // Unanimidade entre os quatro assentos (quem saiu precisa ser substituído antes)
+    let mesa_completa = room.sessions.len() as u32 == MAX_JOGADORES;
+    let todos_votaram = room
+        .jogadores_sentados()
+        .iter()
+        .all(|pid| room.votos_fim.get(pid) == Some(&voto));
+    if voto != VotoFimDeJogo::Sair && mesa_completa && todos_votaram {
+        room.iniciar_nova_partida(voto == VotoFimDeJogo::TrocarDuplas, segredo, codigo);
+    }
+    Ok(())
+}
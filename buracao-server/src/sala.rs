@@ -0,0 +1,101 @@
+use crate::logs;
+use crate::metricas::METRICAS;
+use crate::relogio;
+use crate::state::Room;
+use std::panic::AssertUnwindSafe;
+use std::time::Instant;
+use tokio::sync::{mpsc, oneshot};
+use tracing::Instrument;
+
+// --- A SALA COMO ATOR ---
+// Cada sala roda numa tarefa própria, dona do `Room`: ninguém mais encosta nele.
+// Conexões, relógio, lobby e administração mandam comandos pelo canal e esperam a
+// resposta. Os comandos de uma sala rodam um de cada vez, na ordem em que chegaram,
+// sem lock nenhum: uma sala ocupada não atrasa as outras nem o login. O que sai da
+// sala vai sem serializar; o JSON é montado na tarefa de cada WebSocket (ver `Saida`),
+// e as visões de cada assento numa tarefa ao lado da sala (ver visoes.rs).
+//
+// A tarefa acaba quando a última `Sala` é solta (fora do mapa e sem conexões).
+
+type Comando = Box<dyn FnOnce(&mut Room) + Send>;
+
+/// Endereço da sala: clonar é barato e todas as cópias falam com a mesma tarefa.
+#[derive(Clone)]
+pub struct Sala {
+    comandos: mpsc::UnboundedSender<Comando>,
+}
+
+/// Endereço que não segura a sala viva (relógio de turno).
+#[derive(Clone)]
+pub struct SalaFraca {
+    comandos: mpsc::WeakUnboundedSender<Comando>,
+}
+
+impl Sala {
+    /// Coloca a sala para rodar (e o relógio dela, se tiver).
+    pub fn abrir(mut room: Room, codigo: &str) -> Sala {
+        let com_relogio = room.relogio.is_some();
+        let (tx, rx) = mpsc::unbounded_channel();
+        let sala = Sala { comandos: tx };
+        room.visoes.iniciar(sala.rebaixar(), codigo);
+        tokio::spawn(rodar(room, rx).instrument(logs::span_sala(codigo)));
+        if com_relogio {
+            relogio::iniciar(sala.rebaixar(), codigo);
+        }
+        sala
+    }
+
+    /// Roda `comando` na sala e devolve o resultado. `None` = a sala não existe mais.
+    /// O log do comando sai no span de quem pediu (a conexão, a rota...).
+    pub async fn executar<R: Send + 'static>(
+        &self,
+        comando: impl FnOnce(&mut Room) -> R + Send + 'static,
+    ) -> Option<R> {
+        let (resposta, espera) = oneshot::channel();
+        let span = tracing::Span::current();
+        let enviado = Instant::now();
+        self.comandos
+            .send(Box::new(move |room: &mut Room| {
+                let _dentro = span.enter();
+                let _ = resposta.send(comando(room));
+            }))
+            .ok()?;
+        let resultado = espera.await.ok();
+        METRICAS.espera_sala(enviado.elapsed());
+        resultado
+    }
+
+    pub fn rebaixar(&self) -> SalaFraca {
+        SalaFraca {
+            comandos: self.comandos.downgrade(),
+        }
+    }
+
+    /// As duas cópias falam com a mesma sala.
+    pub fn mesma(&self, outra: &Sala) -> bool {
+        self.comandos.same_channel(&outra.comandos)
+    }
+}
+
+impl SalaFraca {
+    pub fn promover(&self) -> Option<Sala> {
+        self.comandos.upgrade().map(|comandos| Sala { comandos })
+    }
+
+    /// Põe `comando` na fila da sala sem esperar por ele. `false` = a sala não existe mais.
+    pub fn despachar(&self, comando: impl FnOnce(&mut Room) + Send + 'static) -> bool {
+        self.comandos
+            .upgrade()
+            .is_some_and(|comandos| comandos.send(Box::new(comando)).is_ok())
+    }
+}
+
+async fn rodar(mut room: Room, mut comandos: mpsc::UnboundedReceiver<Comando>) {
+    while let Some(comando) = comandos.recv().await {
+        // Um comando com bug derruba só o pedido dele (quem pediu recebe `None`), não a sala
+        if std::panic::catch_unwind(AssertUnwindSafe(|| comando(&mut room))).is_err() {
+            tracing::error!("comando da sala entrou em pânico");
+        }
+    }
+    tracing::debug!("sala encerrada");
+}
@@ -0,0 +1,72 @@
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+// --- TOKENS DE SESSÃO ASSINADOS ---
+// O servidor assina (sala, device_id, assento) com um segredo que só ele conhece.
+// Na reconexão o cliente precisa devolver o token: saber o device_id de alguém
+// não basta mais para sentar no lugar dele.
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Usa o segredo configurado (`BURACAO_SECRET`) ou sorteia um novo a cada inicialização.
+/// Com segredo sorteado, os tokens deixam de valer quando o servidor reinicia.
+pub fn carregar_segredo(configurado: Option<&str>) -> Vec<u8> {
+    match configurado {
+        Some(segredo) => segredo.as_bytes().to_vec(),
+        None => {
+            let mut segredo = vec![0u8; 32];
+            rand::rng().fill_bytes(&mut segredo);
+            segredo
+        }
+    }
+}
+
+fn assinatura(segredo: &[u8], sala: &str, device_id: &str, player_id: u32) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(segredo).expect("HMAC aceita chave de qualquer tamanho");
+    // Separador nulo para que ("AB", "C") e ("A", "BC") não gerem a mesma mensagem
+    mac.update(sala.as_bytes());
+    mac.update(&[0]);
+    mac.update(device_id.as_bytes());
+    mac.update(&[0]);
+    mac.update(&player_id.to_be_bytes());
+    mac
+}
+
+pub fn gerar_token(segredo: &[u8], sala: &str, device_id: &str, player_id: u32) -> String {
+    let mac = assinatura(segredo, sala, device_id, player_id);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+pub fn validar_token(
+    segredo: &[u8],
+    sala: &str,
+    device_id: &str,
+    player_id: u32,
+    token: &str,
+) -> bool {
+    let Ok(bytes) = hex::decode(token) else {
+        return false;
+    };
+    // verify_slice compara em tempo constante
+    assinatura(segredo, sala, device_id, player_id)
+        .verify_slice(&bytes)
+        .is_ok()
+}
+
+/// Chave sorteada em hexadecimal (chaves de torneio, token de anfitrião).
+pub fn chave_aleatoria(bytes: usize) -> String {
+    let mut aleatorios = vec![0u8; bytes];
+    rand::rng().fill_bytes(&mut aleatorios);
+    hex::encode(aleatorios)
+}
+
+/// Comparação de senha sem atalho no primeiro byte diferente.
+pub fn senha_confere(esperada: &str, informada: &str) -> bool {
+    let (a, b) = (esperada.as_bytes(), informada.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
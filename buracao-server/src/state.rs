@@ -1,20 +1,139 @@
+use crate::arquivo::Arquivo;
+use crate::config::Config;
+use crate::desligamento::SalasSalvas;
+use crate::espectador::{Espectador, PRIMEIRO_ESPECTADOR};
+use crate::metricas::METRICAS;
+use crate::presenca::ConfigHeartbeat;
+use crate::ranking::Ranking;
+use crate::relogio::RelogioTurno;
+use crate::sala::Sala;
+use crate::torneio::{Reserva, TorneiosCompartilhados, VinculoTorneio};
+use crate::visoes::Visoes;
+use buracao_core::acoes::{MensagemChat, MsgServidor, PlacarSerie, VotoFimDeJogo};
 use buracao_core::estado::EstadoJogo;
-use std::collections::HashMap;
+use buracao_core::lobby::{AssentoSala, ConfigEspectadores, NovaSala, ResumoSala, StatusSala};
+use buracao_core::regras::ConfigRegras;
+use dashmap::DashMap;
+use rand::Rng;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
-use tokio::sync::{RwLock, mpsc};
+use std::time::Instant;
+use tokio::sync::mpsc;
 use warp::ws::Message;
 
 // Tipos
-pub type Sender = mpsc::UnboundedSender<Message>;
+pub type Sender = mpsc::UnboundedSender<Saida>;
 pub type RoomCode = String;
 pub type DeviceId = String;
 pub type PlayerId = u32;
 
+pub const MAX_JOGADORES: u32 = 4;
+
+// Quantas mensagens recentes a sala guarda para reenviar a quem reconectar
+pub const EVENTOS_MAX: usize = 512;
+
+// Mesmo formato do `Envelope` do core, mas sem clonar a mensagem para serializar
+#[derive(Serialize)]
+struct EnvelopeRef<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<u64>,
+    msg: &'a MsgServidor,
+}
+
+pub fn envelopar(seq: Option<u64>, msg: &MsgServidor) -> Option<String> {
+    serde_json::to_string(&EnvelopeRef { seq, msg }).ok()
+}
+
+/// O que a sala manda para a tarefa que escreve no WebSocket. Mensagens do jogo vão
+/// sem serializar: o JSON é montado lá, fora da sala (ver sala.rs).
+pub enum Saida {
+    Msg {
+        seq: Option<u64>,
+        msg: Arc<MsgServidor>,
+    },
+    /// Frame pronto (close, ping)
+    Ws(Message),
+}
+
+impl Saida {
+    /// Mensagem sem seq (antes do assento, espectadores, avisos da conexão).
+    pub fn msg(msg: &MsgServidor) -> Self {
+        Saida::Msg {
+            seq: None,
+            msg: Arc::new(msg.clone()),
+        }
+    }
+
+    pub fn em_frame(self) -> Option<Message> {
+        match self {
+            Saida::Msg { seq, msg } => envelopar(seq, &msg).map(Message::text),
+            Saida::Ws(frame) => Some(frame),
+        }
+    }
+}
+
+/// Mensagem já entregue (ou que seria entregue) a um jogador, guardada para replay.
+pub struct EventoEnviado {
+    pub para: PlayerId,
+    pub seq: u64,
+    pub msg: Arc<MsgServidor>,
+}
+
 pub struct Room {
     pub game_state: EstadoJogo,
     pub clients: HashMap<PlayerId, Sender>,
     pub sessions: HashMap<DeviceId, PlayerId>,
     pub player_names: HashMap<PlayerId, String>,
+    // Visões e deltas montados fora da sala (ver visoes.rs)
+    pub visoes: Visoes,
+    // Retomada de conexão: última seq de cada jogador e as mensagens recentes
+    pub seq_jogadores: HashMap<PlayerId, u64>,
+    pub eventos: VecDeque<EventoEnviado>,
+    // Latência medida pelo heartbeat (ms)
+    pub latencias: HashMap<PlayerId, u32>,
+    // Relógio de turno (só em salas criadas com limite de tempo)
+    pub relogio: Option<RelogioTurno>,
+    // Quem só assiste (ver espectador.rs)
+    pub espectadores: HashMap<u32, Espectador>,
+    pub proximo_espectador: u32,
+    pub config_espectadores: ConfigEspectadores,
+    // Fim de partida (ver revanche.rs): resumo já anunciado, votos e placar da série
+    pub fim_de_jogo: Option<MsgServidor>,
+    pub votos_fim: HashMap<PlayerId, VotoFimDeJogo>,
+    pub serie: PlacarSerie,
+    // Perfil persistente de cada assento e onde registrar o resultado (ver ranking.rs)
+    pub perfis: HashMap<PlayerId, i64>,
+    pub ranking: Option<Arc<Ranking>>,
+    pub arquivo: Option<Arc<Arquivo>>,
+    // Mesa de torneio: assentos reservados aos inscritos e a mesa que a sala joga
+    pub reservas: HashMap<PlayerId, Reserva>,
+    pub torneio: Option<VinculoTorneio>,
+    // Desde quando ninguém está conectado (ver lobby::iniciar_limpeza)
+    pub vazia_desde: Option<Instant>,
+    // Rodada em andamento e quando ela começou (ver metricas::medir_turno)
+    pub inicio_turno: Option<(u32, Instant)>,
+    // Dados do lobby
+    pub nome: String,
+    pub publica: bool,
+    pub senha: Option<String>,
+    // Moderação
+    pub host: Option<PlayerId>,
+    // Entregue só a quem criou a sala pelo lobby; quem entrar com ele vira anfitrião (uma vez)
+    pub token_anfitriao: Option<String>,
+    pub trancada: bool,
+    pub banidos: HashSet<DeviceId>,
+    // Assentos passados a outro aparelho pela administração: o primeiro login entra sem token
+    pub assentos_transferidos: HashSet<DeviceId>,
+    // Assentos liberados para o robô: a jogada automática joga por eles até alguém sentar
+    pub robos: HashSet<PlayerId>,
+    pub votos_expulsao: HashMap<PlayerId, HashSet<PlayerId>>, // alvo -> quem votou
+    // Chat
+    pub chat: VecDeque<MensagemChat>,
+    pub limite_chat: HashMap<PlayerId, VecDeque<Instant>>, // envios recentes de cada jogador
+    pub ultima_reacao: HashMap<PlayerId, Instant>,
+    // Já saiu do mapa de salas (limpeza, administração): quem chegar atrasado não entra
+    pub fechada: bool,
 }
 
 impl Room {
@@ -24,27 +143,350 @@ impl Room {
             clients: HashMap::new(),
             sessions: HashMap::new(),
             player_names: HashMap::new(), // Inicializa vazio
+            visoes: Visoes::default(),
+            seq_jogadores: HashMap::new(),
+            eventos: VecDeque::new(),
+            latencias: HashMap::new(),
+            relogio: None,
+            espectadores: HashMap::new(),
+            proximo_espectador: PRIMEIRO_ESPECTADOR,
+            config_espectadores: ConfigEspectadores::default(),
+            fim_de_jogo: None,
+            votos_fim: HashMap::new(),
+            serie: PlacarSerie::default(),
+            perfis: HashMap::new(),
+            ranking: None,
+            arquivo: None,
+            reservas: HashMap::new(),
+            torneio: None,
+            vazia_desde: None,
+            inicio_turno: None,
+            nome: String::new(),
+            publica: false,
+            senha: None,
+            host: None,
+            token_anfitriao: None,
+            trancada: false,
+            banidos: HashSet::new(),
+            assentos_transferidos: HashSet::new(),
+            robos: HashSet::new(),
+            votos_expulsao: HashMap::new(),
+            chat: VecDeque::new(),
+            limite_chat: HashMap::new(),
+            ultima_reacao: HashMap::new(),
+            fechada: false,
+        }
+    }
+
+    /// Cria a sala a partir do pedido feito pelo lobby (já validado), com as regras
+    /// já resolvidas (as do pedido ou as padrão do servidor).
+    pub fn from_nova_sala(pedido: NovaSala, regras: ConfigRegras) -> Self {
+        let mut room = Self::new();
+        room.nome = pedido.nome;
+        room.publica = pedido.publica;
+        room.senha = pedido.senha;
+        room.game_state.regras = regras;
+        room.relogio = pedido.relogio.map(RelogioTurno::new);
+        room.config_espectadores = pedido.espectadores;
+        room
+    }
+
+    pub fn status(&self) -> StatusSala {
+        if self.game_state.partida_encerrada {
+            StatusSala::Encerrada
+        } else if (self.sessions.len() as u32) < MAX_JOGADORES {
+            StatusSala::AguardandoJogadores
+        } else {
+            StatusSala::EmAndamento
+        }
+    }
+
+    /// Algum jogador ou espectador conectado.
+    pub fn tem_conexoes(&self) -> bool {
+        !self.clients.is_empty() || !self.espectadores.is_empty()
+    }
+
+    pub fn assento_ocupado(&self, id: PlayerId) -> bool {
+        self.sessions.values().any(|&pid| pid == id)
+    }
+
+    /// Primeiro assento sem dono (assentos liberados pelo anfitrião voltam a ficar disponíveis).
+    pub fn proximo_assento_livre(&self) -> Option<PlayerId> {
+        (0..MAX_JOGADORES).find(|&id| !self.assento_ocupado(id))
+    }
+
+    // --- ENVIO DE MENSAGENS ---
+
+    /// Numera e guarda a mensagem mesmo se o jogador estiver offline: ao voltar ele recebe o que perdeu.
+    pub fn enviar(&mut self, pid: PlayerId, msg: &MsgServidor) {
+        self.entregar(pid, Arc::new(msg.clone()));
+    }
+
+    /// Como `enviar`, sem copiar a mensagem (a mesma vai para vários jogadores e para o buffer).
+    pub(crate) fn entregar(&mut self, pid: PlayerId, msg: Arc<MsgServidor>) {
+        let seq = if self.assento_ocupado(pid) {
+            let contador = self.seq_jogadores.entry(pid).or_insert(0);
+            *contador += 1;
+            Some(*contador)
+        } else {
+            None
+        };
+
+        if let Some(client_tx) = self.clients.get(&pid)
+            && client_tx
+                .send(Saida::Msg {
+                    seq,
+                    msg: msg.clone(),
+                })
+                .is_err()
+        {
+            METRICAS.falha_envio();
+        }
+        if let Some(seq) = seq {
+            self.eventos.push_back(EventoEnviado {
+                para: pid,
+                seq,
+                msg,
+            });
+            while self.eventos.len() > EVENTOS_MAX {
+                self.eventos.pop_front();
+            }
+        }
+    }
+
+    /// Todos os assentos ocupados (conectados ou não).
+    pub fn jogadores_sentados(&self) -> Vec<PlayerId> {
+        let mut ids: Vec<PlayerId> = self.sessions.values().copied().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Para a mesa inteira, incluindo quem assiste.
+    pub fn broadcast(&mut self, msg: &MsgServidor) {
+        let compartilhada = Arc::new(msg.clone());
+        for pid in self.jogadores_sentados() {
+            self.entregar(pid, compartilhada.clone());
+        }
+        self.enviar_espectadores(msg);
+    }
+
+    /// Dá para retomar a partir de `ultimo_seq` só se nada do que veio depois saiu do buffer.
+    pub fn pode_retomar(&self, pid: PlayerId, ultimo_seq: u64) -> bool {
+        let atual = self.seq_jogadores.get(&pid).copied().unwrap_or(0);
+        if ultimo_seq > atual {
+            // Seq de outra vida da sala (ex.: servidor reiniciou)
+            return false;
+        }
+        ultimo_seq == atual
+            || self
+                .eventos
+                .iter()
+                .any(|e| e.para == pid && e.seq == ultimo_seq + 1)
+    }
+
+    /// Reentrega, na ordem, o que o jogador perdeu depois de `ultimo_seq`.
+    pub fn reenviar_desde(&self, pid: PlayerId, ultimo_seq: u64) -> usize {
+        let Some(client_tx) = self.clients.get(&pid) else {
+            return 0;
+        };
+        let mut total = 0;
+        for evento in self
+            .eventos
+            .iter()
+            .filter(|e| e.para == pid && e.seq > ultimo_seq)
+        {
+            let _ = client_tx.send(Saida::Msg {
+                seq: Some(evento.seq),
+                msg: evento.msg.clone(),
+            });
+            total += 1;
+        }
+        total
+    }
+
+    /// Foto completa do jogo para um jogador (login, reconexão ou Resync).
+    pub fn enviar_estado(&mut self, pid: PlayerId) {
+        self.sincronizar_relogio();
+        let tempo = self.tempo_turno();
+        self.reenviar_visao(pid, tempo);
+    }
+
+    /// Depois de uma mudança no jogo: cada jogador recebe só o que mudou na visão dele e,
+    /// se a vez passou para um assento do robô, ele joga (ver moderacao.rs).
+    pub fn propagar_estado(&mut self) {
+        self.enviar_visoes();
+        self.jogar_pelos_robos();
+    }
+
+    /// Só as visões, sem a vez do robô: quem joga por ele chama isto a cada jogada.
+    pub(crate) fn enviar_visoes(&mut self) {
+        self.sincronizar_relogio();
+        let tempo = self.tempo_turno();
+        // Inclui quem está offline: o delta fica no buffer para a retomada
+        self.propagar_visoes(tempo);
+        self.verificar_fim_de_jogo();
+    }
+
+    pub fn info_sala(&self) -> MsgServidor {
+        MsgServidor::InfoSala {
+            host: self.host,
+            trancada: self.trancada,
+            votos_expulsao: self
+                .votos_expulsao
+                .iter()
+                .map(|(alvo, votos)| (*alvo, votos.len()))
+                .collect(),
+            espectadores: self.espectadores.len(),
+        }
+    }
+
+    /// Tira o jogador do assento: avisa, fecha a conexão e esquece a sessão.
+    /// A mão dele continua no jogo para quem sentar no lugar.
+    /// Devolve o device_id que ocupava o assento.
+    pub fn remover_jogador(&mut self, pid: PlayerId, motivo: &str) -> Option<DeviceId> {
+        self.enviar(
+            pid,
+            &MsgServidor::Expulso {
+                motivo: motivo.to_string(),
+            },
+        );
+        if let Some(client_tx) = self.clients.remove(&pid) {
+            let _ = client_tx.send(Saida::Ws(Message::close()));
+        }
+
+        let device_id = self
+            .sessions
+            .iter()
+            .find(|(_, id)| **id == pid)
+            .map(|(device, _)| device.clone());
+        if let Some(device) = &device_id {
+            self.sessions.remove(device);
+        }
+
+        self.player_names.remove(&pid);
+        self.reiniciar_visao(pid);
+        self.seq_jogadores.remove(&pid);
+        self.latencias.remove(&pid);
+        self.perfis.remove(&pid);
+        if let Some(relogio) = self.relogio.as_mut() {
+            relogio.esquecer(pid);
+        }
+        self.eventos.retain(|e| e.para != pid);
+
+        // Votos contra ele e votos dados por ele não valem mais
+        self.votos_expulsao.remove(&pid);
+        self.votos_fim.remove(&pid);
+        for votos in self.votos_expulsao.values_mut() {
+            votos.remove(&pid);
+        }
+
+        if self.host == Some(pid) {
+            self.host = self.sessions.values().min().copied();
+        }
+
+        device_id
+    }
+
+    pub fn resumo(&self, codigo: &str) -> ResumoSala {
+        let assentos = (0..MAX_JOGADORES)
+            .map(|id| AssentoSala {
+                id,
+                nome: if self.assento_ocupado(id) {
+                    self.player_names.get(&id).cloned()
+                } else {
+                    None
+                },
+                conectado: self.clients.get(&id).is_some_and(|tx| !tx.is_closed()),
+            })
+            .collect();
+
+        ResumoSala {
+            codigo: codigo.to_string(),
+            nome: self.nome.clone(),
+            publica: self.publica,
+            tem_senha: self.senha.is_some(),
+            status: self.status(),
+            assentos,
+            regras: self.game_state.regras.clone(),
+            relogio: self.relogio.as_ref().map(|r| r.config),
+            espectadores: self.config_espectadores,
+            qtd_espectadores: self.espectadores.len(),
         }
     }
 }
 // --- ESTADO GLOBAL DO SERVIDOR ---
 pub struct ServerState {
-    // Salas Ativas: "SALA-1" -> Dados da Sala
-    // Usamos RwLock individual na Sala para que ações na Sala A não bloqueiem a Sala B
-    pub rooms: HashMap<RoomCode, Arc<RwLock<Room>>>,
+    // Salas Ativas: "SALA-1" -> endereço da sala (cada sala roda na própria tarefa, ver sala.rs)
+    // Mapa concorrente: abrir ou achar uma sala não espera nenhuma outra
+    pub rooms: DashMap<RoomCode, Sala>,
+    // Chave usada para assinar os tokens de sessão (ver sessao.rs)
+    pub segredo: Vec<u8>,
+    pub heartbeat: ConfigHeartbeat,
+    pub ranking: Arc<Ranking>,
+    pub arquivo: Arc<Arquivo>,
+    // Salas guardadas no desligamento (ver desligamento.rs)
+    pub salas_salvas: Arc<SalasSalvas>,
+    pub torneios: TorneiosCompartilhados,
+    pub config: Arc<Config>,
 }
 
 impl ServerState {
-    pub fn new() -> Self {
-        Self {
-            rooms: HashMap::new(),
+    pub fn new(config: Arc<Config>) -> Result<Self, String> {
+        let salas_salvas = SalasSalvas::abrir(&config.banco)?;
+        let guardado = salas_salvas
+            .retirar()
+            .map_err(|e| format!("Salas salvas: não foi possível ler ({})", e))?;
+        // Sem segredo configurado, o sorteado antes do reinício mantém os tokens válidos
+        let segredo = match (&config.segredo, guardado.segredo) {
+            (None, Some(segredo)) => segredo,
+            _ => crate::sessao::carregar_segredo(config.segredo.as_deref()),
+        };
+        let mut server = Self {
+            rooms: DashMap::new(),
+            segredo,
+            heartbeat: config.heartbeat,
+            ranking: Ranking::abrir(&config.banco)?,
+            arquivo: Arquivo::abrir(&config.banco)?,
+            salas_salvas,
+            torneios: TorneiosCompartilhados::default(),
+            config,
+        };
+        crate::desligamento::restaurar(&mut server, guardado.salas, guardado.torneios);
+        Ok(server)
+    }
+
+    /// Recusa abrir mais `novas` salas quando o limite configurado seria ultrapassado.
+    /// Duas salas abertas ao mesmo tempo podem passar juntas do limite por uma: aceitável.
+    pub fn cabem_salas(&self, novas: usize) -> Result<(), String> {
+        if self.rooms.len() + novas > self.config.max_salas {
+            return Err(
+                "O servidor atingiu o limite de salas abertas. Tente mais tarde.".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Sorteia um código curto que ainda não esteja em uso.
+    /// Evita letras e números que se confundem (O/0, I/1) para facilitar ditar o código.
+    pub fn gerar_codigo_sala(&self) -> RoomCode {
+        const ALFABETO: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+        let mut rng = rand::rng();
+
+        loop {
+            let codigo: String = (0..5)
+                .map(|_| ALFABETO[rng.random_range(0..ALFABETO.len())] as char)
+                .collect();
+
+            if !self.rooms.contains_key(&codigo) {
+                return codigo;
+            }
         }
     }
 }
 
-// O tipo que será passado para o Warp
-pub type GlobalState = Arc<RwLock<ServerState>>;
+// O tipo que será passado para o Warp (sem lock: o que muda tem o próprio, ver `rooms`)
+pub type GlobalState = Arc<ServerState>;
 
-pub fn inicializar_servidor() -> GlobalState {
-    Arc::new(RwLock::new(ServerState::new()))
+pub fn inicializar_servidor(config: Arc<Config>) -> Result<GlobalState, String> {
+    Ok(Arc::new(ServerState::new(config)?))
 }
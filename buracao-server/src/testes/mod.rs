@@ -0,0 +1,299 @@
+// --- TESTES DE INTEGRAÇÃO ---
+// Sobem as rotas de verdade numa porta livre (no próprio processo, banco em memória) e
+// falam com elas por WebSocket, como o navegador faria (e pela API REST, com `http`).
+// `ServidorTeste` também dá acesso direto às salas, para montar uma mão conhecida antes
+// de jogar.
+//
+// Para afirmar sobre o que chega ao cliente: `esperar!(cliente, MsgServidor::X { .. } => ...)`
+// descarta as mensagens até a primeira que casar com o padrão (ou falha depois de `PRAZO`).
+
+mod moderacao;
+mod partida;
+mod protocolo;
+mod torneio;
+
+use crate::config::Config;
+use crate::state::{self, GlobalState, Room};
+use buracao_core::acoes::{
+    AcaoJogador, DadosLogin, Envelope, MsgCliente, MsgServidor, VERSAO_PROTOCOLO,
+};
+use futures::{SinkExt, StreamExt};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+/// Quanto esperar cada mensagem antes de dar o teste por travado
+pub const PRAZO: Duration = Duration::from_secs(5);
+
+/// Descarta mensagens até a primeira que casar com o padrão e devolve o valor do braço.
+macro_rules! esperar {
+    ($cliente:expr, $padrao:pat => $valor:expr) => {
+        $cliente
+            .esperar(|msg| match msg {
+                $padrao => Some($valor),
+                _ => None,
+            })
+            .await
+    };
+    ($cliente:expr, $padrao:pat if $guarda:expr => $valor:expr) => {
+        $cliente
+            .esperar(|msg| match msg {
+                $padrao if $guarda => Some($valor),
+                _ => None,
+            })
+            .await
+    };
+    ($cliente:expr, $padrao:pat if $guarda:expr) => {
+        esperar!($cliente, $padrao if $guarda => ())
+    };
+    ($cliente:expr, $padrao:pat) => {
+        esperar!($cliente, $padrao => ())
+    };
+}
+pub(crate) use esperar;
+
+pub struct ServidorTeste {
+    pub estado: GlobalState,
+    endereco: SocketAddr,
+}
+
+impl ServidorTeste {
+    /// Servidor novo, com as opções padrão mais `opcoes` (`["--max-salas", "2"]`...).
+    pub async fn iniciar_com(opcoes: &[&str]) -> Self {
+        // O site só precisa existir para a configuração aceitar
+        let site = std::env::temp_dir().join(format!("buracao-testes-{}", std::process::id()));
+        std::fs::create_dir_all(&site).unwrap();
+        std::fs::write(site.join("index.html"), "<html></html>").unwrap();
+
+        let mut args = vec![
+            "--banco".to_string(),
+            crate::config::BANCO_EM_MEMORIA.to_string(),
+            "--site".to_string(),
+            site.display().to_string(),
+        ];
+        args.extend(opcoes.iter().map(|o| o.to_string()));
+        // Sem ambiente: a configuração da máquina não vaza para o teste
+        let config = Config::de_fontes(&args, |_| None).expect("configuração de teste");
+        let estado = state::inicializar_servidor(config.into()).expect("estado do servidor");
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endereco = listener.local_addr().unwrap();
+        tokio::spawn(
+            warp::serve(crate::rotas(estado.clone()))
+                .incoming(listener)
+                .run(),
+        );
+        Self { estado, endereco }
+    }
+
+    pub async fn iniciar() -> Self {
+        Self::iniciar_com(&[]).await
+    }
+
+    pub fn url(&self) -> String {
+        format!("ws://{}/buraco", self.endereco)
+    }
+
+    /// Pedido REST (HTTP/1.0 à mão): devolve o status e o corpo JSON da resposta.
+    pub async fn http(
+        &self,
+        metodo: &str,
+        caminho: &str,
+        cabecalhos: &[(&str, &str)],
+        corpo: Option<serde_json::Value>,
+    ) -> (u16, serde_json::Value) {
+        let corpo = corpo.map(|c| c.to_string()).unwrap_or_default();
+        let mut pedido = format!(
+            "{} {} HTTP/1.0\r\nHost: {}\r\n",
+            metodo, caminho, self.endereco
+        );
+        for (nome, valor) in cabecalhos {
+            pedido.push_str(&format!("{}: {}\r\n", nome, valor));
+        }
+        pedido.push_str(&format!(
+            "Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            corpo.len(),
+            corpo
+        ));
+
+        let mut conexao = TcpStream::connect(self.endereco).await.unwrap();
+        conexao.write_all(pedido.as_bytes()).await.unwrap();
+        let mut resposta = String::new();
+        tokio::time::timeout(PRAZO, conexao.read_to_string(&mut resposta))
+            .await
+            .expect("nenhuma resposta HTTP dentro do prazo")
+            .unwrap();
+        let (cabecalho, corpo) = resposta.split_once("\r\n\r\n").expect("resposta sem corpo");
+        let status = cabecalho
+            .split_whitespace()
+            .nth(1)
+            .and_then(|s| s.parse().ok())
+            .expect("resposta sem status");
+        let json = serde_json::from_str(corpo)
+            .unwrap_or_else(|e| panic!("corpo não é JSON ({}): {}", e, corpo));
+        (status, json)
+    }
+
+    /// Roda `comando` dentro da sala (ela precisa existir).
+    pub async fn na_sala<R: Send + 'static>(
+        &self,
+        codigo: &str,
+        comando: impl FnOnce(&mut Room) -> R + Send + 'static,
+    ) -> R {
+        let sala = self
+            .estado
+            .rooms
+            .get(codigo)
+            .map(|sala| sala.clone())
+            .unwrap_or_else(|| panic!("sala {} não existe", codigo));
+        sala.executar(comando).await.expect("a sala não respondeu")
+    }
+
+    /// Quatro jogadores sentados na mesma sala, do assento 0 ao 3.
+    pub async fn mesa_completa(&self, codigo: &str) -> Vec<Cliente> {
+        let mut mesa = Vec::new();
+        for assento in 0..4 {
+            let cliente = Cliente::login(self, &format!("J{}", assento), codigo).await;
+            assert_eq!(cliente.id, Some(assento));
+            mesa.push(cliente);
+        }
+        mesa
+    }
+}
+
+static DEVICES: AtomicU32 = AtomicU32::new(0);
+
+/// `device_id` que nenhum outro cliente do processo usa.
+pub fn novo_device() -> String {
+    format!("teste-{}", DEVICES.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Login com o protocolo atual e os opcionais vazios.
+pub fn dados_login(nome: &str, device_id: &str, sala: &str) -> DadosLogin {
+    DadosLogin {
+        versao: VERSAO_PROTOCOLO,
+        device_id: device_id.to_string(),
+        nome: nome.to_string(),
+        sala: sala.to_string(),
+        senha: None,
+        token: None,
+        ultimo_seq: None,
+        espectador: None,
+        identidade: None,
+        token_anfitriao: None,
+    }
+}
+
+pub struct Cliente {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    pub device_id: String,
+    /// Assento dado pelo `BoasVindas`
+    pub id: Option<u32>,
+    /// Último token recebido (para reconectar)
+    pub token: Option<String>,
+    /// Maior `seq` recebida até agora
+    pub ultimo_seq: Option<u64>,
+}
+
+impl Cliente {
+    pub async fn conectar(servidor: &ServidorTeste) -> Self {
+        let (ws, _) = tokio_tungstenite::connect_async(servidor.url())
+            .await
+            .expect("conexão WebSocket");
+        Self {
+            ws,
+            device_id: String::new(),
+            id: None,
+            token: None,
+            ultimo_seq: None,
+        }
+    }
+
+    /// Conecta e manda o login, sem esperar resposta (para testar recusas).
+    pub async fn entrar(servidor: &ServidorTeste, dados: DadosLogin) -> Self {
+        let mut cliente = Self::conectar(servidor).await;
+        cliente.device_id = dados.device_id.clone();
+        cliente.enviar(&MsgCliente::Login(dados)).await;
+        cliente
+    }
+
+    /// Jogador novo sentado na sala, já com assento e token.
+    pub async fn login(servidor: &ServidorTeste, nome: &str, sala: &str) -> Self {
+        Self::login_com(servidor, dados_login(nome, &novo_device(), sala)).await
+    }
+
+    pub async fn login_com(servidor: &ServidorTeste, dados: DadosLogin) -> Self {
+        let mut cliente = Self::entrar(servidor, dados).await;
+        cliente.id =
+            Some(esperar!(cliente, MsgServidor::BoasVindas { id_jogador, .. } => *id_jogador));
+        cliente.token = Some(esperar!(cliente, MsgServidor::Sessao { token } => token.clone()));
+        cliente
+    }
+
+    pub async fn enviar(&mut self, msg: &MsgCliente) {
+        let json = serde_json::to_string(msg).unwrap();
+        self.enviar_texto(&json).await;
+    }
+
+    /// Texto cru, do jeito que estiver (JSON quebrado, campos a mais...).
+    pub async fn enviar_texto(&mut self, texto: &str) {
+        self.ws
+            .send(Message::text(texto))
+            .await
+            .expect("envio pelo WebSocket");
+    }
+
+    pub async fn jogar(&mut self, acao: AcaoJogador) {
+        self.enviar(&MsgCliente::Acao(acao)).await;
+    }
+
+    /// Próximo frame que não seja ping/pong; `None` quando a conexão fecha.
+    async fn proximo(&mut self) -> Option<Message> {
+        loop {
+            let frame = tokio::time::timeout(PRAZO, self.ws.next())
+                .await
+                .expect("nenhuma mensagem do servidor dentro do prazo");
+            match frame {
+                Some(Ok(Message::Ping(_) | Message::Pong(_))) => continue,
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return None,
+                Some(Ok(frame)) => return Some(frame),
+            }
+        }
+    }
+
+    /// Próxima mensagem do servidor (anota a `seq`). Falha se a conexão fechar.
+    pub async fn receber(&mut self) -> MsgServidor {
+        let frame = self.proximo().await.expect("o servidor fechou a conexão");
+        let texto = frame.into_text().expect("mensagem de texto");
+        let envelope: Envelope = serde_json::from_str(&texto)
+            .unwrap_or_else(|e| panic!("envelope inválido ({}): {}", e, texto.as_str()));
+        if envelope.seq.is_some() {
+            self.ultimo_seq = envelope.seq;
+        }
+        envelope.msg
+    }
+
+    /// Descarta mensagens até `filtro` aceitar uma (ver a macro `esperar!`).
+    pub async fn esperar<T>(&mut self, mut filtro: impl FnMut(&MsgServidor) -> Option<T>) -> T {
+        loop {
+            let msg = self.receber().await;
+            if let Some(valor) = filtro(&msg) {
+                return valor;
+            }
+        }
+    }
+
+    /// Espera o servidor fechar a conexão, descartando o que vier antes.
+    pub async fn esperar_fechamento(&mut self) {
+        while self.proximo().await.is_some() {}
+    }
+
+    /// Fecha do lado do cliente (como uma aba fechada).
+    pub async fn fechar(mut self) {
+        let _ = self.ws.close(None).await;
+    }
+}
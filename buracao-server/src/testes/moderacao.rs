@@ -0,0 +1,121 @@
+use super::{Cliente, ServidorTeste, dados_login, esperar, novo_device};
+use buracao_core::acoes::{AcaoJogador, ComandoSala, MsgCliente, MsgServidor};
+use buracao_core::registro::Lance;
+
+#[tokio::test]
+async fn reconectar_nao_devolve_o_posto_de_anfitriao() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut ana = Cliente::login(&servidor, "Ana", "POSTO").await;
+    let _bia = Cliente::login(&servidor, "Bia", "POSTO").await;
+
+    ana.enviar(&MsgCliente::Sala(ComandoSala::TransferirHost {
+        jogador: 1,
+    }))
+    .await;
+    esperar!(ana, MsgServidor::Notificacao(texto) if texto.contains("anfitrião"));
+    let (device, token) = (ana.device_id.clone(), ana.token.clone());
+    ana.fechar().await;
+
+    let mut dados = dados_login("Ana", &device, "POSTO");
+    dados.token = token;
+    let de_volta = Cliente::login_com(&servidor, dados).await;
+    assert_eq!(de_volta.id, Some(0));
+    let host = servidor.na_sala("POSTO", |room| room.host).await;
+    assert_eq!(host, Some(1));
+}
+
+#[tokio::test]
+async fn com_dois_na_mesa_um_voto_nao_expulsa() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut ana = Cliente::login(&servidor, "Ana", "VOTO").await;
+    let _bia = Cliente::login(&servidor, "Bia", "VOTO").await;
+
+    ana.enviar(&MsgCliente::Sala(ComandoSala::VotarExpulsao { jogador: 1 }))
+        .await;
+    let texto =
+        esperar!(ana, MsgServidor::Notificacao(texto) if texto.contains("Voto") => texto.clone());
+    assert!(texto.contains("1/2"), "notificação inesperada: {}", texto);
+    let sentados = servidor.na_sala("VOTO", |room| room.sessions.len()).await;
+    assert_eq!(sentados, 2);
+}
+
+#[tokio::test]
+async fn robo_joga_pelo_assento_liberado_ate_alguem_sentar() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut mesa = servidor.mesa_completa("ROBO").await;
+
+    // A vez precisa ser de outro assento que não o do anfitrião
+    let vez = servidor
+        .na_sala("ROBO", |room| {
+            if room.game_state.turno_atual == 0 {
+                room.game_state.jogada_automatica(0).unwrap();
+                room.propagar_estado();
+            }
+            room.game_state.turno_atual
+        })
+        .await;
+
+    mesa[0]
+        .enviar(&MsgCliente::Sala(ComandoSala::LiberarAssento {
+            jogador: vez,
+            robo: true,
+        }))
+        .await;
+    esperar!(mesa[0], MsgServidor::Notificacao(texto) if texto.contains("robô"));
+
+    let (turno, robos, ultimo) = servidor
+        .na_sala("ROBO", |room| {
+            (
+                room.game_state.turno_atual,
+                room.robos.clone(),
+                room.game_state.lances.last().cloned(),
+            )
+        })
+        .await;
+    assert_ne!(turno, vez);
+    assert!(robos.contains(&vez));
+    assert!(matches!(
+        ultimo,
+        Some(Lance::Acao { jogador, acao: AcaoJogador::Descartar { .. } }) if jogador == vez
+    ));
+
+    let eva = Cliente::login(&servidor, "Eva", "ROBO").await;
+    assert_eq!(eva.id, Some(vez));
+    let robos = servidor.na_sala("ROBO", |room| room.robos.len()).await;
+    assert_eq!(robos, 0);
+}
+
+#[tokio::test]
+async fn token_da_criacao_faz_o_criador_anfitriao_uma_vez() {
+    let servidor = ServidorTeste::iniciar().await;
+    let (status, criada) = servidor
+        .http(
+            "POST",
+            "/api/salas",
+            &[],
+            Some(serde_json::json!({ "nome": "Mesa da Ana" })),
+        )
+        .await;
+    assert_eq!(status, 201, "{}", criada);
+    let codigo = criada["codigo"].as_str().unwrap().to_string();
+    let token = criada["token_anfitriao"].as_str().unwrap().to_string();
+
+    // Chegou primeiro, mas quem criou a sala ainda não entrou
+    let _bia = Cliente::login(&servidor, "Bia", &codigo).await;
+    let mut dados = dados_login("Ana", &novo_device(), &codigo);
+    dados.token_anfitriao = Some(token.clone());
+    let ana = Cliente::login_com(&servidor, dados).await;
+    let host = servidor.na_sala(&codigo, |room| room.host).await;
+    assert_eq!(host, ana.id);
+
+    // O token já foi gasto: quem o copiou não toma o posto
+    let mut dados = dados_login("Caio", &novo_device(), &codigo);
+    dados.token_anfitriao = Some(token);
+    let _caio = Cliente::login_com(&servidor, dados).await;
+    let host = servidor.na_sala(&codigo, |room| room.host).await;
+    assert_eq!(host, ana.id);
+
+    // A listagem pública não entrega o token
+    let (_, lista) = servidor.http("GET", "/api/salas", &[], None).await;
+    assert!(lista[0].get("token_anfitriao").is_none(), "{}", lista);
+}
@@ -0,0 +1,220 @@
+use super::{Cliente, ServidorTeste, esperar};
+use buracao_core::acoes::{AcaoJogador, MsgCliente, MsgServidor, VotoFimDeJogo};
+use buracao_core::baralho::{Carta, Naipe, Valor, Verso};
+use std::collections::HashMap;
+
+fn carta(valor: Valor, naipe: Naipe) -> Carta {
+    Carta {
+        naipe,
+        valor,
+        verso: Verso::Blue,
+    }
+}
+
+/// Mão montada para acabar em poucos lances: o time A já tem uma canastra limpa de
+/// copas (4 a 10) na mesa e o jogador 0 só tem o rei de espadas. Cada um compra e
+/// descarta a carta comprada; na volta o jogador 0 compra o valete de copas, ajunta
+/// na canastra e bate descartando o rei.
+async fn preparar_mao_para_batida(servidor: &ServidorTeste, codigo: &str) {
+    servidor
+        .na_sala(codigo, |room| {
+            let jogo = &mut room.game_state;
+            let canastra: Vec<Carta> = [
+                Valor::Quatro,
+                Valor::Cinco,
+                Valor::Seis,
+                Valor::Sete,
+                Valor::Oito,
+                Valor::Nove,
+                Valor::Dez,
+            ]
+            .into_iter()
+            .map(|valor| carta(valor, Naipe::Copas))
+            .collect();
+            jogo.jogos_time_a = HashMap::from([(0, canastra)]);
+            jogo.jogos_time_b.clear();
+            jogo.proximo_id_jogo = 1;
+            jogo.tres_vermelhos_time_a.clear();
+            jogo.tres_vermelhos_time_b.clear();
+
+            jogo.maos = vec![
+                vec![carta(Valor::Rei, Naipe::Espadas)],
+                vec![
+                    carta(Valor::Nove, Naipe::Ouros),
+                    carta(Valor::Dez, Naipe::Ouros),
+                ],
+                vec![
+                    carta(Valor::Nove, Naipe::Paus),
+                    carta(Valor::Dez, Naipe::Paus),
+                ],
+                vec![
+                    carta(Valor::Dama, Naipe::Ouros),
+                    carta(Valor::Rei, Naipe::Ouros),
+                ],
+            ];
+            // O monte sai do fim: 5♣, 6♣, 7♣, 8♣ e então o valete de copas
+            let mut monte: Vec<Carta> = [Valor::Quatro, Valor::Dama, Valor::Rei]
+                .into_iter()
+                .map(|valor| carta(valor, Naipe::Espadas))
+                .collect();
+            monte.push(carta(Valor::Valete, Naipe::Copas));
+            monte.extend(
+                [Valor::Oito, Valor::Sete, Valor::Seis, Valor::Cinco]
+                    .into_iter()
+                    .map(|valor| carta(valor, Naipe::Paus)),
+            );
+            jogo.baralho.cartas = monte;
+            jogo.qtd_monte = jogo.baralho.cartas.len() as u32;
+            jogo.lixo.clear();
+            jogo.qtd_lixo = 0;
+
+            jogo.turno_atual = 0;
+            jogo.rodada = 0;
+            jogo.comprou_nesta_rodada = false;
+            jogo.pegou_lixo_nesta_rodada = false;
+            jogo.partida_encerrada = false;
+            jogo.marcar_alteracao();
+            room.propagar_estado();
+        })
+        .await;
+}
+
+/// Compra do monte e descarta a carta comprada.
+async fn comprar_e_descartar(jogador: &mut Cliente, comprada: Carta) {
+    jogador.jogar(AcaoJogador::ComprarBaralho).await;
+    esperar!(jogador, MsgServidor::Notificacao(texto) if texto.starts_with("Você comprou"));
+    jogador
+        .jogar(AcaoJogador::Descartar { carta: comprada })
+        .await;
+    esperar!(jogador, MsgServidor::Notificacao(texto) if texto.contains("descartada"));
+}
+
+/// Joga a mão preparada até a batida do jogador 0 e devolve o `FimDeJogo` que cada
+/// jogador recebeu.
+async fn jogar_ate_a_batida(mesa: &mut [Cliente]) -> Vec<MsgServidor> {
+    let compras =
+        [Naipe::Paus; 4]
+            .into_iter()
+            .zip([Valor::Cinco, Valor::Seis, Valor::Sete, Valor::Oito]);
+    for (jogador, (naipe, valor)) in mesa.iter_mut().zip(compras) {
+        comprar_e_descartar(jogador, carta(valor, naipe)).await;
+    }
+
+    let valete = carta(Valor::Valete, Naipe::Copas);
+    mesa[0].jogar(AcaoJogador::ComprarBaralho).await;
+    esperar!(mesa[0], MsgServidor::Notificacao(texto) if texto.starts_with("Você comprou"));
+    mesa[0]
+        .jogar(AcaoJogador::Ajuntar {
+            indice_jogo: 0,
+            cartas: vec![valete],
+        })
+        .await;
+    esperar!(mesa[0], MsgServidor::Notificacao(texto) if texto.contains("inseridas"));
+    mesa[0]
+        .jogar(AcaoJogador::Descartar {
+            carta: carta(Valor::Rei, Naipe::Espadas),
+        })
+        .await;
+
+    let mut fins = Vec::new();
+    for jogador in mesa.iter_mut() {
+        fins.push(esperar!(jogador, fim @ MsgServidor::FimDeJogo { .. } => fim.clone()));
+    }
+    fins
+}
+
+#[tokio::test]
+async fn mao_roteirizada_termina_em_batida() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut mesa = servidor.mesa_completa("BATIDA").await;
+    preparar_mao_para_batida(&servidor, "BATIDA").await;
+
+    for fim in jogar_ate_a_batida(&mut mesa).await {
+        let MsgServidor::FimDeJogo {
+            vencedor_time,
+            pontos_a,
+            pontos_b,
+            motivo,
+            empate,
+            serie,
+        } = fim
+        else {
+            unreachable!()
+        };
+        assert_eq!(motivo, "Batida");
+        assert_eq!(vencedor_time, 0);
+        assert!(!empate);
+        assert!(pontos_a > pontos_b, "placar {} x {}", pontos_a, pontos_b);
+        assert_eq!(serie.vitorias_a, 1);
+    }
+
+    let bateu = servidor
+        .na_sala("BATIDA", |room| room.game_state.bateu)
+        .await;
+    assert_eq!(bateu, Some(0));
+
+    // Acabou: nenhuma jogada passa mais
+    mesa[1].jogar(AcaoJogador::ComprarBaralho).await;
+    let erro = esperar!(mesa[1], MsgServidor::Erro(erro) => erro.clone());
+    assert!(erro.contains("encerrou"), "erro inesperado: {}", erro);
+}
+
+#[tokio::test]
+async fn revanche_unanime_da_as_cartas_de_novo() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut mesa = servidor.mesa_completa("REVANCHE").await;
+    preparar_mao_para_batida(&servidor, "REVANCHE").await;
+    jogar_ate_a_batida(&mut mesa).await;
+
+    // Votar antes de todos não recomeça nada
+    for jogador in mesa.iter_mut().take(3) {
+        jogador
+            .enviar(&MsgCliente::VotoFimDeJogo(VotoFimDeJogo::Revanche))
+            .await;
+    }
+    esperar!(mesa[3], MsgServidor::VotosFimDeJogo(votos) if votos.len() == 3);
+    mesa[3]
+        .enviar(&MsgCliente::VotoFimDeJogo(VotoFimDeJogo::Revanche))
+        .await;
+
+    for (assento, jogador) in mesa.iter_mut().enumerate() {
+        let (serie, trocadas) = esperar!(
+            jogador,
+            MsgServidor::NovaPartida { serie, duplas_trocadas } => (*serie, *duplas_trocadas)
+        );
+        assert_eq!(serie.vitorias_a, 1);
+        assert!(!trocadas);
+        // A mesa nova chega como delta da visão anterior
+        let mao = esperar!(
+            jogador,
+            MsgServidor::Delta(delta) if delta.minha_mao.is_some() => delta.minha_mao.as_ref().map_or(0, Vec::len)
+        );
+        assert_eq!(mao, 15, "assento {} recebeu {} cartas", assento, mao);
+    }
+    let numero = servidor
+        .na_sala("REVANCHE", |room| room.game_state.numero_partida)
+        .await;
+    assert_eq!(numero, 1);
+}
+
+#[tokio::test]
+async fn compra_com_monte_e_lixo_vazios_encerra_a_partida_para_todos() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut mesa = servidor.mesa_completa("ESGOTOU").await;
+    preparar_mao_para_batida(&servidor, "ESGOTOU").await;
+    servidor
+        .na_sala("ESGOTOU", |room| {
+            room.game_state.baralho.cartas.clear();
+            room.game_state.qtd_monte = 0;
+        })
+        .await;
+
+    // A compra é recusada, mas encerrou a mão: a mesa toda fica sabendo
+    mesa[0].jogar(AcaoJogador::ComprarBaralho).await;
+    for jogador in mesa.iter_mut() {
+        let motivo = esperar!(jogador, MsgServidor::FimDeJogo { motivo, .. } => motivo.clone());
+        assert_eq!(motivo, "Baralho Esgotado");
+    }
+    let erro = esperar!(mesa[0], MsgServidor::Erro(erro) => erro.clone());
+    assert!(erro.contains("Fim de jogo"), "erro inesperado: {}", erro);
+}
@@ -0,0 +1,223 @@
+use super::{Cliente, ServidorTeste, dados_login, esperar, novo_device};
+use buracao_core::acoes::{
+    AcaoJogador, ModoEspectador, MotivoRecusa, MsgCliente, MsgServidor, VERSAO_PROTOCOLO,
+};
+use buracao_core::protocolo::ErroMensagem;
+
+#[tokio::test]
+async fn login_senta_e_manda_a_mesa() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut cliente = Cliente::login(&servidor, "Ana", "MESA").await;
+
+    assert_eq!(cliente.id, Some(0));
+    // A foto vem da tarefa de visões, depois do que a sala manda no próprio login
+    let nomes = esperar!(cliente, MsgServidor::Nomes(nomes) => nomes.clone());
+    assert_eq!(nomes.get(&0).map(String::as_str), Some("Ana"));
+    let visao = esperar!(cliente, MsgServidor::Estado(visao) => visao.clone());
+    assert_eq!(visao.meu_id, 0);
+    assert_eq!(visao.minha_mao.len(), 15);
+
+    // Quem chega depois é anunciado para quem já estava
+    let _bia = Cliente::login(&servidor, "Bia", "MESA").await;
+    esperar!(cliente, MsgServidor::Nomes(nomes) if nomes.len() == 2);
+}
+
+#[tokio::test]
+async fn reconexao_volta_ao_mesmo_assento() {
+    let servidor = ServidorTeste::iniciar().await;
+    let _outro = Cliente::login(&servidor, "Ana", "VOLTA").await;
+    let cliente = Cliente::login(&servidor, "Bia", "VOLTA").await;
+    let (device, token, assento) = (cliente.device_id.clone(), cliente.token.clone(), cliente.id);
+    cliente.fechar().await;
+
+    let mut dados = dados_login("Bia", &device, "VOLTA");
+    dados.token = token;
+    let mut de_volta = Cliente::login_com(&servidor, dados).await;
+    assert_eq!(de_volta.id, assento);
+    // Sem `ultimo_seq` a reconexão recebe a foto completa
+    esperar!(de_volta, MsgServidor::Estado(visao) if Some(visao.meu_id) == assento);
+}
+
+#[tokio::test]
+async fn reconexao_com_ultimo_seq_retoma_sem_foto() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut cliente = Cliente::login(&servidor, "Ana", "RETOMA").await;
+    esperar!(cliente, MsgServidor::Estado(_));
+    let (device, token, ultimo_seq) = (
+        cliente.device_id.clone(),
+        cliente.token.clone(),
+        cliente.ultimo_seq,
+    );
+    cliente.fechar().await;
+
+    let mut dados = dados_login("Ana", &device, "RETOMA");
+    dados.token = token;
+    dados.ultimo_seq = ultimo_seq;
+    let mut de_volta = Cliente::entrar(&servidor, dados).await;
+    let retomada = esperar!(de_volta, MsgServidor::BoasVindas { retomada, .. } => *retomada);
+    assert!(retomada);
+}
+
+#[tokio::test]
+async fn reconexao_sem_token_e_recusada() {
+    let servidor = ServidorTeste::iniciar().await;
+    let cliente = Cliente::login(&servidor, "Ana", "TOKEN").await;
+    let device = cliente.device_id.clone();
+    cliente.fechar().await;
+
+    let mut intruso = Cliente::entrar(&servidor, dados_login("Eva", &device, "TOKEN")).await;
+    let motivo = esperar!(intruso, MsgServidor::LoginRecusado(motivo) => motivo.clone());
+    assert_eq!(motivo, MotivoRecusa::SessaoInvalida);
+    intruso.esperar_fechamento().await;
+}
+
+#[tokio::test]
+async fn quinto_jogador_encontra_a_sala_cheia() {
+    let servidor = ServidorTeste::iniciar().await;
+    let _mesa = servidor.mesa_completa("CHEIA").await;
+
+    let mut quinto = Cliente::entrar(&servidor, dados_login("Eva", &novo_device(), "CHEIA")).await;
+    let motivo = esperar!(quinto, MsgServidor::LoginRecusado(motivo) => motivo.clone());
+    assert_eq!(motivo, MotivoRecusa::SalaCheia);
+    quinto.esperar_fechamento().await;
+}
+
+#[tokio::test]
+async fn versao_antiga_e_recusada() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut dados = dados_login("Ana", &novo_device(), "VERSAO");
+    dados.versao = VERSAO_PROTOCOLO - 1;
+    let mut cliente = Cliente::entrar(&servidor, dados).await;
+
+    let motivo = esperar!(cliente, MsgServidor::LoginRecusado(motivo) => motivo.clone());
+    assert_eq!(
+        motivo,
+        MotivoRecusa::VersaoIncompativel {
+            servidor: VERSAO_PROTOCOLO,
+            cliente: VERSAO_PROTOCOLO - 1,
+        }
+    );
+    cliente.esperar_fechamento().await;
+}
+
+#[tokio::test]
+async fn primeira_mensagem_precisa_ser_login() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut cliente = Cliente::conectar(&servidor).await;
+    cliente.enviar(&MsgCliente::Resync).await;
+
+    let motivo = esperar!(cliente, MsgServidor::LoginRecusado(motivo) => motivo.clone());
+    assert_eq!(motivo, MotivoRecusa::MensagemInvalida);
+    cliente.esperar_fechamento().await;
+}
+
+#[tokio::test]
+async fn jogada_fora_da_vez_responde_erro() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut mesa = servidor.mesa_completa("VEZ").await;
+    servidor
+        .na_sala("VEZ", |room| room.game_state.turno_atual = 0)
+        .await;
+
+    mesa[1].jogar(AcaoJogador::ComprarBaralho).await;
+    let erro = esperar!(mesa[1], MsgServidor::Erro(erro) => erro.clone());
+    assert!(erro.contains("turno"), "erro inesperado: {}", erro);
+}
+
+#[tokio::test]
+async fn mensagem_malformada_e_rejeitada_sem_derrubar() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut cliente = Cliente::login(&servidor, "Ana", "LIXO").await;
+
+    cliente.enviar_texto("{ isso não é json").await;
+    let erro = esperar!(cliente, MsgServidor::MensagemRejeitada(erro) => erro.clone());
+    assert!(matches!(erro, ErroMensagem::JsonInvalido { .. }));
+
+    // A conexão continua valendo
+    cliente.enviar(&MsgCliente::Resync).await;
+    esperar!(cliente, MsgServidor::Estado(_));
+}
+
+#[tokio::test]
+async fn login_com_campo_longo_demais_e_rejeitado() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut cliente = Cliente::conectar(&servidor).await;
+    let dados = dados_login(&"x".repeat(500), &novo_device(), "LONGO");
+    cliente.enviar(&MsgCliente::Login(dados)).await;
+
+    let motivo = esperar!(cliente, MsgServidor::LoginRecusado(motivo) => motivo.clone());
+    assert_eq!(motivo, MotivoRecusa::MensagemInvalida);
+}
+
+#[tokio::test]
+async fn codigo_da_sala_nao_diferencia_maiusculas() {
+    let servidor = ServidorTeste::iniciar().await;
+    let _ana = Cliente::login(&servidor, "Ana", "minusculas").await;
+    let bia = Cliente::login(&servidor, "Bia", "Minusculas").await;
+
+    // Os dois caem na mesma sala, guardada com o código em maiúsculas
+    assert_eq!(bia.id, Some(1));
+    let ocupados = servidor
+        .na_sala("MINUSCULAS", |room| room.clients.len())
+        .await;
+    assert_eq!(ocupados, 2);
+}
+
+#[tokio::test]
+async fn mesmo_perfil_nao_senta_duas_vezes_na_sala() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut ana = Cliente::login(&servidor, "Ana", "PERFIL").await;
+    let chave = esperar!(ana, MsgServidor::Identidade { chave: Some(chave), .. } => chave.clone());
+
+    // Outra aba (outro device) com a mesma identidade
+    let mut dados = dados_login("Ana", &novo_device(), "PERFIL");
+    dados.identidade = Some(chave.clone());
+    let mut outra_aba = Cliente::entrar(&servidor, dados).await;
+    let motivo = esperar!(outra_aba, MsgServidor::LoginRecusado(motivo) => motivo.clone());
+    assert_eq!(motivo, MotivoRecusa::PerfilJaSentado);
+
+    // Reconectar no próprio assento continua valendo
+    let (device, token) = (ana.device_id.clone(), ana.token.clone());
+    ana.fechar().await;
+    let mut dados = dados_login("Ana", &device, "PERFIL");
+    dados.token = token;
+    dados.identidade = Some(chave);
+    let de_volta = Cliente::login_com(&servidor, dados).await;
+    assert_eq!(de_volta.id, Some(0));
+}
+
+#[tokio::test]
+async fn espectador_nao_abre_sala_que_nao_existe() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut dados = dados_login("Eva", &novo_device(), "NINGUEM");
+    dados.espectador = Some(ModoEspectador::Publico);
+    let mut espectador = Cliente::entrar(&servidor, dados).await;
+    let motivo = esperar!(espectador, MsgServidor::LoginRecusado(motivo) => motivo.clone());
+    assert_eq!(motivo, MotivoRecusa::SalaInexistente);
+    assert!(servidor.estado.rooms.is_empty());
+}
+
+#[tokio::test]
+async fn delta_montado_antes_da_foto_completa_nao_chega() {
+    let servidor = ServidorTeste::iniciar().await;
+    let mut ana = Cliente::login(&servidor, "Ana", "EPOCA").await;
+    esperar!(ana, MsgServidor::Estado(_));
+
+    // O delta da jogada ainda está na tarefa de visões quando a sala pede a foto completa
+    servidor
+        .na_sala("EPOCA", |room| {
+            let vez = room.game_state.turno_atual;
+            room.game_state
+                .jogada_automatica(vez)
+                .expect("jogada automática");
+            room.propagar_estado();
+            room.enviar_estado(0);
+        })
+        .await;
+    let completa = esperar!(
+        ana,
+        msg @ (MsgServidor::Estado(_) | MsgServidor::Delta(_)) =>
+            matches!(msg, MsgServidor::Estado(_))
+    );
+    assert!(completa, "delta da época anterior chegou antes da foto");
+}
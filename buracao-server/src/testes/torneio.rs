@@ -0,0 +1,235 @@
+use super::{Cliente, ServidorTeste, dados_login, esperar, novo_device};
+use buracao_core::acoes::{MotivoRecusa, MsgServidor};
+use buracao_core::torneio::{ResumoTorneio, TorneioCriado};
+use serde_json::json;
+
+/// Torneio com as duplas inscritas ("A1 & A2", "B1 & B2"...). Devolve o id, a chave
+/// de organizador e o resumo que o organizador recebeu depois da última inscrição.
+async fn torneio_com_duplas(
+    servidor: &ServidorTeste,
+    formato: serde_json::Value,
+    duplas: usize,
+) -> (u32, String, ResumoTorneio) {
+    let (status, criado) = servidor
+        .http(
+            "POST",
+            "/api/torneios",
+            &[],
+            Some(json!({ "nome": "Copa", "formato": formato })),
+        )
+        .await;
+    assert_eq!(status, 201, "{}", criado);
+    let criado: TorneioCriado = serde_json::from_value(criado).unwrap();
+    let (id, chave) = (criado.torneio.id, criado.chave_admin);
+
+    let mut resumo = criado.torneio;
+    for letra in ('A'..='Z').take(duplas) {
+        let (status, corpo) = servidor
+            .http(
+                "POST",
+                &format!("/api/torneios/{}/duplas", id),
+                &[("x-chave-torneio", &chave)],
+                Some(json!({ "jogadores": [format!("{}1", letra), format!("{}2", letra)] })),
+            )
+            .await;
+        assert_eq!(status, 200, "{}", corpo);
+        resumo = serde_json::from_value(corpo).unwrap();
+    }
+    (id, chave, resumo)
+}
+
+async fn gerar_rodada(servidor: &ServidorTeste, id: u32, chave: &str) -> ResumoTorneio {
+    let (status, corpo) = servidor
+        .http(
+            "POST",
+            &format!("/api/torneios/{}/rodadas", id),
+            &[("x-chave-torneio", chave)],
+            None,
+        )
+        .await;
+    assert_eq!(status, 200, "{}", corpo);
+    serde_json::from_value(corpo).unwrap()
+}
+
+async fn lancar(
+    servidor: &ServidorTeste,
+    id: u32,
+    chave: &str,
+    rodada: u32,
+    mesa: usize,
+    placar: (i32, i32),
+) -> ResumoTorneio {
+    let (status, corpo) = servidor
+        .http(
+            "POST",
+            &format!("/api/torneios/{}/resultados", id),
+            &[("x-chave-torneio", chave)],
+            Some(json!({ "rodada": rodada, "mesa": mesa, "pontos_a": placar.0, "pontos_b": placar.1 })),
+        )
+        .await;
+    assert_eq!(status, 200, "{}", corpo);
+    serde_json::from_value(corpo).unwrap()
+}
+
+fn confrontos(resumo: &ResumoTorneio, rodada: usize) -> Vec<(u32, Option<u32>)> {
+    resumo.rodadas[rodada]
+        .mesas
+        .iter()
+        .map(|m| (m.dupla_a, m.dupla_b))
+        .collect()
+}
+
+fn ordem(resumo: &ResumoTorneio) -> Vec<u32> {
+    resumo.classificacao.iter().map(|l| l.dupla).collect()
+}
+
+#[tokio::test]
+async fn chaves_de_assento_so_aparecem_para_o_organizador() {
+    let servidor = ServidorTeste::iniciar().await;
+    let formato = json!({ "tipo": "Suico", "rodadas": 1 });
+    let (id, chave, resumo) = torneio_com_duplas(&servidor, formato, 2).await;
+    let chaves = resumo.duplas[0]
+        .chaves
+        .clone()
+        .expect("chaves na inscrição");
+    assert_ne!(chaves[0], chaves[1]);
+
+    let caminho = format!("/api/torneios/{}", id);
+    let (_, publico) = servidor.http("GET", &caminho, &[], None).await;
+    assert!(publico["duplas"][0].get("chaves").is_none(), "{}", publico);
+    let (_, errada) = servidor
+        .http("GET", &caminho, &[("x-chave-torneio", "errada")], None)
+        .await;
+    assert!(errada["duplas"][0].get("chaves").is_none(), "{}", errada);
+    let (_, organizador) = servidor
+        .http("GET", &caminho, &[("x-chave-torneio", &chave)], None)
+        .await;
+    assert_eq!(organizador["duplas"][0]["chaves"][0], chaves[0].as_str());
+}
+
+#[tokio::test]
+async fn mesa_de_torneio_exige_nome_e_chave_de_assento() {
+    let servidor = ServidorTeste::iniciar().await;
+    let formato = json!({ "tipo": "EliminatoriaSimples" });
+    let (id, chave, resumo) = torneio_com_duplas(&servidor, formato, 2).await;
+    let chaves_a = resumo.duplas[0].chaves.clone().unwrap();
+    let chaves_b = resumo.duplas[1].chaves.clone().unwrap();
+    let sala = gerar_rodada(&servidor, id, &chave).await.rodadas[0].mesas[0]
+        .sala
+        .clone();
+
+    // Só o nome não basta, nem a chave de outro jogador
+    for senha in [None, Some(chaves_b[0].clone())] {
+        let mut dados = dados_login("A1", &novo_device(), &sala);
+        dados.senha = senha;
+        let mut impostor = Cliente::entrar(&servidor, dados).await;
+        let motivo = esperar!(impostor, MsgServidor::LoginRecusado(motivo) => motivo.clone());
+        assert!(matches!(motivo, MotivoRecusa::AssentoReservado));
+    }
+
+    let mut dados = dados_login("a1", &novo_device(), &sala);
+    dados.senha = Some(chaves_a[0].clone());
+    let a1 = Cliente::login_com(&servidor, dados).await;
+    assert_eq!(a1.id, Some(0));
+    let mut dados = dados_login("B2", &novo_device(), &sala);
+    dados.senha = Some(chaves_b[1].clone());
+    let b2 = Cliente::login_com(&servidor, dados).await;
+    assert_eq!(b2.id, Some(3));
+}
+
+#[tokio::test]
+async fn suico_emparelha_pela_classificacao_sem_repetir_confronto() {
+    let servidor = ServidorTeste::iniciar().await;
+    let formato = json!({ "tipo": "Suico", "rodadas": 2 });
+    let (id, chave, _) = torneio_com_duplas(&servidor, formato, 4).await;
+
+    // Primeira rodada: todos empatados, vale a ordem de inscrição
+    let resumo = gerar_rodada(&servidor, id, &chave).await;
+    assert_eq!(confrontos(&resumo, 0), vec![(1, Some(2)), (3, Some(4))]);
+    lancar(&servidor, id, &chave, 1, 0, (1000, 500)).await;
+    let resumo = lancar(&servidor, id, &chave, 1, 1, (300, 800)).await;
+
+    // Vitória vale 2; empate em pontos e saldo desempata pelos pontos a favor
+    assert_eq!(ordem(&resumo), vec![1, 4, 2, 3]);
+    let lider = &resumo.classificacao[0];
+    assert_eq!(
+        (
+            lider.jogos,
+            lider.vitorias,
+            lider.derrotas,
+            lider.pontos_torneio
+        ),
+        (1, 1, 0, 2)
+    );
+    assert_eq!(lider.saldo(), 500);
+    assert_eq!(resumo.classificacao[3].pontos_torneio, 0);
+
+    // Segunda: líderes entre si, sem repetir 1 x 2 nem 3 x 4
+    let resumo = gerar_rodada(&servidor, id, &chave).await;
+    assert_eq!(confrontos(&resumo, 1), vec![(1, Some(4)), (2, Some(3))]);
+}
+
+#[tokio::test]
+async fn suico_com_numero_impar_da_folga_a_quem_ainda_nao_folgou() {
+    let servidor = ServidorTeste::iniciar().await;
+    let formato = json!({ "tipo": "Suico", "rodadas": 2 });
+    let (id, chave, _) = torneio_com_duplas(&servidor, formato, 3).await;
+
+    let resumo = gerar_rodada(&servidor, id, &chave).await;
+    assert_eq!(confrontos(&resumo, 0), vec![(1, Some(2)), (3, None)]);
+    // Folga conta como vitória
+    let folga = resumo.classificacao.iter().find(|l| l.dupla == 3).unwrap();
+    assert_eq!((folga.vitorias, folga.pontos_torneio), (1, 2));
+
+    let resumo = lancar(&servidor, id, &chave, 1, 0, (900, 400)).await;
+    assert_eq!(ordem(&resumo), vec![1, 3, 2]);
+
+    let resumo = gerar_rodada(&servidor, id, &chave).await;
+    assert_eq!(confrontos(&resumo, 1), vec![(1, Some(3)), (2, None)]);
+}
+
+#[tokio::test]
+async fn torneio_e_mesas_voltam_depois_do_reinicio() {
+    let banco = std::env::temp_dir().join(format!("buracao-reinicio-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&banco);
+    let caminho = banco.display().to_string();
+
+    let servidor = ServidorTeste::iniciar_com(&["--banco", &caminho]).await;
+    let formato = json!({ "tipo": "EliminatoriaSimples" });
+    let (id, chave, resumo) = torneio_com_duplas(&servidor, formato, 2).await;
+    let chaves_a = resumo.duplas[0].chaves.clone().unwrap();
+    let sala = gerar_rodada(&servidor, id, &chave).await.rodadas[0].mesas[0]
+        .sala
+        .clone();
+    crate::desligamento::encerrar(&servidor.estado).await;
+
+    let servidor = ServidorTeste::iniciar_com(&["--banco", &caminho]).await;
+    let (status, corpo) = servidor
+        .http(
+            "GET",
+            &format!("/api/torneios/{}", id),
+            &[("x-chave-torneio", &chave)],
+            None,
+        )
+        .await;
+    assert_eq!(status, 200, "{}", corpo);
+    let voltou: ResumoTorneio = serde_json::from_value(corpo).unwrap();
+    assert_eq!(voltou.duplas[0].chaves, Some(chaves_a.clone()));
+    assert_eq!(voltou.rodadas[0].mesas[0].sala, sala);
+
+    // A mesa continua reservada e ligada ao torneio
+    let mut impostor = Cliente::entrar(&servidor, dados_login("A1", &novo_device(), &sala)).await;
+    let motivo = esperar!(impostor, MsgServidor::LoginRecusado(motivo) => motivo.clone());
+    assert!(matches!(motivo, MotivoRecusa::AssentoReservado));
+    let mut dados = dados_login("A1", &novo_device(), &sala);
+    dados.senha = Some(chaves_a[0].clone());
+    let a1 = Cliente::login_com(&servidor, dados).await;
+    assert_eq!(a1.id, Some(0));
+    let mesa = servidor.estado.rooms.get(&sala).unwrap().clone();
+    assert_eq!(
+        mesa.executar(|room| room.torneio.is_some()).await,
+        Some(true)
+    );
+
+    let _ = std::fs::remove_file(&banco);
+}
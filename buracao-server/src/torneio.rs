@@ -0,0 +1,786 @@
+use crate::lobby;
+use crate::sessao;
+use crate::state::{GlobalState, PlayerId, Room};
+use buracao_core::acoes::MsgServidor;
+use buracao_core::lobby::{ConfigEspectadores, ConfigRelogio, NovaSala};
+use buracao_core::regras::ConfigRegras;
+use buracao_core::torneio::{
+    DuplaTorneio, FormatoTorneio, LinhaClassificacao, MesaTorneio, NovaDupla, NovoTorneio,
+    ResultadoManual, ResultadoMesa, ResumoTorneio, RodadaTorneio, StatusTorneio, TorneioCriado,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex, Weak};
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+
+// --- TORNEIOS ---
+// O organizador inscreve as duplas e pede cada rodada; o servidor emparelha, abre uma
+// sala por confronto com os assentos reservados para os jogadores inscritos e, quando a
+// partida da sala acaba, lança o placar no torneio sozinho. Cada jogador recebe na
+// inscrição uma chave de assento (o organizador repassa): o nome sozinho qualquer um digita.
+//
+// Os torneios ficam num `Mutex<Torneios>` só deles. A tarefa da sala (ver sala.rs) trava
+// esse Mutex por um instante para lançar o resultado; as rotas o seguram só enquanto mexem
+// no torneio e abrem as salas da rodada, sem nunca esperar a resposta de uma sala.
+// No desligamento vão para o banco junto com as salas (ver desligamento.rs).
+
+const MAX_TORNEIOS: usize = 100;
+const MAX_DUPLAS: usize = 64;
+const MAX_RODADAS_SUICO: u32 = 15;
+const TAMANHO_MAX_NOME: usize = 40;
+const TAMANHO_MAX_JOGADOR: usize = 20;
+// Bytes da chave de assento: curta o bastante para digitar, longa demais para chutar
+const BYTES_CHAVE_ASSENTO: usize = 5;
+
+#[derive(Serialize, Deserialize)]
+pub struct Torneio {
+    id: u32,
+    nome: String,
+    formato: FormatoTorneio,
+    regras: ConfigRegras,
+    relogio: Option<ConfigRelogio>,
+    // Só o organizador (quem criou) conhece: protege inscrições, rodadas e resultados
+    chave_admin: String,
+    duplas: Vec<DuplaTorneio>,
+    rodadas: Vec<RodadaTorneio>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Torneios {
+    lista: HashMap<u32, Torneio>,
+    proximo_id: u32,
+}
+
+pub type TorneiosCompartilhados = Arc<Mutex<Torneios>>;
+
+/// Assento de uma mesa de torneio: quem foi escalado para ele e a chave que prova isso.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Reserva {
+    pub nome: String,
+    pub chave: String,
+}
+
+/// Liga a sala à mesa do torneio que ela está jogando. Vai guardado com a sala no
+/// desligamento e, na volta, é religado aos torneios restaurados.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VinculoTorneio {
+    #[serde(skip)]
+    torneios: Weak<Mutex<Torneios>>,
+    id: u32,
+    rodada: u32,
+    mesa: usize,
+}
+
+impl VinculoTorneio {
+    /// Religa a mesa restaurada ao torneio dela; None se o torneio não voltou.
+    pub fn religar(mut self, torneios: &TorneiosCompartilhados) -> Option<Self> {
+        if !torneios.lock().unwrap().lista.contains_key(&self.id) {
+            return None;
+        }
+        self.torneios = Arc::downgrade(torneios);
+        Some(self)
+    }
+}
+
+fn mesma_pessoa(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+/// Vencedor do confronto (None = empate ou sem resultado). Folga é vitória da dupla A.
+fn vencedor(mesa: &MesaTorneio) -> Option<u32> {
+    let Some(b) = mesa.dupla_b else {
+        return Some(mesa.dupla_a);
+    };
+    let resultado = mesa.resultado?;
+    match resultado.pontos_a.cmp(&resultado.pontos_b) {
+        std::cmp::Ordering::Greater => Some(mesa.dupla_a),
+        std::cmp::Ordering::Less => Some(b),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+fn mesa_fechada(mesa: &MesaTorneio) -> bool {
+    mesa.dupla_b.is_none() || mesa.resultado.is_some()
+}
+
+impl Torneio {
+    fn ultima_rodada_fechada(&self) -> bool {
+        self.rodadas
+            .last()
+            .is_none_or(|r| r.mesas.iter().all(mesa_fechada))
+    }
+
+    /// Duplas ainda vivas na eliminatória, na ordem da chave.
+    fn vivas(&self) -> Vec<u32> {
+        match self.rodadas.last() {
+            None => self.duplas.iter().map(|d| d.id).collect(),
+            Some(rodada) => rodada.mesas.iter().filter_map(vencedor).collect(),
+        }
+    }
+
+    fn encerrado(&self) -> bool {
+        if self.rodadas.is_empty() || !self.ultima_rodada_fechada() {
+            return false;
+        }
+        match self.formato {
+            FormatoTorneio::Suico { rodadas } => self.rodadas.len() as u32 >= rodadas,
+            FormatoTorneio::EliminatoriaSimples => {
+                let ultima = self.rodadas.last().unwrap();
+                // Empate na rodada final ainda precisa de desempate
+                ultima.mesas.iter().all(|m| vencedor(m).is_some()) && self.vivas().len() <= 1
+            }
+        }
+    }
+
+    fn status(&self) -> StatusTorneio {
+        if self.rodadas.is_empty() {
+            StatusTorneio::Inscricoes
+        } else if self.encerrado() {
+            StatusTorneio::Encerrado
+        } else if self.ultima_rodada_fechada() {
+            StatusTorneio::EntreRodadas
+        } else {
+            StatusTorneio::EmAndamento
+        }
+    }
+
+    fn classificacao(&self) -> Vec<LinhaClassificacao> {
+        let mut linhas: Vec<LinhaClassificacao> = self
+            .duplas
+            .iter()
+            .map(|d| LinhaClassificacao {
+                dupla: d.id,
+                nome: d.nome.clone(),
+                jogos: 0,
+                vitorias: 0,
+                empates: 0,
+                derrotas: 0,
+                pontos_torneio: 0,
+                pontos_pro: 0,
+                pontos_contra: 0,
+                eliminada: false,
+            })
+            .collect();
+        let eliminatoria = self.formato == FormatoTorneio::EliminatoriaSimples;
+
+        for mesa in self.rodadas.iter().flat_map(|r| r.mesas.iter()) {
+            let Some(b) = mesa.dupla_b else {
+                if let Some(linha) = linhas.iter_mut().find(|l| l.dupla == mesa.dupla_a) {
+                    linha.jogos += 1;
+                    linha.vitorias += 1;
+                    linha.pontos_torneio += 2;
+                }
+                continue;
+            };
+            let Some(resultado) = mesa.resultado else {
+                continue;
+            };
+            let ganhou = vencedor(mesa);
+            for (dupla, pro, contra) in [
+                (mesa.dupla_a, resultado.pontos_a, resultado.pontos_b),
+                (b, resultado.pontos_b, resultado.pontos_a),
+            ] {
+                let Some(linha) = linhas.iter_mut().find(|l| l.dupla == dupla) else {
+                    continue;
+                };
+                linha.jogos += 1;
+                linha.pontos_pro += pro;
+                linha.pontos_contra += contra;
+                match ganhou {
+                    Some(d) if d == dupla => {
+                        linha.vitorias += 1;
+                        linha.pontos_torneio += 2;
+                    }
+                    Some(_) => {
+                        linha.derrotas += 1;
+                        linha.eliminada = eliminatoria;
+                    }
+                    None => {
+                        linha.empates += 1;
+                        linha.pontos_torneio += 1;
+                    }
+                }
+            }
+        }
+
+        linhas.sort_by_key(|l| {
+            (
+                l.eliminada,
+                std::cmp::Reverse(l.pontos_torneio),
+                std::cmp::Reverse(l.saldo()),
+                std::cmp::Reverse(l.pontos_pro),
+                l.dupla,
+            )
+        });
+        linhas
+    }
+
+    /// Situação pública: sem as chaves de assento.
+    pub fn resumo(&self) -> ResumoTorneio {
+        let mut resumo = self.resumo_organizador();
+        for dupla in &mut resumo.duplas {
+            dupla.chaves = None;
+        }
+        resumo
+    }
+
+    fn resumo_organizador(&self) -> ResumoTorneio {
+        ResumoTorneio {
+            id: self.id,
+            nome: self.nome.clone(),
+            formato: self.formato,
+            status: self.status(),
+            duplas: self.duplas.clone(),
+            rodadas: self.rodadas.clone(),
+            classificacao: self.classificacao(),
+        }
+    }
+
+    fn inscrever(&mut self, pedido: NovaDupla) -> Result<(), String> {
+        if !self.rodadas.is_empty() {
+            return Err("As inscrições fecham quando a primeira rodada começa.".to_string());
+        }
+        if self.duplas.len() >= MAX_DUPLAS {
+            return Err(format!("O torneio aceita no máximo {} duplas.", MAX_DUPLAS));
+        }
+
+        let jogadores = pedido.jogadores.map(|j| j.trim().to_string());
+        if jogadores
+            .iter()
+            .any(|j| j.is_empty() || j.chars().count() > TAMANHO_MAX_JOGADOR)
+        {
+            return Err(format!(
+                "O nome de cada jogador deve ter entre 1 e {} caracteres.",
+                TAMANHO_MAX_JOGADOR
+            ));
+        }
+        // O assento é reservado pelo nome: dois jogadores com o mesmo nome se confundiriam
+        let repetido = mesma_pessoa(&jogadores[0], &jogadores[1])
+            || self.duplas.iter().any(|d| {
+                d.jogadores
+                    .iter()
+                    .any(|j| jogadores.iter().any(|novo| mesma_pessoa(j, novo)))
+            });
+        if repetido {
+            return Err("Já existe um jogador inscrito com esse nome.".to_string());
+        }
+
+        let nome = pedido
+            .nome
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| format!("{} & {}", jogadores[0], jogadores[1]));
+        if nome.chars().count() > TAMANHO_MAX_NOME {
+            return Err("Nome da dupla muito longo.".to_string());
+        }
+
+        let id = self.duplas.iter().map(|d| d.id + 1).max().unwrap_or(1);
+        self.duplas.push(DuplaTorneio {
+            id,
+            nome,
+            jogadores,
+            chaves: Some([
+                sessao::chave_aleatoria(BYTES_CHAVE_ASSENTO),
+                sessao::chave_aleatoria(BYTES_CHAVE_ASSENTO),
+            ]),
+        });
+        Ok(())
+    }
+
+    /// Confrontos da próxima rodada (dupla A, dupla B ou folga).
+    fn emparelhar(&self) -> Result<Vec<(u32, Option<u32>)>, String> {
+        match self.status() {
+            StatusTorneio::Encerrado => return Err("O torneio já terminou.".to_string()),
+            StatusTorneio::EmAndamento => {
+                return Err("A rodada atual ainda tem mesas sem resultado.".to_string());
+            }
+            StatusTorneio::Inscricoes if self.duplas.len() < 2 => {
+                return Err("Inscreva pelo menos duas duplas.".to_string());
+            }
+            _ => {}
+        }
+
+        match self.formato {
+            FormatoTorneio::Suico { .. } => Ok(self.emparelhar_suico()),
+            FormatoTorneio::EliminatoriaSimples => {
+                let empatada = self
+                    .rodadas
+                    .last()
+                    .and_then(|r| r.mesas.iter().position(|m| vencedor(m).is_none()));
+                if let Some(mesa) = empatada {
+                    return Err(format!(
+                        "A mesa {} empatou: lance o resultado do desempate antes da próxima rodada.",
+                        mesa + 1
+                    ));
+                }
+                // Vizinhas na chave se enfrentam; com número ímpar, a última avança direto
+                Ok(self
+                    .vivas()
+                    .chunks(2)
+                    .map(|par| (par[0], par.get(1).copied()))
+                    .collect())
+            }
+        }
+    }
+
+    /// Suíço: na ordem da classificação, cada dupla pega a próxima com quem ainda não
+    /// jogou (se já jogou com todas, repete o confronto). Com número ímpar, a folga vai
+    /// para a pior colocada que ainda não folgou.
+    fn emparelhar_suico(&self) -> Vec<(u32, Option<u32>)> {
+        let mut ordem: Vec<u32> = self.classificacao().iter().map(|l| l.dupla).collect();
+        let mut jogaram: HashSet<(u32, u32)> = HashSet::new();
+        let mut folgaram: HashSet<u32> = HashSet::new();
+        for mesa in self.rodadas.iter().flat_map(|r| r.mesas.iter()) {
+            match mesa.dupla_b {
+                Some(b) => {
+                    jogaram.insert((mesa.dupla_a.min(b), mesa.dupla_a.max(b)));
+                }
+                None => {
+                    folgaram.insert(mesa.dupla_a);
+                }
+            }
+        }
+
+        let mut confrontos = Vec::new();
+        let mut folga = None;
+        if ordem.len() % 2 == 1 {
+            let posicao = ordem
+                .iter()
+                .rposition(|d| !folgaram.contains(d))
+                .unwrap_or(ordem.len() - 1);
+            folga = Some(ordem.remove(posicao));
+        }
+
+        while !ordem.is_empty() {
+            let a = ordem.remove(0);
+            let posicao = ordem
+                .iter()
+                .position(|&b| !jogaram.contains(&(a.min(b), a.max(b))))
+                .unwrap_or(0);
+            let b = ordem.remove(posicao);
+            confrontos.push((a, Some(b)));
+        }
+        if let Some(d) = folga {
+            confrontos.push((d, None));
+        }
+        confrontos
+    }
+
+    /// Os dois assentos da dupla, na ordem da inscrição.
+    fn reservas(&self, dupla: u32) -> Result<[Reserva; 2], String> {
+        let dupla = self
+            .duplas
+            .iter()
+            .find(|d| d.id == dupla)
+            .ok_or_else(|| format!("A dupla {} não está inscrita.", dupla))?;
+        let chaves = dupla
+            .chaves
+            .clone()
+            .ok_or_else(|| format!("A dupla {} está sem chaves de assento.", dupla.id))?;
+        let [nome_0, nome_1] = dupla.jogadores.clone();
+        let [chave_0, chave_1] = chaves;
+        Ok([
+            Reserva {
+                nome: nome_0,
+                chave: chave_0,
+            },
+            Reserva {
+                nome: nome_1,
+                chave: chave_1,
+            },
+        ])
+    }
+
+    /// Lança o placar de uma mesa. O automático (fim da partida na sala) só vale uma vez:
+    /// se o organizador já lançou ou a sala jogou uma revanche, não sobrescreve.
+    fn registrar(
+        &mut self,
+        rodada: u32,
+        mesa: usize,
+        resultado: ResultadoMesa,
+        manual: bool,
+    ) -> Result<bool, String> {
+        let ultima = self.rodadas.len() as u32;
+        if manual && rodada != ultima {
+            return Err("Só dá para lançar resultados da rodada atual.".to_string());
+        }
+        let Some(mesa) = self
+            .rodadas
+            .get_mut((rodada as usize).wrapping_sub(1))
+            .and_then(|r| r.mesas.get_mut(mesa))
+        else {
+            return Err("Mesa não encontrada.".to_string());
+        };
+        if mesa.dupla_b.is_none() {
+            return Err("Essa mesa é uma folga: não tem placar.".to_string());
+        }
+        if !manual && mesa.resultado.is_some() {
+            return Ok(false);
+        }
+        mesa.resultado = Some(resultado);
+        Ok(true)
+    }
+}
+
+impl Room {
+    /// Assento reservado (livre) para quem entra com este nome e esta chave de assento.
+    pub fn assento_reservado(&self, nome: &str, chave: &str) -> Option<PlayerId> {
+        self.reservas
+            .iter()
+            .filter(|(pid, _)| !self.assento_ocupado(**pid))
+            .find(|(_, reserva)| {
+                mesma_pessoa(&reserva.nome, nome) && sessao::senha_confere(&reserva.chave, chave)
+            })
+            .map(|(pid, _)| *pid)
+    }
+
+    /// Partida de torneio acabou: lança o placar na mesa correspondente.
+    pub fn registrar_no_torneio(&mut self) {
+        let Some(vinculo) = &self.torneio else {
+            return;
+        };
+        let Some(torneios) = vinculo.torneios.upgrade() else {
+            return;
+        };
+        let resultado = ResultadoMesa {
+            pontos_a: self.game_state.pontuacao_a,
+            pontos_b: self.game_state.pontuacao_b,
+        };
+        let lancado = {
+            let mut torneios = torneios.lock().unwrap();
+            match torneios.lista.get_mut(&vinculo.id) {
+                Some(torneio) => torneio.registrar(vinculo.rodada, vinculo.mesa, resultado, false),
+                None => Ok(false),
+            }
+        };
+        match lancado {
+            Ok(true) => {
+                tracing::info!(
+                    torneio = vinculo.id,
+                    rodada = vinculo.rodada,
+                    mesa = vinculo.mesa + 1,
+                    pontos_a = resultado.pontos_a,
+                    pontos_b = resultado.pontos_b,
+                    "mesa de torneio terminou"
+                );
+                self.broadcast(&MsgServidor::Notificacao(
+                    "🏆 Resultado enviado para o torneio.".to_string(),
+                ));
+            }
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!(torneio = vinculo.id, erro = %e, "resultado de torneio recusado")
+            }
+        }
+    }
+}
+
+// --- ROTAS REST ---
+// GET  /api/torneios                   -> lista os torneios
+// POST /api/torneios                   -> cria (a resposta traz a chave do organizador)
+// GET  /api/torneios/{id}              -> duplas, rodadas e classificação (com
+//                                         x-chave-torneio, também as chaves de assento)
+// POST /api/torneios/{id}/duplas       -> inscreve uma dupla          (x-chave-torneio)
+// POST /api/torneios/{id}/rodadas      -> gera a próxima rodada       (x-chave-torneio)
+// POST /api/torneios/{id}/resultados   -> lança/corrige um placar     (x-chave-torneio)
+pub fn rotas(
+    state: GlobalState,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let state_filter = warp::any().map(move || state.clone());
+    let chave = warp::header::optional::<String>("x-chave-torneio");
+
+    let listar = warp::path!("api" / "torneios")
+        .and(warp::get())
+        .and(state_filter.clone())
+        .and_then(listar_torneios);
+
+    let criar = warp::path!("api" / "torneios")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(4 * 1024))
+        .and(warp::body::json())
+        .and(state_filter.clone())
+        .and_then(criar_torneio);
+
+    let detalhe = warp::path!("api" / "torneios" / u32)
+        .and(warp::get())
+        .and(chave)
+        .and(state_filter.clone())
+        .and_then(detalhe_torneio);
+
+    let inscrever = warp::path!("api" / "torneios" / u32 / "duplas")
+        .and(warp::post())
+        .and(chave)
+        .and(warp::body::content_length_limit(1024))
+        .and(warp::body::json())
+        .and(state_filter.clone())
+        .and_then(inscrever_dupla);
+
+    let rodada = warp::path!("api" / "torneios" / u32 / "rodadas")
+        .and(warp::post())
+        .and(chave)
+        .and(state_filter.clone())
+        .and_then(gerar_rodada);
+
+    let resultado = warp::path!("api" / "torneios" / u32 / "resultados")
+        .and(warp::post())
+        .and(chave)
+        .and(warp::body::content_length_limit(1024))
+        .and(warp::body::json())
+        .and(state_filter)
+        .and_then(lancar_resultado);
+
+    listar
+        .or(criar)
+        .or(detalhe)
+        .or(inscrever)
+        .or(rodada)
+        .or(resultado)
+}
+
+fn resposta_erro(status: StatusCode, mensagem: &str) -> warp::reply::Response {
+    warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({ "erro": mensagem })),
+        status,
+    )
+    .into_response()
+}
+
+fn torneios_do(global_state: &GlobalState) -> TorneiosCompartilhados {
+    global_state.torneios.clone()
+}
+
+/// Roda `operacao` no torneio, se ele existir e a chave do organizador conferir.
+fn como_organizador(
+    torneios: &TorneiosCompartilhados,
+    id: u32,
+    chave: Option<String>,
+    operacao: impl FnOnce(&mut Torneio) -> Result<(), String>,
+) -> warp::reply::Response {
+    let mut torneios = torneios.lock().unwrap();
+    let Some(torneio) = torneios.lista.get_mut(&id) else {
+        return resposta_erro(StatusCode::NOT_FOUND, "Torneio não encontrado.");
+    };
+    let autorizado = chave.is_some_and(|c| sessao::senha_confere(&torneio.chave_admin, &c));
+    if !autorizado {
+        return resposta_erro(
+            StatusCode::FORBIDDEN,
+            "Só o organizador pode mexer no torneio.",
+        );
+    }
+    match operacao(torneio) {
+        Ok(()) => warp::reply::json(&torneio.resumo_organizador()).into_response(),
+        Err(e) => resposta_erro(StatusCode::BAD_REQUEST, &e),
+    }
+}
+
+async fn listar_torneios(global_state: GlobalState) -> Result<impl Reply, Infallible> {
+    let torneios = torneios_do(&global_state);
+    let mut lista: Vec<ResumoTorneio> = torneios
+        .lock()
+        .unwrap()
+        .lista
+        .values()
+        .map(Torneio::resumo)
+        .collect();
+    lista.sort_by_key(|t| std::cmp::Reverse(t.id));
+    Ok(warp::reply::json(&lista))
+}
+
+async fn criar_torneio(
+    pedido: NovoTorneio,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let nome = pedido.nome.trim().to_string();
+    if nome.is_empty() || nome.chars().count() > TAMANHO_MAX_NOME {
+        return Ok(resposta_erro(
+            StatusCode::BAD_REQUEST,
+            "O nome do torneio deve ter entre 1 e 40 caracteres.",
+        ));
+    }
+    if let FormatoTorneio::Suico { rodadas } = pedido.formato
+        && !(1..=MAX_RODADAS_SUICO).contains(&rodadas)
+    {
+        return Ok(resposta_erro(
+            StatusCode::BAD_REQUEST,
+            "O suíço deve ter entre 1 e 15 rodadas.",
+        ));
+    }
+    if let Some(Err(e)) = pedido.regras.as_ref().map(|r| r.validar()) {
+        return Ok(resposta_erro(StatusCode::BAD_REQUEST, &e));
+    }
+    if let Some(Err(e)) = pedido.relogio.map(|r| r.validar()) {
+        return Ok(resposta_erro(StatusCode::BAD_REQUEST, &e));
+    }
+    let regras = match pedido.regras {
+        Some(regras) => regras,
+        None => global_state.config.regras.clone(),
+    };
+
+    let chave_admin = sessao::chave_aleatoria(16);
+
+    let torneios = torneios_do(&global_state);
+    let mut torneios = torneios.lock().unwrap();
+    if torneios.lista.len() >= MAX_TORNEIOS {
+        return Ok(resposta_erro(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Limite de torneios no servidor atingido.",
+        ));
+    }
+    torneios.proximo_id += 1;
+    let id = torneios.proximo_id;
+    let torneio = Torneio {
+        id,
+        nome,
+        formato: pedido.formato,
+        regras,
+        relogio: pedido.relogio,
+        chave_admin: chave_admin.clone(),
+        duplas: Vec::new(),
+        rodadas: Vec::new(),
+    };
+    tracing::info!(
+        torneio = id,
+        nome = %torneio.nome,
+        formato = ?torneio.formato,
+        "torneio criado"
+    );
+    let criado = TorneioCriado {
+        torneio: torneio.resumo(),
+        chave_admin,
+    };
+    torneios.lista.insert(id, torneio);
+
+    Ok(warp::reply::with_status(warp::reply::json(&criado), StatusCode::CREATED).into_response())
+}
+
+async fn detalhe_torneio(
+    id: u32,
+    chave: Option<String>,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let torneios = torneios_do(&global_state);
+    let torneios = torneios.lock().unwrap();
+    let Some(torneio) = torneios.lista.get(&id) else {
+        return Ok(resposta_erro(
+            StatusCode::NOT_FOUND,
+            "Torneio não encontrado.",
+        ));
+    };
+    // Chave errada não é erro aqui: só não mostra as chaves de assento
+    let organizador = chave.is_some_and(|c| sessao::senha_confere(&torneio.chave_admin, &c));
+    let resumo = if organizador {
+        torneio.resumo_organizador()
+    } else {
+        torneio.resumo()
+    };
+    Ok(warp::reply::json(&resumo).into_response())
+}
+
+async fn inscrever_dupla(
+    id: u32,
+    chave: Option<String>,
+    pedido: NovaDupla,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let torneios = torneios_do(&global_state);
+    Ok(como_organizador(&torneios, id, chave, |torneio| {
+        torneio.inscrever(pedido)
+    }))
+}
+
+async fn lancar_resultado(
+    id: u32,
+    chave: Option<String>,
+    pedido: ResultadoManual,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let torneios = torneios_do(&global_state);
+    Ok(como_organizador(&torneios, id, chave, |torneio| {
+        let resultado = ResultadoMesa {
+            pontos_a: pedido.pontos_a,
+            pontos_b: pedido.pontos_b,
+        };
+        torneio
+            .registrar(pedido.rodada, pedido.mesa, resultado, true)
+            .map(|_| {
+                tracing::info!(
+                    torneio = torneio.id,
+                    rodada = pedido.rodada,
+                    mesa = pedido.mesa + 1,
+                    "resultado lançado pelo organizador"
+                )
+            })
+    }))
+}
+
+/// Emparelha a próxima rodada e abre uma sala por confronto, com os assentos reservados.
+async fn gerar_rodada(
+    id: u32,
+    chave: Option<String>,
+    global_state: GlobalState,
+) -> Result<warp::reply::Response, Infallible> {
+    let torneios = torneios_do(&global_state);
+    let vinculo = Arc::downgrade(&torneios);
+
+    Ok(como_organizador(&torneios, id, chave, |torneio| {
+        let confrontos = torneio.emparelhar()?;
+        global_state.cabem_salas(confrontos.iter().filter(|(_, b)| b.is_some()).count())?;
+        let numero = torneio.rodadas.len() as u32 + 1;
+        // Todas as reservas antes de abrir qualquer sala: um erro não deixa mesa pela metade
+        let confrontos = confrontos
+            .into_iter()
+            .map(|(dupla_a, dupla_b)| {
+                let reservas = match dupla_b {
+                    Some(b) => Some((torneio.reservas(dupla_a)?, torneio.reservas(b)?)),
+                    None => None,
+                };
+                Ok((dupla_a, dupla_b, reservas))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mut mesas = Vec::new();
+        for (indice, (dupla_a, dupla_b, reservas)) in confrontos.into_iter().enumerate() {
+            let sala = match reservas {
+                None => String::new(),
+                Some(([a0, a1], [b0, b1])) => {
+                    let mut room = Room::from_nova_sala(
+                        NovaSala {
+                            nome: format!("{} · R{} M{}", torneio.nome, numero, indice + 1),
+                            publica: true,
+                            senha: None,
+                            regras: None,
+                            relogio: torneio.relogio,
+                            espectadores: ConfigEspectadores::default(),
+                        },
+                        torneio.regras.clone(),
+                    );
+                    room.reservas = HashMap::from([(0, a0), (2, a1), (1, b0), (3, b1)]);
+                    room.torneio = Some(VinculoTorneio {
+                        torneios: vinculo.clone(),
+                        id: torneio.id,
+                        rodada: numero,
+                        mesa: indice,
+                    });
+                    lobby::abrir_sala(&global_state, room).codigo
+                }
+            };
+            mesas.push(MesaTorneio {
+                sala,
+                dupla_a,
+                dupla_b,
+                resultado: None,
+            });
+        }
+
+        tracing::info!(
+            torneio = torneio.id,
+            rodada = numero,
+            mesas = mesas.len(),
+            "rodada de torneio gerada"
+        );
+        torneio.rodadas.push(RodadaTorneio { numero, mesas });
+        Ok(())
+    }))
+}
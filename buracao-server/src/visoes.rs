@@ -0,0 +1,252 @@
+use crate::logs;
+use crate::sala::SalaFraca;
+use crate::state::{MAX_JOGADORES, PlayerId, Room, Saida, Sender};
+use buracao_core::acoes::{MsgServidor, TempoTurno, VisaoJogador};
+use buracao_core::estado::EstadoJogo;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::Instrument;
+
+// --- VISÕES FORA DA SALA ---
+// Montar a visão de cada assento e comparar com a anterior é o trabalho mais pesado de
+// uma ação, e não precisa da sala. Depois de cada mudança a sala manda uma foto do jogo
+// (`Arc<EstadoJogo>`) para a tarefa de visões dela, que monta as visões e os deltas.
+// Espectadores recebem direto dessa tarefa (não têm seq); para os jogadores ela devolve
+// as mensagens prontas, e a sala só numera e guarda para a retomada.
+//
+// Foto completa pedida pela sala (login, Resync, assento que trocou de dono) abre uma época
+// nova para aquele destino: o que foi montado antes, e ainda está a caminho, é descartado
+// na volta, porque parte de uma base que o cliente não tem.
+
+/// Canal para a tarefa de visões e a época de cada destino (assento ou espectador).
+#[derive(Default)]
+pub struct Visoes {
+    pedidos: Option<mpsc::UnboundedSender<Pedido>>,
+    epocas: HashMap<u32, u64>,
+    ultima_epoca: u64,
+}
+
+impl Visoes {
+    /// Coloca a tarefa para rodar; ela acaba junto com a sala.
+    pub fn iniciar(&mut self, sala: SalaFraca, codigo: &str) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(montar(sala, rx).instrument(logs::span_sala(codigo)));
+        self.pedidos = Some(tx);
+    }
+
+    fn epoca(&self, id: u32) -> u64 {
+        self.epocas.get(&id).copied().unwrap_or(0)
+    }
+}
+
+enum Pedido {
+    /// Depois de uma mudança: todos os destinos. Quem não está na lista é esquecido.
+    Propagar(Foto),
+    /// Foto completa só para os destinos da lista
+    Reenviar(Foto),
+}
+
+struct Foto {
+    estado: Arc<EstadoJogo>,
+    tempo: Option<TempoTurno>,
+    destinos: Vec<Destino>,
+}
+
+enum Destino {
+    Jogador {
+        pid: PlayerId,
+        epoca: u64,
+    },
+    Espectador {
+        id: u32,
+        epoca: u64,
+        treinando: Option<PlayerId>,
+        tx: Sender,
+    },
+}
+
+impl Destino {
+    fn id(&self) -> u32 {
+        match self {
+            Destino::Jogador { pid, .. } => *pid,
+            Destino::Espectador { id, .. } => *id,
+        }
+    }
+}
+
+/// Mensagem pronta para um jogador, montada na época `epoca` dele.
+pub struct Entrega {
+    pid: PlayerId,
+    epoca: u64,
+    msg: Arc<MsgServidor>,
+}
+
+/// Última visão mandada a um destino (base para o próximo delta).
+struct Base {
+    epoca: u64,
+    visao: VisaoJogador,
+}
+
+async fn montar(sala: SalaFraca, mut pedidos: mpsc::UnboundedReceiver<Pedido>) {
+    let mut bases: HashMap<u32, Base> = HashMap::new();
+    while let Some(pedido) = pedidos.recv().await {
+        let foto = match pedido {
+            Pedido::Propagar(foto) => {
+                bases.retain(|id, _| foto.destinos.iter().any(|d| d.id() == *id));
+                foto
+            }
+            Pedido::Reenviar(foto) => foto,
+        };
+        let entregas = montar_foto(&mut bases, foto);
+        if !entregas.is_empty() && !sala.despachar(move |room| room.entregar_visoes(entregas)) {
+            break;
+        }
+    }
+    tracing::debug!("tarefa de visões encerrada");
+}
+
+/// Monta a visão de cada destino; espectadores recebem na hora, jogadores voltam para a sala.
+fn montar_foto(bases: &mut HashMap<u32, Base>, foto: Foto) -> Vec<Entrega> {
+    let com_tempo = |mut visao: VisaoJogador| {
+        visao.tempo = foto.tempo;
+        visao
+    };
+    // A visão pública é igual para todos os espectadores: monta uma vez
+    let mut publica: Option<VisaoJogador> = None;
+    let mut entregas = Vec::new();
+
+    for destino in &foto.destinos {
+        match destino {
+            Destino::Jogador { pid, epoca } => {
+                let nova = com_tempo(foto.estado.gerar_visao_para_jogador(*pid));
+                if let Some(msg) = proxima_mensagem(bases, *pid, *epoca, nova) {
+                    entregas.push(Entrega {
+                        pid: *pid,
+                        epoca: *epoca,
+                        msg,
+                    });
+                }
+            }
+            Destino::Espectador {
+                id,
+                epoca,
+                treinando,
+                tx,
+            } => {
+                let nova = match treinando {
+                    Some(_) => com_tempo(foto.estado.gerar_visao_espectador(*treinando)),
+                    None => publica
+                        .get_or_insert_with(|| com_tempo(foto.estado.gerar_visao_espectador(None)))
+                        .clone(),
+                };
+                if let Some(msg) = proxima_mensagem(bases, *id, *epoca, nova) {
+                    let _ = tx.send(Saida::Msg { seq: None, msg });
+                }
+            }
+        }
+    }
+    entregas
+}
+
+/// Delta contra a base do destino; sem base da mesma época, a foto completa.
+/// `None` = nada mudou para ele, e a base continua a mesma.
+fn proxima_mensagem(
+    bases: &mut HashMap<u32, Base>,
+    id: u32,
+    epoca: u64,
+    nova: VisaoJogador,
+) -> Option<Arc<MsgServidor>> {
+    let msg = match bases.get(&id) {
+        Some(base) if base.epoca == epoca => {
+            let delta = base.visao.diferenca(&nova);
+            if delta.vazio() {
+                return None;
+            }
+            MsgServidor::Delta(delta)
+        }
+        _ => MsgServidor::Estado(nova.clone()),
+    };
+    bases.insert(id, Base { epoca, visao: nova });
+    Some(Arc::new(msg))
+}
+
+impl Room {
+    /// Manda a foto do jogo para a tarefa de visões: cada um recebe o que mudou para ele.
+    pub(crate) fn propagar_visoes(&mut self, tempo: Option<TempoTurno>) {
+        let foto = self.foto(tempo, None);
+        self.pedir_visoes(Pedido::Propagar(foto));
+    }
+
+    /// Foto completa para um destino só (jogador ou espectador).
+    pub(crate) fn reenviar_visao(&mut self, id: u32, tempo: Option<TempoTurno>) {
+        self.reiniciar_visao(id);
+        let foto = self.foto(tempo, Some(id));
+        self.pedir_visoes(Pedido::Reenviar(foto));
+    }
+
+    /// A próxima visão de `id` sai completa; o que já estiver a caminho para ele é descartado.
+    pub(crate) fn reiniciar_visao(&mut self, id: u32) {
+        self.visoes.ultima_epoca += 1;
+        let epoca = self.visoes.ultima_epoca;
+        self.visoes.epocas.insert(id, epoca);
+    }
+
+    /// Todos os destinos recomeçam da foto completa (assentos trocados, mão nova).
+    pub(crate) fn reiniciar_visoes(&mut self) {
+        let ids: Vec<u32> = (0..MAX_JOGADORES)
+            .chain(self.espectadores.keys().copied())
+            .collect();
+        for id in ids {
+            self.reiniciar_visao(id);
+        }
+    }
+
+    /// Espectador que saiu: a época dele não serve mais para nada.
+    pub(crate) fn esquecer_visao(&mut self, id: u32) {
+        self.visoes.epocas.remove(&id);
+    }
+
+    fn foto(&self, tempo: Option<TempoTurno>, apenas: Option<u32>) -> Foto {
+        let incluir = |id: u32| apenas.is_none_or(|apenas| apenas == id);
+        let jogadores = self
+            .jogadores_sentados()
+            .into_iter()
+            .filter(|&pid| incluir(pid))
+            .map(|pid| Destino::Jogador {
+                pid,
+                epoca: self.visoes.epoca(pid),
+            });
+        let espectadores = self
+            .espectadores
+            .iter()
+            .filter(|(id, _)| incluir(**id))
+            .map(|(id, espectador)| Destino::Espectador {
+                id: *id,
+                epoca: self.visoes.epoca(*id),
+                treinando: espectador.treinando,
+                tx: espectador.tx.clone(),
+            });
+        Foto {
+            estado: Arc::new(self.game_state.clone()),
+            tempo,
+            destinos: jogadores.chain(espectadores).collect(),
+        }
+    }
+
+    fn pedir_visoes(&self, pedido: Pedido) {
+        if let Some(pedidos) = &self.visoes.pedidos {
+            let _ = pedidos.send(pedido);
+        }
+    }
+
+    /// Volta da tarefa de visões: numera e guarda como qualquer mensagem do jogador.
+    fn entregar_visoes(&mut self, entregas: Vec<Entrega>) {
+        for entrega in entregas {
+            // Montada antes de uma foto completa: o cliente não tem a base desse delta
+            if self.visoes.epoca(entrega.pid) == entrega.epoca {
+                self.entregar(entrega.pid, entrega.msg);
+            }
+        }
+    }
+}
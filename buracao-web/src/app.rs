@@ -1,6 +1,4 @@
 use futures::channel::mpsc;
-use futures::{SinkExt, StreamExt};
-use gloo_net::websocket::{futures::WebSocket, Message};
 use leptos::html::Audio;
 use leptos::prelude::window;
 use leptos::prelude::*;
@@ -10,27 +8,34 @@ use std::collections::HashSet;
 use wasm_bindgen_futures::JsFuture;
 
 use crate::components::board::Board;
+use crate::components::chat::ChatPanel;
 use crate::components::controls::GameControls;
 use crate::components::hand::Hand;
 use crate::components::login::LoginScreen;
+use crate::components::match_end::{MatchEndModal, ResumoFim};
 use crate::components::notification::{NotificationToast, Toast, ToastType};
+use crate::components::reactions::ReactionBar;
 use crate::components::scoreboard::Scoreboard;
 use crate::components::settings::SettingsModal;
 use crate::components::table::Table;
+use crate::components::turn_clock::TurnClock;
 use crate::components::turn_indicator::TurnIndicator;
+use crate::services::socket::{self, StatusConexao};
 
 use crate::utils::assets::get_card_path;
+use crate::utils::helper::{
+    apagar_token_anfitriao, get_or_create_device_id, ler_identidade, ler_token_anfitriao,
+    salvar_id_perfil, salvar_identidade,
+};
 use crate::utils::mappers::{carta_para_asset, verso_para_asset};
 
-use buracao_core::acoes::{AcaoJogador, DetalheJogo, MsgServidor};
+use buracao_core::acoes::{
+    AcaoJogador, ComandoSala, DadosLogin, DetalheJogo, MensagemChat, ModoEspectador, MotivoRecusa,
+    MsgCliente, MsgServidor, PresencaJogador, Reacao, TempoTurno, VisaoJogador, VotoFimDeJogo,
+    ID_ESPECTADOR, VERSAO_PROTOCOLO,
+};
 use buracao_core::baralho::Carta;
 
-#[derive(serde::Deserialize, Debug, Clone)]
-struct EventoNomes {
-    // tipo: String, // Não precisamos mapear o tipo aqui pois já filtramos antes
-    mapa: std::collections::HashMap<u32, String>,
-}
-
 #[component]
 fn CardImage(
     carta: buracao_core::baralho::Carta,
@@ -42,20 +47,19 @@ fn CardImage(
     view! { <img src=src style=format!("width: {}; height: auto;", width) /> }
 }
 
-fn get_or_create_device_id() -> String {
-    let window = web_sys::window().expect("no global `window` exists");
-    let storage = window
-        .local_storage()
-        .ok()
-        .flatten()
-        .expect("no local storage");
-
-    if let Ok(Some(id)) = storage.get_item("buraco_device_id") {
-        id
-    } else {
-        let new_id = format!("user_{}", rand::random::<u32>());
-        let _ = storage.set_item("buraco_device_id", &new_id);
-        new_id
+// O token de sessão é guardado por sala: cada assento tem o seu
+fn chave_token(sala: &str) -> String {
+    format!("buraco_token_{}", sala)
+}
+
+fn ler_token_sessao(sala: &str) -> Option<String> {
+    let storage = web_sys::window()?.local_storage().ok()??;
+    storage.get_item(&chave_token(sala)).ok()?
+}
+
+fn salvar_token_sessao(sala: &str, token: &str) {
+    if let Some(Ok(Some(storage))) = web_sys::window().map(|w| w.local_storage()) {
+        let _ = storage.set_item(&chave_token(sala), token);
     }
 }
 
@@ -78,6 +82,8 @@ pub fn App() -> impl IntoView {
     let (meu_id, set_meu_id) = signal(0_u32);
     let (status_jogo, set_status_jogo) = signal("Conectando...".to_string());
     let (sou_o_jogador_da_vez, set_sou_o_jogador_da_vez) = signal(false);
+    // Relógio da vez (só em salas com limite de tempo) e quando ele chegou
+    let (tempo_turno, set_tempo_turno) = signal(Option::<(TempoTurno, f64)>::None);
     let (lixo_selecionado, set_lixo_selecionado) = signal(false);
     let selected_indices = RwSignal::new(HashSet::new());
     let (ws_sender, set_ws_sender) = signal(Option::<mpsc::UnboundedSender<String>>::None);
@@ -105,6 +111,14 @@ pub fn App() -> impl IntoView {
     let (in_game, set_in_game) = signal(false); // false = Tela de Login, true = Jogo
     let (player_name, set_player_name) = signal("".to_string());
     let (room_code, set_room_code) = signal("".to_string());
+    let (room_password, set_room_password) = signal("".to_string());
+    // Some = entrou só para assistir
+    let (modo_espectador, set_modo_espectador) = signal(Option::<ModoEspectador>::None);
+    let (qtd_espectadores, set_qtd_espectadores) = signal(0_usize);
+
+    // --- FIM DE PARTIDA ---
+    let (fim_de_jogo, set_fim_de_jogo) = signal(Option::<ResumoFim>::None);
+    let (votos_fim, set_votos_fim) = signal(std::collections::HashMap::<u32, VotoFimDeJogo>::new());
     let device_id = StoredValue::new(get_or_create_device_id());
 
     // NOVO: Mapa de Nomes para traduzir IDs
@@ -112,7 +126,27 @@ pub fn App() -> impl IntoView {
 
     let (qtd_cartas_jogadores, set_qtd_cartas_jogadores) = signal(Vec::<usize>::new());
 
+    // --- CHAT ---
+    let (mensagens_chat, set_mensagens_chat) = signal(Vec::<MensagemChat>::new());
+
+    // --- MODERAÇÃO DA SALA ---
+    let (host_id, set_host_id) = signal(Option::<u32>::None);
+    let (presenca, set_presenca) = signal(std::collections::HashMap::<u32, PresencaJogador>::new());
+    let (sala_trancada, set_sala_trancada) = signal(false);
+    let (votos_expulsao, set_votos_expulsao) =
+        signal(std::collections::HashMap::<u32, usize>::new());
+
     let audio_ref = NodeRef::<Audio>::new();
+    let audio_reacao_ref = NodeRef::<Audio>::new();
+
+    // --- REAÇÕES RÁPIDAS ---
+    // Reação visível em cada assento; o id serve para só apagar o balão certo
+    let (reacoes_ativas, set_reacoes_ativas) =
+        signal(std::collections::HashMap::<u32, (u64, Reacao)>::new());
+    let proximo_id_reacao = StoredValue::new(0_u64);
+    // Jogadores silenciados (vale só para esta aba) e som das reações
+    let silenciados = RwSignal::new(HashSet::<u32>::new());
+    let som_reacoes = RwSignal::new(true);
 
     let add_toast = move |msg: String, tipo: ToastType| {
         let id = next_toast_id.get_value();
@@ -132,16 +166,20 @@ pub fn App() -> impl IntoView {
         );
     };
 
-    let ao_entrar = Callback::new(move |(nome, sala): (String, String)| {
-        minha_mao.set(Vec::new());
-        set_mesa_a.set(Vec::new());
-        set_mesa_b.set(Vec::new());
-        set_jogos_preparados.set(Vec::new());
-        set_status_jogo.set("Conectando à sala...".to_string());
-        set_player_name.set(nome);
-        set_room_code.set(sala);
-        set_in_game.set(true);
-    });
+    let ao_entrar = Callback::new(
+        move |(nome, sala, senha, modo): (String, String, String, Option<ModoEspectador>)| {
+            minha_mao.set(Vec::new());
+            set_mesa_a.set(Vec::new());
+            set_mesa_b.set(Vec::new());
+            set_jogos_preparados.set(Vec::new());
+            set_status_jogo.set("Conectando à sala...".to_string());
+            set_player_name.set(nome);
+            set_room_code.set(sala);
+            set_room_password.set(senha);
+            set_modo_espectador.set(modo);
+            set_in_game.set(true);
+        },
+    );
 
     let acao_sair = move |_| {
         set_in_game.set(false);
@@ -203,187 +241,359 @@ pub fn App() -> impl IntoView {
         }
     };
 
-    Effect::new(move |_| {
-        if !in_game.get() {
+    // Som curto das reações: mesmo arquivo, bem mais baixo que o aviso de vez
+    let tocar_som_reacao = move || {
+        let vol = volume.get_untracked() * 0.3;
+        if vol <= 0.0 {
             return;
         }
+        if let Some(audio_element) = audio_reacao_ref.get() {
+            audio_element.set_volume(vol);
+            audio_element.set_current_time(0.0);
+            if let Ok(promise) = audio_element.play() {
+                spawn_local(async move {
+                    let _ = JsFuture::from(promise).await;
+                });
+            }
+        }
+    };
 
-        let (tx, mut rx) = mpsc::unbounded();
-        set_ws_sender.set(Some(tx));
-
-        spawn_local(async move {
-            let ws_url = {
-                let location = window().location();
-                let protocol = if location.protocol().unwrap() == "https:" {
-                    "wss"
-                } else {
-                    "ws"
-                };
-                let host = location.host().unwrap();
-                if host.contains("3000") {
-                    "ws://127.0.0.1:8080/buraco".to_string()
-                } else {
-                    format!("{}://{}/buraco", protocol, host)
-                }
-            };
-
-            leptos::logging::log!("Tentando conectar no WebSocket em: {}", ws_url);
-            let ws = match WebSocket::open(&ws_url) {
-                Ok(ws) => ws,
-                Err(e) => {
-                    leptos::logging::error!("Erro WS: {:?}", e);
-                    set_status_jogo.set("Erro na conexão!".to_string());
-                    return;
-                }
-            };
-
-            let (mut write, mut read) = ws.split();
-            set_status_jogo.set("Conectado! Aguardando jogo...".to_string());
-
-            let login_msg = serde_json::json!({
-                "tipo": "Login",
-                "device_id": device_id.get_value(),
-                "nome": player_name.get_untracked(),
-                "sala": room_code.get_untracked()
-            });
+    // Última visão completa recebida; os deltas do servidor são aplicados em cima dela
+    let ultima_visao = StoredValue::new(Option::<VisaoJogador>::None);
 
-            leptos::logging::log!(">>> ENVIANDO LOGIN: {}", login_msg.to_string());
+    // Joga a visão (foto ou já com o delta aplicado) nos sinais da tela
+    let aplicar_visao = move |visao: VisaoJogador| {
+        // 1. Atualiza dados básicos
+        minha_mao.set(visao.minha_mao);
+        set_lixo_topo.set(visao.lixo);
+        set_meu_id.set(visao.meu_id);
 
-            if let Err(e) = write.send(Message::Text(login_msg.to_string())).await {
-                leptos::logging::error!("Falha crítica ao enviar login: {:?}", e);
-                set_status_jogo.set("Erro ao autenticar".to_string());
-                return;
-            }
+        set_qtd_cartas_jogadores.set(visao.qtd_cartas_jogadores);
 
-            spawn_local(async move {
-                while let Some(msg_json) = rx.next().await {
-                    if let Err(e) = write.send(Message::Text(msg_json)).await {
-                        leptos::logging::error!("Falha envio: {:?}", e);
-                    }
-                }
-            });
+        set_mesa_a.set(visao.mesa_time_a);
+        set_mesa_b.set(visao.mesa_time_b);
 
-            while let Some(msg) = read.next().await {
-                if let Ok(Message::Text(text)) = msg {
-                    // TENTATIVA 1: É uma mensagem padrão do jogo?
-                    if let Ok(msg_servidor) = serde_json::from_str::<MsgServidor>(&text) {
-                        match msg_servidor {
-                            MsgServidor::BoasVindas { .. } => {
-                                // Apenas ignora ou loga, já que estamos usando a mensagem de Estado para sincronizar
-                                leptos::logging::log!("👋 Boas vindas recebidas");
-                            }
-                            MsgServidor::Estado(visao) => {
-                                // 1. Atualiza dados básicos
-                                minha_mao.set(visao.minha_mao);
-                                set_lixo_topo.set(visao.lixo);
-                                set_meu_id.set(visao.meu_id);
+        set_pontuacao_a.set(visao.pontuacao_a);
+        set_pontuacao_b.set(visao.pontuacao_b);
 
-                                set_qtd_cartas_jogadores.set(visao.qtd_cartas_jogadores);
+        set_tres_vermelhos_a.set(visao.tres_vermelho_time_a);
+        set_tres_vermelhos_b.set(visao.tres_vermelho_time_b);
 
-                                set_mesa_a.set(visao.mesa_time_a);
-                                set_mesa_b.set(visao.mesa_time_b);
+        set_sou_o_jogador_da_vez.set(visao.posso_jogar);
+        set_tempo_turno.set(visao.tempo.map(|t| (t, js_sys::Date::now())));
 
-                                set_pontuacao_a.set(visao.pontuacao_a);
-                                set_pontuacao_b.set(visao.pontuacao_b);
+        // --- LÓGICA CORRIGIDA DE SOM E TURNO ---
 
-                                set_tres_vermelhos_a.set(visao.tres_vermelho_time_a);
-                                set_tres_vermelhos_b.set(visao.tres_vermelho_time_b);
+        // 1. Captura o estado ANTIGO (antes de atualizar o sinal)
+        let turno_antigo = turno_atual_id.get_untracked();
 
-                                set_sou_o_jogador_da_vez.set(visao.posso_jogar);
+        // 2. Pega os dados NOVOS
+        let turno_novo = visao.turno_atual;
+        let sou_eu = visao.meu_id;
 
-                                // --- LÓGICA CORRIGIDA DE SOM E TURNO ---
+        // 3. AGORA SIM: Atualiza o estado visual para o novo
+        set_turno_atual_id.set(turno_novo);
 
-                                // 1. Captura o estado ANTIGO (antes de atualizar o sinal)
-                                let turno_antigo = turno_atual_id.get_untracked();
+        // 4. Verifica se houve MUDANÇA para a MINHA vez
+        if turno_novo == sou_eu {
+            if turno_antigo != sou_eu {
+                leptos::logging::log!(
+                    "🔔 [SOM] Mudança de turno detectada ({} -> {}). Tocando!",
+                    turno_antigo,
+                    turno_novo
+                );
+                tocar_som_sua_vez();
+                add_toast("Sua vez de jogar!".to_string(), ToastType::Info);
+            } else {
+                leptos::logging::log!("ℹ️ [SOM] Já era minha vez. Silêncio.");
+            }
+        } else {
+            leptos::logging::log!("zzz [SOM] Vez do jogador {}.", turno_novo);
+        }
 
-                                // 2. Pega os dados NOVOS
-                                let turno_novo = visao.turno_atual;
-                                let sou_eu = visao.meu_id;
+        // ---------------------------------------
 
-                                // 3. AGORA SIM: Atualiza o estado visual para o novo
-                                set_turno_atual_id.set(turno_novo);
+        // Atualiza texto de status (com número da rodada apenas)
+        // O "Vez de..." agora é calculado no view!
+        set_status_jogo.set(format!("Rodada {}", visao.rodada));
 
-                                // 4. Verifica se houve MUDANÇA para a MINHA vez
-                                if turno_novo == sou_eu {
-                                    if turno_antigo != sou_eu {
-                                        leptos::logging::log!(
-                                            "🔔 [SOM] Mudança de turno detectada ({} -> {}). Tocando!",
-                                            turno_antigo,
-                                            turno_novo
-                                        );
-                                        tocar_som_sua_vez();
-                                        add_toast("Sua vez de jogar!".to_string(), ToastType::Info);
-                                    } else {
-                                        leptos::logging::log!(
-                                            "ℹ️ [SOM] Já era minha vez. Silêncio."
-                                        );
-                                    }
-                                } else {
-                                    leptos::logging::log!(
-                                        "zzz [SOM] Vez do jogador {}.",
-                                        turno_novo
-                                    );
-                                }
+        set_jogos_preparados.set(Vec::new());
 
-                                // ---------------------------------------
+        set_qtd_monte.set(visao.qtd_monte);
+        set_qtd_lixo.set(visao.qtd_lixo);
 
-                                // Atualiza texto de status (com número da rodada apenas)
-                                // O "Vez de..." agora é calculado no view!
-                                set_status_jogo.set(format!("Rodada {}", visao.rodada));
+        let nome_arquivo = verso_para_asset(visao.verso_topo);
+        set_verso_monte.set(nome_arquivo.to_string());
+    };
 
-                                set_jogos_preparados.set(Vec::new());
+    // O servidor recusou o que mandamos: avisa e devolve à mão os jogos preparados
+    let jogada_recusada = move |texto: String| {
+        add_toast(texto, ToastType::Error);
+        selected_indices.update(|s| s.clear());
 
-                                set_qtd_monte.set(visao.qtd_monte);
-                                set_qtd_lixo.set(visao.qtd_lixo);
+        let jogos_pendentes = jogos_preparados.get();
+        if !jogos_pendentes.is_empty() {
+            minha_mao.update(|mao| {
+                for jogo in jogos_pendentes {
+                    mao.extend(jogo);
+                }
+                mao.sort();
+            });
+            set_jogos_preparados.set(Vec::new());
+        }
 
-                                let nome_arquivo = verso_para_asset(visao.verso_topo);
-                                set_verso_monte.set(nome_arquivo.to_string());
-                            }
-                            MsgServidor::Erro(e) => {
-                                add_toast(format!("ERRO: {}", e), ToastType::Error);
-                                selected_indices.update(|s| s.clear());
-
-                                let jogos_pendentes = jogos_preparados.get();
-                                if !jogos_pendentes.is_empty() {
-                                    minha_mao.update(|mao| {
-                                        for jogo in jogos_pendentes {
-                                            mao.extend(jogo);
-                                        }
-                                        mao.sort();
-                                    });
-                                    set_jogos_preparados.set(Vec::new());
-                                }
+        // Não precisamos mais do set_timeout para corrigir o texto,
+        // pois o view! é reativo e recalcula tudo automaticamente.
+    };
 
-                                // Não precisamos mais do set_timeout para corrigir o texto,
-                                // pois o view! é reativo e recalcula tudo automaticamente.
-                            }
-                            MsgServidor::Notificacao(n) => {
-                                add_toast(n, ToastType::Info);
-                            }
-                            MsgServidor::FimDeJogo { vencedor_time, .. } => {
-                                set_status_jogo.set(format!("Vencedor: Time {}", vencedor_time));
-                            }
+    // Trata cada mensagem do servidor (já sem o envelope de seq)
+    let tratar_msg = move |msg_servidor: MsgServidor| {
+        match msg_servidor {
+            MsgServidor::BoasVindas { id_jogador, .. } => {
+                leptos::logging::log!("👋 Boas vindas: assento {}", id_jogador);
+                set_meu_id.set(id_jogador);
+            }
+            MsgServidor::BoasVindasEspectador {
+                treinando,
+                atraso_segundos,
+                ..
+            } => {
+                set_meu_id.set(treinando.unwrap_or(ID_ESPECTADOR));
+                if atraso_segundos > 0 {
+                    add_toast(
+                        format!("Transmissão com {} s de atraso.", atraso_segundos),
+                        ToastType::Info,
+                    );
+                }
+            }
+            MsgServidor::LoginRecusado(motivo) => {
+                if let MotivoRecusa::VersaoIncompativel { servidor, cliente } = &motivo {
+                    leptos::logging::warn!(
+                        "Protocolo incompatível: servidor v{}, cliente v{}",
+                        servidor,
+                        cliente
+                    );
+                }
+                add_toast(motivo.texto(), ToastType::Error);
+                set_status_jogo.set(motivo.texto());
+                set_in_game.set(false);
+            }
+            MsgServidor::Nomes(mapa) => {
+                leptos::logging::log!("👥 [NOMES] Recebi lista: {:?}", mapa);
+                set_mapa_nomes.set(mapa);
+            }
+            MsgServidor::Identidade { id_perfil, chave } => {
+                // Perfil novo: guarda a chave para os próximos logins
+                if let Some(chave) = chave {
+                    salvar_identidade(&chave);
+                }
+                salvar_id_perfil(id_perfil);
+            }
+            MsgServidor::Sessao { token } => {
+                let sala = room_code.get_untracked();
+                salvar_token_sessao(&sala, &token);
+                // Login aceito: o token de anfitrião já foi usado (ou não serve mais)
+                apagar_token_anfitriao(&sala);
+            }
+            MsgServidor::Estado(visao) => {
+                ultima_visao.set_value(Some(visao.clone()));
+                aplicar_visao(visao);
+            }
+            MsgServidor::Delta(delta) => {
+                let mut visao = ultima_visao.get_value();
+                match visao.as_mut().map(|v| v.aplicar_delta(&delta)) {
+                    Some(Ok(())) => {
+                        ultima_visao.set_value(visao.clone());
+                        aplicar_visao(visao.unwrap());
+                    }
+                    // Perdemos algum delta (ou ainda não temos a foto): pede tudo de novo
+                    falha => {
+                        if let Some(Err(e)) = falha {
+                            leptos::logging::warn!("🔁 [SYNC] {}", e);
+                        }
+                        if let Some(sender) = ws_sender.get_untracked() {
+                            let json = serde_json::to_string(&MsgCliente::Resync).unwrap();
+                            let _ = sender.unbounded_send(json);
                         }
                     }
-                    // TENTATIVA 2: É a lista de nomes?
-                    else if let Ok(evento) = serde_json::from_str::<EventoNomes>(&text) {
-                        leptos::logging::log!("👥 [NOMES] Recebi lista: {:?}", evento.mapa);
-                        set_mapa_nomes.set(evento.mapa);
+                }
+            }
+            MsgServidor::Erro(e) => jogada_recusada(format!("ERRO: {}", e)),
+            MsgServidor::MensagemRejeitada(erro) => {
+                leptos::logging::warn!("Mensagem rejeitada pelo servidor: {:?}", erro);
+                jogada_recusada(erro.texto());
+            }
+            MsgServidor::Notificacao(n) => {
+                add_toast(n, ToastType::Info);
+            }
+            MsgServidor::InfoSala {
+                host,
+                trancada,
+                votos_expulsao,
+                espectadores,
+            } => {
+                set_qtd_espectadores.set(espectadores);
+                set_host_id.set(host);
+                set_sala_trancada.set(trancada);
+                set_votos_expulsao.set(votos_expulsao);
+            }
+            MsgServidor::Presenca(mapa) => {
+                set_presenca.set(mapa);
+            }
+            MsgServidor::Chat(mensagem) => {
+                set_mensagens_chat.update(|lista| {
+                    lista.push(mensagem);
+                    // Guarda só as mais recentes
+                    if lista.len() > 100 {
+                        lista.remove(0);
                     }
+                });
+            }
+            MsgServidor::HistoricoChat(historico) => {
+                set_mensagens_chat.set(historico);
+            }
+            MsgServidor::Reacao { jogador, reacao } => {
+                // Reações de quem foi silenciado são ignoradas
+                if silenciados.get_untracked().contains(&jogador) {
+                    return;
                 }
+                let id = proximo_id_reacao.get_value();
+                proximo_id_reacao.set_value(id + 1);
+                set_reacoes_ativas.update(|r| {
+                    r.insert(jogador, (id, reacao));
+                });
+                set_timeout(
+                    move || {
+                        set_reacoes_ativas.update(|r| {
+                            if r.get(&jogador).is_some_and(|(i, _)| *i == id) {
+                                r.remove(&jogador);
+                            }
+                        });
+                    },
+                    std::time::Duration::from_secs(3),
+                );
+                if jogador != meu_id.get_untracked() && som_reacoes.get_untracked() {
+                    tocar_som_reacao();
+                }
+            }
+            MsgServidor::Expulso { motivo } => {
+                add_toast(motivo.clone(), ToastType::Error);
+                set_status_jogo.set(motivo);
+                set_in_game.set(false);
             }
+            MsgServidor::FimDeJogo {
+                vencedor_time,
+                pontos_a,
+                pontos_b,
+                motivo,
+                empate,
+                serie,
+            } => {
+                set_status_jogo.set(if empate {
+                    "Empate".to_string()
+                } else {
+                    format!("Vencedor: Time {}", vencedor_time)
+                });
+                set_fim_de_jogo.set(Some(ResumoFim {
+                    vencedor_time,
+                    pontos_a,
+                    pontos_b,
+                    motivo,
+                    empate,
+                    serie,
+                }));
+            }
+            MsgServidor::VotosFimDeJogo(votos) => {
+                set_votos_fim.set(votos);
+            }
+            MsgServidor::NovaPartida {
+                serie,
+                duplas_trocadas,
+            } => {
+                set_fim_de_jogo.set(None);
+                set_votos_fim.set(Default::default());
+                let mut texto = format!(
+                    "Nova partida! Série: Time A {} x {} Time B",
+                    serie.vitorias_a, serie.vitorias_b
+                );
+                if duplas_trocadas {
+                    texto.push_str(" (duplas trocadas)");
+                }
+                add_toast(texto, ToastType::Info);
+            }
+        }
+    };
 
-            set_status_jogo.set("Desconectado.".to_string());
-        });
+    Effect::new(move |_| {
+        if !in_game.get() {
+            return;
+        }
+
+        let sala = room_code.get_untracked();
+        let senha = room_password.get_untracked();
+        let nome = player_name.get_untracked();
+        let device = device_id.get_value();
+
+        let tx = socket::conectar(
+            socket::url_do_jogo(),
+            move |ultimo_seq| {
+                // Não loga a mensagem inteira: ela carrega senha e token
+                leptos::logging::log!(">>> ENVIANDO LOGIN na sala {}", sala);
+                MsgCliente::Login(DadosLogin {
+                    versao: VERSAO_PROTOCOLO,
+                    device_id: device.clone(),
+                    nome: nome.clone(),
+                    sala: sala.clone(),
+                    senha: if senha.is_empty() {
+                        None
+                    } else {
+                        Some(senha.clone())
+                    },
+                    // Lido a cada tentativa: o token muda a cada login aceito
+                    token: ler_token_sessao(&sala),
+                    ultimo_seq,
+                    espectador: modo_espectador.get_untracked(),
+                    identidade: ler_identidade(),
+                    token_anfitriao: ler_token_anfitriao(&sala),
+                })
+            },
+            tratar_msg,
+            move |status| match status {
+                StatusConexao::Conectando => {
+                    set_status_jogo.set("Conectando...".to_string());
+                }
+                StatusConexao::Conectado => {
+                    // Na retomada pode não vir foto nova: volta o texto da rodada que já temos
+                    let texto = match ultima_visao.get_value() {
+                        Some(v) => format!("Rodada {}", v.rodada),
+                        None => "Conectado! Aguardando jogo...".to_string(),
+                    };
+                    set_status_jogo.set(texto);
+                }
+                StatusConexao::Reconectando { tentativa } => {
+                    set_status_jogo.set(format!(
+                        "Conexão perdida. Reconectando (tentativa {})...",
+                        tentativa
+                    ));
+                }
+                StatusConexao::Encerrada => {}
+            },
+        );
+        set_ws_sender.set(Some(tx));
     });
 
-    let enviar_acao = move |acao: AcaoJogador| {
+    let enviar_msg = move |msg: MsgCliente| {
         if let Some(sender) = ws_sender.get_untracked() {
-            let json = serde_json::to_string(&acao).unwrap();
+            let json = serde_json::to_string(&msg).unwrap();
             let _ = sender.unbounded_send(json);
         }
     };
 
+    let enviar_acao = move |acao: AcaoJogador| enviar_msg(MsgCliente::Acao(acao));
+
+    let enviar_comando =
+        Callback::new(move |comando: ComandoSala| enviar_msg(MsgCliente::Sala(comando)));
+
     let acao_descartar = move |_| {
         let indices = selected_indices.get();
         if indices.len() != 1 {
@@ -584,8 +794,21 @@ pub fn App() -> impl IntoView {
                             <small style="opacity: 0.8; font-size: 0.85rem;">
                                 {move || {
                                     let id = meu_id.get();
-                                    let time = if id % 2 == 0 { "Time A" } else { "Time B" };
-                                    format!("Meu ID: {} ({})", id, time)
+                                    let plateia = match qtd_espectadores.get() {
+                                        0 => String::new(),
+                                        n => format!(" · 👀 {}", n),
+                                    };
+                                    let quem = match modo_espectador.get() {
+                                        Some(ModoEspectador::Publico) => "👀 Assistindo".to_string(),
+                                        Some(ModoEspectador::Treinador(alvo)) => {
+                                            format!("🎓 Treinando o jogador {}", alvo)
+                                        }
+                                        None => {
+                                            let time = if id % 2 == 0 { "Time A" } else { "Time B" };
+                                            format!("Meu ID: {} ({})", id, time)
+                                        }
+                                    };
+                                    format!("{}{}", quem, plateia)
                                 }}
                             </small>
                             <button
@@ -661,7 +884,13 @@ pub fn App() -> impl IntoView {
                     {move || {
                         let sou_time_a = meu_id.get() % 2 == 0;
                         let cb = if sou_time_a { Some(Callback::new(acao_ajuntar)) } else { None };
-                        let titulo = if sou_time_a { "MEU TIME" } else { "TIME INIMIGO" };
+                        let titulo = if meu_id.get() == ID_ESPECTADOR {
+                            "TIME A"
+                        } else if sou_time_a {
+                            "MEU TIME"
+                        } else {
+                            "TIME INIMIGO"
+                        };
                         view! {
                             <Table
                                 titulo=titulo.to_string()
@@ -708,15 +937,26 @@ pub fn App() -> impl IntoView {
                                 current_turn=turno_atual_id
                                 names=mapa_nomes
                                 cards_count=qtd_cartas_jogadores
+                                reactions=Signal::derive(move || {
+                                    reacoes_ativas.get().into_iter().map(|(j, (_, r))| (j, r)).collect()
+                                })
+                                presence=presenca
                             />
                         </div>
+                        <TurnClock tempo=tempo_turno my_id=meu_id />
                     </div>
 
                     // MESA TIME B
                     {move || {
                         let sou_time_b = meu_id.get() % 2 != 0;
                         let cb = if sou_time_b { Some(Callback::new(acao_ajuntar)) } else { None };
-                        let titulo = if sou_time_b { "MEU TIME" } else { "TIME INIMIGO" };
+                        let titulo = if meu_id.get() == ID_ESPECTADOR {
+                            "TIME B"
+                        } else if sou_time_b {
+                            "MEU TIME"
+                        } else {
+                            "TIME INIMIGO"
+                        };
                         view! {
                             <Table
                                 titulo=titulo.to_string()
@@ -811,16 +1051,54 @@ pub fn App() -> impl IntoView {
                             current_theme_path=current_theme
                             card_scale=card_scale
                             volume=volume
+                            meu_id=meu_id
+                            host_id=host_id
+                            sala_trancada=sala_trancada
+                            votos_expulsao=votos_expulsao
+                            nomes=mapa_nomes
+                            on_comando=enviar_comando
+                            silenciados=silenciados
+                            som_reacoes=som_reacoes
                         />
                     </div>
                 </div>
 
+                <ChatPanel
+                    mensagens=mensagens_chat
+                    meu_id=meu_id
+                    on_enviar=Callback::new(move |(texto, apenas_time): (String, bool)| {
+                        enviar_msg(MsgCliente::Chat { texto, apenas_time });
+                    })
+                />
+
+                <Show when=move || modo_espectador.get().is_none()>
+                    <ReactionBar on_reagir=Callback::new(move |reacao: Reacao| {
+                        enviar_msg(MsgCliente::Reagir { reacao });
+                    }) />
+                </Show>
+
+                <MatchEndModal
+                    fim=fim_de_jogo
+                    votos=votos_fim
+                    nomes=mapa_nomes
+                    meu_id=meu_id
+                    pode_votar=Signal::derive(move || modo_espectador.get().is_none())
+                    on_votar=Callback::new(move |voto: VotoFimDeJogo| {
+                        enviar_msg(MsgCliente::VotoFimDeJogo(voto));
+                    })
+                />
+
                 <NotificationToast toasts=toasts />
                 <audio
                     node_ref=audio_ref
                     src=SOUND_PATH
                     style="display: none;"
                 />
+                <audio
+                    node_ref=audio_reacao_ref
+                    src=SOUND_PATH
+                    style="display: none;"
+                />
             </div>
         </Show>
     }
@@ -15,10 +15,13 @@ pub fn Board(
     #[prop(into)] verso_monte: Signal<String>,
     #[prop(default = None)] on_click_deck: Option<Callback<web_sys::MouseEvent>>,
     #[prop(default = None)] on_click_trash: Option<Callback<web_sys::MouseEvent>>,
+    // Replay: a compra do monte aparece como seleção da carta do monte
+    #[prop(into, default = Signal::derive(|| false))] monte_destacado: Signal<bool>,
 ) -> impl IntoView {
     // Helpers
     // Usamos tipagem explícita no None para ajudar o compilador
-    let no_selection = Signal::derive(|| Option::<usize>::None);
+    let selection_monte =
+        Signal::derive(move || if monte_destacado.get() { Some(1) } else { None });
     let selection_lixo_visual = Signal::derive(move || {
         if lixo_selecionado.get() {
             Some(1)
@@ -55,7 +58,7 @@ pub fn Board(
                     // A CORREÇÃO ESTÁ AQUI:
                     theme=theme_monte
 
-                    selection_group=no_selection
+                    selection_group=selection_monte
                     on_click=on_click_deck
                 />
             </div>
@@ -0,0 +1,122 @@
+use buracao_core::acoes::MensagemChat;
+use leptos::html::Div;
+use leptos::prelude::*;
+
+// Hora local "HH:MM" a partir do timestamp do servidor
+fn formatar_hora(timestamp_ms: u64) -> String {
+    let data = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(timestamp_ms as f64));
+    format!("{:02}:{:02}", data.get_hours(), data.get_minutes())
+}
+
+#[component]
+pub fn ChatPanel(
+    #[prop(into)] mensagens: Signal<Vec<MensagemChat>>,
+    #[prop(into)] meu_id: Signal<u32>,
+    // (texto, apenas_time)
+    #[prop(into)] on_enviar: Callback<(String, bool)>,
+) -> impl IntoView {
+    let (aberto, set_aberto) = signal(false);
+    let (texto, set_texto) = signal("".to_string());
+    let (apenas_time, set_apenas_time) = signal(false);
+    let (lidas, set_lidas) = signal(0_usize);
+    let lista_ref = NodeRef::<Div>::new();
+
+    let nao_lidas = move || mensagens.get().len().saturating_sub(lidas.get());
+
+    // Com o painel aberto, tudo que chega já conta como lido e a lista desce até o fim
+    Effect::new(move |_| {
+        let total = mensagens.get().len();
+        if aberto.get() {
+            set_lidas.set(total);
+            if let Some(lista) = lista_ref.get() {
+                lista.set_scroll_top(lista.scroll_height());
+            }
+        }
+    });
+
+    let enviar = move || {
+        let t = texto.get();
+        if t.trim().is_empty() {
+            return;
+        }
+        on_enviar.run((t, apenas_time.get()));
+        set_texto.set("".to_string());
+    };
+
+    view! {
+        <div style="position: fixed; bottom: 20px; left: 20px; z-index: 1500; font-family: sans-serif;">
+            <Show
+                when=move || aberto.get()
+                fallback=move || view! {
+                    <button
+                        on:click=move |_| set_aberto.set(true)
+                        style="background: rgba(0,0,0,0.7); color: white; border: 1px solid #ffeb3b; border-radius: 20px; padding: 8px 14px; cursor: pointer;"
+                    >
+                        {move || match nao_lidas() {
+                            0 => "💬 Chat".to_string(),
+                            n => format!("💬 Chat ({})", n),
+                        }}
+                    </button>
+                }
+            >
+                <div style="
+                    width: 300px; height: 320px; background: rgba(0,0,0,0.85); border-radius: 10px;
+                    border: 1px solid #444; display: flex; flex-direction: column; color: white;
+                    box-shadow: 0 4px 12px rgba(0,0,0,0.5);
+                ">
+                    <div style="display: flex; justify-content: space-between; align-items: center; padding: 8px 10px; border-bottom: 1px solid #444;">
+                        <strong style="font-size: 13px; color: #ffeb3b;">"Chat da Mesa"</strong>
+                        <button on:click=move |_| set_aberto.set(false) style="background: none; border: none; color: #aaa; cursor: pointer;">"✕"</button>
+                    </div>
+
+                    <div node_ref=lista_ref style="flex: 1; overflow-y: auto; padding: 8px 10px; font-size: 13px; display: flex; flex-direction: column; gap: 4px;">
+                        {move || {
+                            let eu = meu_id.get();
+                            mensagens.get().into_iter().map(|m| {
+                                let cor_nome = if m.autor_id == eu { "#ffeb3b" } else if m.autor_id % 2 == eu % 2 { "#90caf9" } else { "#ffcc80" };
+                                let canal = if m.apenas_time { "[dupla] " } else { "" };
+                                view! {
+                                    <div style="word-wrap: break-word;">
+                                        <span style="opacity: 0.5; font-size: 11px;">{formatar_hora(m.timestamp_ms)}" "</span>
+                                        <span style="opacity: 0.7; font-size: 11px;">{canal}</span>
+                                        <strong style=format!("color: {};", cor_nome)>{m.autor_nome}": "</strong>
+                                        <span>{m.texto}</span>
+                                    </div>
+                                }
+                            }).collect::<Vec<_>>()
+                        }}
+                    </div>
+
+                    <div style="padding: 8px; border-top: 1px solid #444; display: flex; flex-direction: column; gap: 6px;">
+                        <div style="display: flex; gap: 6px;">
+                            <input
+                                type="text"
+                                maxlength="300"
+                                prop:value=texto
+                                on:input=move |e| set_texto.set(event_target_value(&e))
+                                on:keydown=move |e| {
+                                    if e.key() == "Enter" {
+                                        enviar();
+                                    }
+                                }
+                                placeholder="Digite uma mensagem..."
+                                style="flex: 1; padding: 6px; border-radius: 4px; border: none;"
+                            />
+                            <button
+                                on:click=move |_| enviar()
+                                style="background: #2e7d32; color: white; border: none; border-radius: 4px; padding: 6px 10px; cursor: pointer;"
+                            >
+                                "Enviar"
+                            </button>
+                        </div>
+                        <label style="font-size: 11px; display: flex; align-items: center; gap: 4px; opacity: 0.8;">
+                            <input type="checkbox" prop:checked=apenas_time
+                                on:change=move |e| set_apenas_time.set(event_target_checked(&e)) />
+                            "Só para minha dupla"
+                        </label>
+                    </div>
+                </div>
+            </Show>
+        </div>
+    }
+}
@@ -0,0 +1,274 @@
+use crate::services::api;
+use crate::utils::helper::salvar_token_anfitriao;
+use buracao_core::lobby::{ConfigEspectadores, ConfigRelogio, NovaSala, ResumoSala, StatusSala};
+use buracao_core::regras::ConfigRegras;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+fn texto_status(status: StatusSala) -> &'static str {
+    match status {
+        StatusSala::AguardandoJogadores => "Aguardando",
+        StatusSala::EmAndamento => "Em jogo",
+        StatusSala::Encerrada => "Encerrada",
+    }
+}
+
+#[component]
+pub fn RoomBrowser(
+    // Devolve o código da sala escolhida para a tela de login
+    #[prop(into)] on_select: Callback<String>,
+) -> impl IntoView {
+    let (salas, set_salas) = signal(Vec::<ResumoSala>::new());
+    let (carregando, set_carregando) = signal(false);
+    let (erro, set_erro) = signal(Option::<String>::None);
+    let (selecionada, set_selecionada) = signal(Option::<ResumoSala>::None);
+
+    // --- FORMULÁRIO DE CRIAÇÃO ---
+    let (mostrar_criar, set_mostrar_criar) = signal(false);
+    let (novo_nome, set_novo_nome) = signal("".to_string());
+    let (nova_publica, set_nova_publica) = signal(true);
+    let (nova_senha, set_nova_senha) = signal("".to_string());
+    let (novos_pontos, set_novos_pontos) = signal(ConfigRegras::default().pontos_para_descer);
+    let (com_relogio, set_com_relogio) = signal(false);
+    let (espectadores, set_espectadores) = signal(ConfigEspectadores::default());
+    let (segundos_turno, set_segundos_turno) = signal(ConfigRelogio::default().segundos_por_turno);
+
+    let atualizar = move || {
+        set_carregando.set(true);
+        spawn_local(async move {
+            match api::listar_salas().await {
+                Ok(lista) => {
+                    set_salas.set(lista);
+                    set_erro.set(None);
+                }
+                Err(e) => set_erro.set(Some(e)),
+            }
+            set_carregando.set(false);
+        });
+    };
+
+    // Carrega a lista assim que a tela abre
+    atualizar();
+
+    let escolher = move |codigo: String| {
+        on_select.run(codigo.clone());
+        // Busca o resumo atualizado (assentos podem ter mudado desde a listagem)
+        spawn_local(async move {
+            match api::buscar_sala(&codigo).await {
+                Ok(resumo) => set_selecionada.set(Some(resumo)),
+                Err(e) => set_erro.set(Some(e)),
+            }
+        });
+    };
+
+    let criar = move |_| {
+        let nome = novo_nome.get();
+        if nome.trim().is_empty() {
+            set_erro.set(Some("Dê um nome para a sala.".to_string()));
+            return;
+        }
+
+        let senha = nova_senha.get();
+        let pontos = novos_pontos.get();
+        let pedido = NovaSala {
+            nome,
+            publica: nova_publica.get(),
+            senha: if senha.is_empty() { None } else { Some(senha) },
+            regras: Some(ConfigRegras {
+                pontos_para_descer: pontos,
+                pontos_para_descer_alto: pontos
+                    .max(ConfigRegras::default().pontos_para_descer_alto),
+                ..ConfigRegras::default()
+            }),
+            relogio: com_relogio.get().then(|| ConfigRelogio {
+                segundos_por_turno: segundos_turno.get(),
+                ..ConfigRelogio::default()
+            }),
+            espectadores: espectadores.get(),
+        };
+
+        spawn_local(async move {
+            match api::criar_sala(&pedido).await {
+                Ok(criada) => {
+                    // Quem cria a mesa vira o anfitrião quando entrar
+                    salvar_token_anfitriao(&criada.resumo.codigo, &criada.token_anfitriao);
+                    let resumo = criada.resumo;
+                    set_mostrar_criar.set(false);
+                    set_novo_nome.set("".to_string());
+                    set_nova_senha.set("".to_string());
+                    on_select.run(resumo.codigo.clone());
+                    set_selecionada.set(Some(resumo));
+                    atualizar();
+                }
+                Err(e) => set_erro.set(Some(e)),
+            }
+        });
+    };
+
+    let input_style = "width: 100%; padding: 8px; margin-top: 4px; border-radius: 5px; border: none; box-sizing: border-box;";
+
+    view! {
+        <div style="
+            background: rgba(0,0,0,0.5); padding: 20px; border-radius: 15px;
+            border: 2px solid #4caf50; width: 340px; display: flex; flex-direction: column; gap: 10px;
+            box-shadow: 0 10px 30px rgba(0,0,0,0.5); max-height: 80vh; overflow-y: auto;
+        ">
+            <div style="display: flex; justify-content: space-between; align-items: center;">
+                <h2 style="margin: 0; color: #ffeb3b; font-size: 1.2rem;">"Mesas Abertas"</h2>
+                <div style="display: flex; gap: 5px;">
+                    <button
+                        on:click=move |_| atualizar()
+                        title="Atualizar lista"
+                        style="background: #455a64; color: white; border: none; border-radius: 4px; padding: 5px 10px; cursor: pointer;"
+                    >
+                        {move || if carregando.get() { "..." } else { "↻" }}
+                    </button>
+                    <button
+                        on:click=move |_| set_mostrar_criar.update(|v| *v = !*v)
+                        style="background: #2e7d32; color: white; border: none; border-radius: 4px; padding: 5px 10px; cursor: pointer;"
+                    >
+                        "+ Nova"
+                    </button>
+                </div>
+            </div>
+
+            {move || erro.get().map(|e| view! {
+                <div style="background: rgba(183, 28, 28, 0.9); padding: 8px; border-radius: 6px; font-size: 13px;">{e}</div>
+            })}
+
+            // --- CRIAÇÃO DE SALA ---
+            <Show when=move || mostrar_criar.get() fallback=|| ()>
+                <div style="background: rgba(255,255,255,0.05); padding: 10px; border-radius: 8px; display: flex; flex-direction: column; gap: 8px; font-size: 13px;">
+                    <label>
+                        "Nome da mesa:"
+                        <input type="text" prop:value=novo_nome
+                            on:input=move |e| set_novo_nome.set(event_target_value(&e))
+                            style=input_style placeholder="Ex: Buraco de Sexta" />
+                    </label>
+                    <label>
+                        "Senha (opcional):"
+                        <input type="password" prop:value=nova_senha
+                            on:input=move |e| set_nova_senha.set(event_target_value(&e))
+                            style=input_style />
+                    </label>
+                    <label>
+                        "Pontos para descer:"
+                        <input type="number" min="0" step="10" prop:value=move || novos_pontos.get().to_string()
+                            on:input=move |e| {
+                                if let Ok(v) = event_target_value(&e).parse::<i32>() {
+                                    set_novos_pontos.set(v);
+                                }
+                            }
+                            style=input_style />
+                    </label>
+                    <label style="display: flex; align-items: center; gap: 6px;">
+                        <input type="checkbox" prop:checked=com_relogio
+                            on:change=move |e| set_com_relogio.set(event_target_checked(&e)) />
+                        "Limite de tempo por turno"
+                    </label>
+                    <Show when=move || com_relogio.get()>
+                        <label>
+                            "Segundos por turno (+ banco de tempo):"
+                            <input type="number" min="10" max="600" step="5" prop:value=move || segundos_turno.get().to_string()
+                                on:input=move |e| {
+                                    if let Ok(v) = event_target_value(&e).parse::<u32>() {
+                                        set_segundos_turno.set(v);
+                                    }
+                                }
+                                style=input_style />
+                        </label>
+                    </Show>
+                    <label style="display: flex; align-items: center; gap: 6px;">
+                        <input type="checkbox" prop:checked=move || espectadores.get().permitidos
+                            on:change=move |e| set_espectadores.update(|c| c.permitidos = event_target_checked(&e)) />
+                        "Aceitar espectadores"
+                    </label>
+                    <Show when=move || espectadores.get().permitidos>
+                        <label>
+                            "Atraso da transmissão (segundos):"
+                            <input type="number" min="0" max="600" step="5" prop:value=move || espectadores.get().atraso_segundos.to_string()
+                                on:input=move |e| {
+                                    if let Ok(v) = event_target_value(&e).parse::<u32>() {
+                                        set_espectadores.update(|c| c.atraso_segundos = v);
+                                    }
+                                }
+                                style=input_style />
+                        </label>
+                        <label style="display: flex; align-items: center; gap: 6px;">
+                            <input type="checkbox" prop:checked=move || espectadores.get().chat
+                                on:change=move |e| set_espectadores.update(|c| c.chat = event_target_checked(&e)) />
+                            "Espectadores podem usar o chat"
+                        </label>
+                        <label style="display: flex; align-items: center; gap: 6px;">
+                            <input type="checkbox" prop:checked=move || espectadores.get().treinador
+                                on:change=move |e| set_espectadores.update(|c| c.treinador = event_target_checked(&e)) />
+                            "Permitir treinador (vê a mão de um jogador)"
+                        </label>
+                    </Show>
+                    <label style="display: flex; align-items: center; gap: 6px;">
+                        <input type="checkbox" prop:checked=nova_publica
+                            on:change=move |e| set_nova_publica.set(event_target_checked(&e)) />
+                        "Mostrar na lista de mesas"
+                    </label>
+                    <button
+                        on:click=criar
+                        style="background: #ffeb3b; color: black; font-weight: bold; border: none; border-radius: 5px; padding: 8px; cursor: pointer;"
+                    >
+                        "CRIAR MESA"
+                    </button>
+                </div>
+            </Show>
+
+            // --- LISTA ---
+            <Show
+                when=move || !salas.get().is_empty()
+                fallback=|| view! { <div style="opacity: 0.7; font-size: 13px; text-align: center;">"Nenhuma mesa pública no momento."</div> }
+            >
+                <For
+                    each=move || salas.get()
+                    key=|sala| (sala.codigo.clone(), sala.assentos_ocupados(), sala.status)
+                    children=move |sala| {
+                        let codigo = sala.codigo.clone();
+                        let cheia = sala.status != StatusSala::AguardandoJogadores;
+                        view! {
+                            <div
+                                on:click=move |_| escolher(codigo.clone())
+                                style=format!("
+                                    display: flex; justify-content: space-between; align-items: center;
+                                    padding: 8px 10px; border-radius: 8px; cursor: pointer;
+                                    background: rgba(255,255,255,0.08); opacity: {};
+                                ", if cheia { 0.6 } else { 1.0 })
+                            >
+                                <div style="display: flex; flex-direction: column;">
+                                    <strong>{sala.nome.clone()} {if sala.tem_senha { " 🔒" } else { "" }}</strong>
+                                    <small style="opacity: 0.7;">
+                                        {format!("{} · {}", sala.codigo, texto_status(sala.status))}
+                                        {(sala.qtd_espectadores > 0).then(|| format!(" · 👀 {}", sala.qtd_espectadores))}
+                                    </small>
+                                </div>
+                                <span style="font-weight: bold; color: #ffeb3b;">
+                                    {format!("{}/{}", sala.assentos_ocupados(), sala.assentos.len())}
+                                </span>
+                            </div>
+                        }
+                    }
+                />
+            </Show>
+
+            // --- DETALHE DA SALA ESCOLHIDA ---
+            {move || selecionada.get().map(|sala| view! {
+                <div style="border-top: 1px solid rgba(255,255,255,0.2); padding-top: 8px; font-size: 13px;">
+                    <div style="margin-bottom: 4px;">{format!("Mesa selecionada: {} ({})", sala.nome, sala.codigo)}</div>
+                    {sala.assentos.into_iter().map(|a| {
+                        let time = if a.id % 2 == 0 { "A" } else { "B" };
+                        let texto = match a.nome {
+                            Some(nome) => format!("Lugar {} (Time {}): {}{}", a.id, time, nome, if a.conectado { "" } else { " (offline)" }),
+                            None => format!("Lugar {} (Time {}): livre", a.id, time),
+                        };
+                        view! { <div style="opacity: 0.85;">{texto}</div> }
+                    }).collect::<Vec<_>>()}
+                </div>
+            })}
+        </div>
+    }
+}
@@ -1,12 +1,19 @@
+use crate::components::lobby::RoomBrowser;
+use crate::components::ranking::Leaderboard;
+use buracao_core::acoes::ModoEspectador;
 use leptos::prelude::*; // Importante para eventos do DOM
 
 #[component]
 pub fn LoginScreen(
-    // Callback que devolve uma tupla (Nome, Sala) para o pai
-    #[prop(into)] on_enter: Callback<(String, String)>,
+    // Callback que devolve uma tupla (Nome, Sala, Senha, Modo de espectador) para o pai
+    #[prop(into)] on_enter: Callback<(String, String, String, Option<ModoEspectador>)>,
 ) -> impl IntoView {
     let (nome, set_nome) = signal("".to_string());
     let (sala, set_sala) = signal("SALA-1".to_string());
+    let (senha, set_senha) = signal("".to_string());
+    let (assistir, set_assistir) = signal(false);
+    // Assento cujo jogador o treinador quer acompanhar ("" = visão pública)
+    let (treinar, set_treinar) = signal("".to_string());
 
     let enviar = move |_| {
         let n = nome.get();
@@ -14,14 +21,18 @@ pub fn LoginScreen(
 
         if !n.trim().is_empty() && !s.trim().is_empty() {
             // AQUI ESTÁ A MÁGICA: Passa os dados para o pai (App)
-            on_enter.run((n, s));
+            let modo = assistir.get().then(|| match treinar.get().parse::<u32>() {
+                Ok(assento) => ModoEspectador::Treinador(assento),
+                Err(_) => ModoEspectador::Publico,
+            });
+            on_enter.run((n, s, senha.get(), modo));
         }
     };
 
     view! {
         <div style="
-            height: 100vh; display: flex; align-items: center; justify-content: center;
-            background: #1b5e20; font-family: sans-serif; color: white;
+            min-height: 100vh; display: flex; align-items: center; justify-content: center;
+            flex-wrap: wrap; gap: 30px; background: #1b5e20; font-family: sans-serif; color: white;
         ">
             <div style="
                 background: rgba(0,0,0,0.5); padding: 40px; border-radius: 15px;
@@ -52,6 +63,33 @@ pub fn LoginScreen(
                     />
                 </div>
 
+                <div>
+                    <label>"Senha (se a sala tiver) ou chave do torneio:"</label>
+                    <input
+                        type="password"
+                        prop:value=senha
+                        on:input=move |e| set_senha.set(event_target_value(&e))
+                        style="width: 100%; padding: 10px; margin-top: 5px; border-radius: 5px; border: none; box-sizing: border-box;"
+                    />
+                </div>
+
+                <label style="display: flex; align-items: center; gap: 6px;">
+                    <input type="checkbox" prop:checked=assistir
+                        on:change=move |e| set_assistir.set(event_target_checked(&e)) />
+                    "Só assistir"
+                </label>
+                <Show when=move || assistir.get()>
+                    <select
+                        on:change=move |e| set_treinar.set(event_target_value(&e))
+                        style="width: 100%; padding: 8px; border-radius: 5px; border: none;"
+                    >
+                        <option value="" selected=move || treinar.get().is_empty()>"Visão pública da mesa"</option>
+                        {(0..4).map(|assento| view! {
+                            <option value=assento.to_string()>{format!("Treinador do jogador {}", assento)}</option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </Show>
+
                 <button
                     on:click=enviar
                     style="
@@ -71,7 +109,15 @@ pub fn LoginScreen(
                 >
                     "ENTRAR"
                 </button>
+
+                <a href="/torneios" style="text-align: center; color: #ffeb3b; font-size: 13px;">"🏆 Torneios do clube"</a>
+                <a href="/replay" style="text-align: center; color: #ffeb3b; font-size: 13px;">"🎬 Rever partidas"</a>
             </div>
+
+            // Navegador de mesas: clicar em uma mesa preenche o código acima
+            <RoomBrowser on_select=Callback::new(move |codigo: String| set_sala.set(codigo)) />
+
+            <Leaderboard />
         </div>
     }
 }
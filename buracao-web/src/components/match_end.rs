@@ -0,0 +1,115 @@
+use buracao_core::acoes::{PlacarSerie, VotoFimDeJogo};
+use leptos::prelude::*;
+use std::collections::HashMap;
+
+/// O que o servidor mandou no FimDeJogo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResumoFim {
+    pub vencedor_time: u8,
+    pub pontos_a: i32,
+    pub pontos_b: i32,
+    pub motivo: String,
+    pub empate: bool,
+    pub serie: PlacarSerie,
+}
+
+fn texto_voto(voto: VotoFimDeJogo) -> &'static str {
+    match voto {
+        VotoFimDeJogo::Revanche => "Revanche",
+        VotoFimDeJogo::TrocarDuplas => "Trocar duplas",
+        VotoFimDeJogo::Sair => "Sair",
+    }
+}
+
+const OPCOES: [VotoFimDeJogo; 3] = [
+    VotoFimDeJogo::Revanche,
+    VotoFimDeJogo::TrocarDuplas,
+    VotoFimDeJogo::Sair,
+];
+
+// Resumo da partida encerrada com a votação do que fazer a seguir
+#[component]
+pub fn MatchEndModal(
+    #[prop(into)] fim: Signal<Option<ResumoFim>>,
+    #[prop(into)] votos: Signal<HashMap<u32, VotoFimDeJogo>>,
+    #[prop(into)] nomes: Signal<HashMap<u32, String>>,
+    #[prop(into)] meu_id: Signal<u32>,
+    // Espectadores veem o resumo, mas não votam
+    #[prop(into)] pode_votar: Signal<bool>,
+    #[prop(into)] on_votar: Callback<VotoFimDeJogo>,
+) -> impl IntoView {
+    view! {
+        {move || fim.get().map(|resumo| {
+            let titulo = if resumo.empate {
+                "Empate!".to_string()
+            } else {
+                format!("Vitória do Time {}!", if resumo.vencedor_time == 0 { "A" } else { "B" })
+            };
+            let serie = resumo.serie;
+            let texto_serie = format!(
+                "Série (melhor de {}): Time A {} x {} Time B{}",
+                serie.melhor_de,
+                serie.vitorias_a,
+                serie.vitorias_b,
+                if serie.decidida() { " — série decidida" } else { "" }
+            );
+
+            view! {
+                <div style="
+                    position: fixed; inset: 0; background: rgba(0,0,0,0.6); z-index: 3000;
+                    display: flex; align-items: center; justify-content: center;
+                ">
+                    <div style="
+                        background: #2e7d32; color: white; padding: 24px; border-radius: 12px;
+                        border: 2px solid #ffeb3b; width: 340px; display: flex; flex-direction: column; gap: 12px;
+                        box-shadow: 0 10px 30px rgba(0,0,0,0.5); font-family: sans-serif;
+                    ">
+                        <h2 style="margin: 0; color: #ffeb3b; text-align: center;">{titulo}</h2>
+                        <div style="text-align: center; opacity: 0.85;">{resumo.motivo.clone()}</div>
+                        <div style="display: flex; justify-content: space-around; font-size: 1.2rem; font-weight: bold;">
+                            <span>{format!("Time A: {}", resumo.pontos_a)}</span>
+                            <span>{format!("Time B: {}", resumo.pontos_b)}</span>
+                        </div>
+                        <div style="text-align: center; font-size: 0.9rem;">{texto_serie}</div>
+
+                        // Quem já votou em quê
+                        <div style="background: rgba(0,0,0,0.2); border-radius: 8px; padding: 8px; font-size: 0.85rem;">
+                            {move || {
+                                let todos_votos = votos.get();
+                                let todos_nomes = nomes.get();
+                                (0..4u32).map(|assento| {
+                                    let nome = todos_nomes.get(&assento).cloned().unwrap_or(format!("Assento {}", assento));
+                                    let voto = todos_votos.get(&assento).map(|v| texto_voto(*v)).unwrap_or("…");
+                                    view! { <div>{format!("{}: {}", nome, voto)}</div> }
+                                }).collect::<Vec<_>>()
+                            }}
+                        </div>
+
+                        <Show when=move || pode_votar.get()>
+                            <div style="display: flex; gap: 6px;">
+                                {OPCOES.into_iter().map(|opcao| view! {
+                                    <button
+                                        on:click=move |_| on_votar.run(opcao)
+                                        style=move || {
+                                            let escolhido = votos.get().get(&meu_id.get()) == Some(&opcao);
+                                            format!(
+                                                "flex: 1; padding: 8px; border-radius: 6px; cursor: pointer; font-weight: bold; border: 2px solid {}; background: {}; color: black;",
+                                                if escolhido { "white" } else { "transparent" },
+                                                if opcao == VotoFimDeJogo::Sair { "#ef9a9a" } else { "#ffeb3b" },
+                                            )
+                                        }
+                                    >
+                                        {texto_voto(opcao)}
+                                    </button>
+                                }).collect::<Vec<_>>()}
+                            </div>
+                            <small style="opacity: 0.7; text-align: center;">
+                                "A próxima partida começa quando os quatro escolherem a mesma opção."
+                            </small>
+                        </Show>
+                    </div>
+                </div>
+            }
+        })}
+    }
+}
@@ -1,11 +1,19 @@
 pub mod board;
 pub mod card;
+pub mod chat;
 pub mod controls;
 pub mod hand;
+pub mod lobby;
 pub mod login;
+pub mod match_end;
 pub mod notification;
 pub mod opponents;
+pub mod ranking;
+pub mod reactions;
+pub mod replay;
 pub mod scoreboard;
 pub mod settings;
 pub mod table;
+pub mod tournament;
+pub mod turn_clock;
 pub mod turn_indicator;
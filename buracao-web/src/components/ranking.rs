@@ -0,0 +1,119 @@
+use crate::services::api;
+use crate::utils::helper::{ler_id_perfil, ler_identidade, salvar_identidade};
+use buracao_core::lobby::EntradaRanking;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+#[component]
+pub fn Leaderboard() -> impl IntoView {
+    let (linhas, set_linhas) = signal(Vec::<EntradaRanking>::new());
+    let (carregando, set_carregando) = signal(false);
+    let (erro, set_erro) = signal(Option::<String>::None);
+    let meu_perfil = ler_id_perfil();
+
+    // Código de jogador: colar aqui o código de outro aparelho traz o perfil para este
+    let (mostrar_codigo, set_mostrar_codigo) = signal(false);
+    let (codigo, set_codigo) = signal(ler_identidade().unwrap_or_default());
+
+    let atualizar = move || {
+        set_carregando.set(true);
+        spawn_local(async move {
+            match api::ranking().await {
+                Ok(lista) => {
+                    set_linhas.set(lista);
+                    set_erro.set(None);
+                }
+                Err(e) => set_erro.set(Some(e)),
+            }
+            set_carregando.set(false);
+        });
+    };
+
+    atualizar();
+
+    let celula = "padding: 4px 6px; text-align: right;";
+
+    view! {
+        <div style="
+            background: rgba(0,0,0,0.5); padding: 20px; border-radius: 15px;
+            border: 2px solid #4caf50; width: 420px; display: flex; flex-direction: column; gap: 10px;
+            box-shadow: 0 10px 30px rgba(0,0,0,0.5); max-height: 80vh; overflow-y: auto;
+        ">
+            <div style="display: flex; justify-content: space-between; align-items: center;">
+                <h2 style="margin: 0; color: #ffeb3b; font-size: 1.2rem;">"🏆 Ranking"</h2>
+                <button
+                    on:click=move |_| atualizar()
+                    title="Atualizar ranking"
+                    style="background: #455a64; color: white; border: none; border-radius: 4px; padding: 5px 10px; cursor: pointer;"
+                >
+                    {move || if carregando.get() { "..." } else { "↻" }}
+                </button>
+            </div>
+
+            {move || erro.get().map(|e| view! {
+                <div style="background: rgba(183, 28, 28, 0.9); padding: 8px; border-radius: 6px; font-size: 13px;">{e}</div>
+            })}
+
+            <Show
+                when=move || !linhas.get().is_empty()
+                fallback=|| view! { <div style="opacity: 0.7; font-size: 13px; text-align: center;">"Ninguém terminou uma partida ainda."</div> }
+            >
+                <table style="width: 100%; border-collapse: collapse; font-size: 13px;">
+                    <thead>
+                        <tr style="opacity: 0.7;">
+                            <th style="padding: 4px 6px; text-align: left;">"#"</th>
+                            <th style="padding: 4px 6px; text-align: left;">"Jogador"</th>
+                            <th style=celula title="Rating (Elo por dupla)">"Rating"</th>
+                            <th style=celula title="Partidas / vitórias">"V/P"</th>
+                            <th style=celula title="Batidas">"Bat."</th>
+                            <th style=celula title="Canastras limpas do time">"Limpas"</th>
+                            <th style=celula title="Média de pontos do time por partida">"Média"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || linhas.get().into_iter().enumerate().map(|(posicao, linha)| {
+                            let minha = meu_perfil == Some(linha.id_perfil);
+                            view! {
+                                <tr style=if minha { "background: rgba(255,235,59,0.2); font-weight: bold;" } else { "" }>
+                                    <td style="padding: 4px 6px;">{posicao + 1}</td>
+                                    <td style="padding: 4px 6px;">{linha.nome}</td>
+                                    <td style=celula>{format!("{:.0}", linha.rating)}</td>
+                                    <td style=celula>{format!("{}/{}", linha.vitorias, linha.partidas)}</td>
+                                    <td style=celula>{linha.batidas}</td>
+                                    <td style=celula>{linha.canastras_limpas}</td>
+                                    <td style=celula>{format!("{:.0}", linha.media_pontos)}</td>
+                                </tr>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            </Show>
+
+            <div style="border-top: 1px solid rgba(255,255,255,0.2); padding-top: 8px; font-size: 13px;">
+                <button
+                    on:click=move |_| set_mostrar_codigo.update(|v| *v = !*v)
+                    style="background: none; color: #ffeb3b; border: none; padding: 0; cursor: pointer; text-decoration: underline;"
+                >
+                    "Código de jogador"
+                </button>
+                <Show when=move || mostrar_codigo.get()>
+                    <div style="display: flex; flex-direction: column; gap: 6px; margin-top: 6px;">
+                        <small style="opacity: 0.8;">
+                            "Guarde este código para manter seu rating em outro aparelho. Cole aqui o código de lá para usar o mesmo perfil."
+                        </small>
+                        <input type="text" prop:value=codigo
+                            on:input=move |e| set_codigo.set(event_target_value(&e))
+                            placeholder="Criado no primeiro jogo"
+                            style="width: 100%; padding: 8px; border-radius: 5px; border: none; box-sizing: border-box; font-family: monospace;" />
+                        <button
+                            on:click=move |_| salvar_identidade(&codigo.get())
+                            style="background: #2e7d32; color: white; border: none; border-radius: 4px; padding: 5px 10px; cursor: pointer;"
+                        >
+                            "Usar este código"
+                        </button>
+                    </div>
+                </Show>
+            </div>
+        </div>
+    }
+}
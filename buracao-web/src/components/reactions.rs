@@ -0,0 +1,51 @@
+use buracao_core::acoes::Reacao;
+use leptos::prelude::*;
+
+#[component]
+pub fn ReactionBar(#[prop(into)] on_reagir: Callback<Reacao>) -> impl IntoView {
+    view! {
+        <div style="
+            position: fixed; bottom: 70px; left: 20px; z-index: 1500;
+            display: flex; gap: 4px; background: rgba(0,0,0,0.5);
+            padding: 4px; border-radius: 20px;
+        ">
+            {Reacao::TODAS.into_iter().map(|reacao| view! {
+                <button
+                    on:click=move |_| on_reagir.run(reacao)
+                    title=reacao.texto()
+                    style="background: rgba(255,255,255,0.1); color: white; border: none; border-radius: 14px; padding: 4px 8px; cursor: pointer; font-size: 12px;"
+                >
+                    {reacao.texto()}
+                </button>
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
+
+// Balão que aparece sobre o assento de quem reagiu
+#[component]
+pub fn ReactionBubble(#[prop(into)] reacao: Signal<Option<Reacao>>) -> impl IntoView {
+    view! {
+        <style>
+            "
+            @keyframes popReacao {
+                0% { opacity: 0; transform: translate(-50%, 10px) scale(0.6); }
+                15% { opacity: 1; transform: translate(-50%, 0) scale(1.1); }
+                25% { transform: translate(-50%, 0) scale(1.0); }
+                85% { opacity: 1; }
+                100% { opacity: 0; }
+            }
+            "
+        </style>
+        {move || reacao.get().map(|r| view! {
+            <div style="
+                position: absolute; bottom: 48px; left: 50%;
+                background: white; color: black; padding: 4px 10px; border-radius: 12px;
+                font-size: 13px; font-weight: bold; white-space: nowrap; pointer-events: none;
+                box-shadow: 0 2px 6px rgba(0,0,0,0.4); animation: popReacao 3s forwards; z-index: 5;
+            ">
+                {r.texto()}
+            </div>
+        })}
+    }
+}
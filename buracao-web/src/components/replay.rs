@@ -0,0 +1,581 @@
+use crate::components::board::Board;
+use crate::components::card::Card;
+use crate::components::table::Table;
+use crate::services::api;
+use crate::utils::mappers::{carta_para_asset, verso_para_asset};
+use buracao_core::acoes::{AcaoJogador, DetalheJogo};
+use buracao_core::baralho::Carta;
+use buracao_core::estado::{ComposicaoPlacar, EstadoJogo};
+use buracao_core::registro::{Lance, RegistroPartida, ResumoRegistro};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use std::collections::HashMap;
+use std::time::Duration;
+
+// --- REPLAY DE PARTIDAS (/replay, /replay/{id}) ---
+// Refaz no navegador uma partida do arquivo (ver docs/registro-de-partida.md) e deixa
+// andar lance a lance. Dá para ver as quatro mãos abertas ou só o que um assento via.
+
+const TEMA: &str = "/assets/cards/PaperCards";
+const PAINEL: &str = "
+    background: rgba(0,0,0,0.5); padding: 20px; border-radius: 15px;
+    border: 2px solid #4caf50; display: flex; flex-direction: column; gap: 10px;
+    box-shadow: 0 10px 30px rgba(0,0,0,0.5);
+";
+const BOTAO: &str = "background: #2e7d32; color: white; border: none; border-radius: 4px; padding: 6px 12px; cursor: pointer; font-size: 16px;";
+const SELECT: &str = "padding: 6px; border-radius: 5px; border: none;";
+
+/// O que mudou no passo atual, para destacar na mesa.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Destaque {
+    texto: String,
+    jogador: Option<u32>,
+    jogos_a: Vec<u32>,
+    jogos_b: Vec<u32>,
+    monte: bool,
+    lixo: bool,
+    /// Cartas que entraram na mão de quem jogou
+    cartas_novas: Vec<Carta>,
+}
+
+fn jogos_alterados(
+    antes: &HashMap<u32, Vec<Carta>>,
+    depois: &HashMap<u32, Vec<Carta>>,
+) -> Vec<u32> {
+    depois
+        .iter()
+        .filter(|(id, cartas)| antes.get(id) != Some(cartas))
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+fn cartas_novas(antes: &[Carta], depois: &[Carta]) -> Vec<Carta> {
+    let mut restantes = antes.to_vec();
+    depois
+        .iter()
+        .filter(|c| match restantes.iter().position(|r| r == *c) {
+            Some(i) => {
+                restantes.remove(i);
+                false
+            }
+            None => true,
+        })
+        .cloned()
+        .collect()
+}
+
+fn descrever(lance: &Lance, antes: &EstadoJogo, depois: &EstadoJogo, nome: &str) -> String {
+    let texto = match lance {
+        Lance::Acao { acao, .. } => match acao {
+            AcaoJogador::ComprarBaralho => format!("{} comprou do monte", nome),
+            AcaoJogador::ComprarLixo { .. } => {
+                format!("{} pegou o lixo ({} cartas)", nome, antes.lixo.len())
+            }
+            AcaoJogador::BaixarJogos { jogos } => {
+                let texto_jogos: Vec<String> = jogos
+                    .iter()
+                    .map(|j| {
+                        j.iter()
+                            .map(|c| c.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .collect();
+                format!("{} baixou: {}", nome, texto_jogos.join(" | "))
+            }
+            AcaoJogador::Ajuntar { cartas, .. } => {
+                let texto_cartas: Vec<String> = cartas.iter().map(|c| c.to_string()).collect();
+                format!("{} ajuntou {}", nome, texto_cartas.join(" "))
+            }
+            AcaoJogador::Descartar { carta } => format!("{} descartou {}", nome, carta),
+        },
+        Lance::MonteVazio { .. } => format!(
+            "{} foi comprar e o monte tinha acabado: última chance de pegar o lixo",
+            nome
+        ),
+        Lance::Esgotamento { .. } => format!("O monte acabou na vez de {}", nome),
+    };
+    if depois.partida_encerrada && depois.bateu.is_some() {
+        format!("{} e bateu!", texto)
+    } else {
+        texto
+    }
+}
+
+fn destacar(
+    registro: &RegistroPartida,
+    estados: &[EstadoJogo],
+    mao: usize,
+    passo: usize,
+) -> Destaque {
+    let lance = passo
+        .checked_sub(1)
+        .and_then(|i| registro.maos.get(mao).and_then(|m| m.lances.get(i)));
+    let (Some(lance), Some(antes), Some(depois)) = (
+        lance,
+        passo.checked_sub(1).and_then(|i| estados.get(i)),
+        estados.get(passo),
+    ) else {
+        return Destaque {
+            texto: "Cartas dadas".to_string(),
+            ..Default::default()
+        };
+    };
+    let jogador = match lance {
+        Lance::Acao { jogador, .. }
+        | Lance::MonteVazio { jogador }
+        | Lance::Esgotamento { jogador } => *jogador,
+    };
+    let acao = match lance {
+        Lance::Acao { acao, .. } => Some(acao),
+        Lance::MonteVazio { .. } | Lance::Esgotamento { .. } => None,
+    };
+    let indice = jogador as usize;
+    Destaque {
+        texto: descrever(lance, antes, depois, registro.nome(jogador)),
+        jogador: Some(jogador),
+        jogos_a: jogos_alterados(&antes.jogos_time_a, &depois.jogos_time_a),
+        jogos_b: jogos_alterados(&antes.jogos_time_b, &depois.jogos_time_b),
+        monte: matches!(acao, Some(AcaoJogador::ComprarBaralho)),
+        lixo: matches!(acao, Some(AcaoJogador::Descartar { .. })),
+        cartas_novas: match (antes.maos.get(indice), depois.maos.get(indice)) {
+            (Some(a), Some(d)) => cartas_novas(a, d),
+            _ => Vec::new(),
+        },
+    }
+}
+
+/// Lê algo do estado do passo atual (valor padrão enquanto nada foi carregado).
+fn ler<T: Default>(
+    estados: RwSignal<Vec<EstadoJogo>>,
+    passo: RwSignal<usize>,
+    f: impl Fn(&EstadoJogo) -> T,
+) -> T {
+    estados.with(|e| e.get(passo.get()).map(f).unwrap_or_default())
+}
+
+fn tabela_placar(a: ComposicaoPlacar, b: ComposicaoPlacar) -> impl IntoView {
+    let linhas = [
+        ("Cartas na mesa", a.cartas_mesa, b.cartas_mesa),
+        ("Canastras limpas", a.canastras_limpas, b.canastras_limpas),
+        ("Canastras sujas", a.canastras_sujas, b.canastras_sujas),
+        ("Três vermelhos", a.tres_vermelhos, b.tres_vermelhos),
+        ("Batida", a.batida, b.batida),
+        ("Cartas na mão", a.cartas_na_mao, b.cartas_na_mao),
+    ];
+    let celula = "padding: 4px 10px; text-align: right;";
+    view! {
+        <table style="border-collapse: collapse; font-size: 14px;">
+            <tr style="color: #ffeb3b;">
+                <th></th>
+                <th style=celula>"Time A"</th>
+                <th style=celula>"Time B"</th>
+            </tr>
+            {linhas.into_iter().map(|(item, pa, pb)| view! {
+                <tr>
+                    <td style="padding: 4px 10px;">{item}</td>
+                    <td style=celula>{pa}</td>
+                    <td style=celula>{pb}</td>
+                </tr>
+            }).collect::<Vec<_>>()}
+            <tr style="font-weight: bold; border-top: 1px solid rgba(255,255,255,0.3);">
+                <td style="padding: 4px 10px;">"Total"</td>
+                <td style=celula>{a.total()}</td>
+                <td style=celula>{b.total()}</td>
+            </tr>
+        </table>
+    }
+}
+
+#[component]
+pub fn ReplayPage() -> impl IntoView {
+    let (partidas, set_partidas) = signal(Vec::<ResumoRegistro>::new());
+    let (erro, set_erro) = signal(Option::<String>::None);
+    let (aberta, set_aberta) = signal(Option::<i64>::None);
+    let registro = RwSignal::new(Option::<RegistroPartida>::None);
+    // Estados da mão aberta: a distribuição e um depois de cada lance
+    let estados = RwSignal::new(Vec::<EstadoJogo>::new());
+    let mao = RwSignal::new(0_usize);
+    let passo = RwSignal::new(0_usize);
+    // None = as quatro mãos abertas; Some(assento) = só o que aquele assento via
+    let visao = RwSignal::new(Option::<u32>::None);
+    let tocando = RwSignal::new(false);
+
+    let ultimo_passo = move || estados.with(|e| e.len().saturating_sub(1));
+
+    let abrir_mao = move |indice: usize| {
+        let refeita = registro.with_untracked(|r| {
+            r.as_ref()
+                .and_then(|r| r.maos.get(indice).map(|m| m.reproduzir(&r.regras)))
+        });
+        match refeita {
+            Some(Ok(lista)) => {
+                estados.set(lista);
+                set_erro.set(None);
+            }
+            Some(Err(e)) => {
+                estados.set(Vec::new());
+                set_erro.set(Some(e));
+            }
+            None => estados.set(Vec::new()),
+        }
+        mao.set(indice);
+        passo.set(0);
+        tocando.set(false);
+    };
+
+    let abrir_partida = move |id: i64| {
+        spawn_local(async move {
+            match api::baixar_partida(id).await {
+                Ok(r) => {
+                    registro.set(Some(r));
+                    set_aberta.set(Some(id));
+                    abrir_mao(0);
+                }
+                Err(e) => set_erro.set(Some(e)),
+            }
+        });
+    };
+
+    spawn_local(async move {
+        match api::listar_partidas().await {
+            Ok(lista) => set_partidas.set(lista),
+            Err(e) => set_erro.set(Some(e)),
+        }
+    });
+
+    // /replay/{id} abre a partida direto
+    let caminho = web_sys::window()
+        .and_then(|w| w.location().pathname().ok())
+        .unwrap_or_default();
+    if let Ok(id) = caminho
+        .trim_start_matches("/replay")
+        .trim_matches('/')
+        .parse::<i64>()
+    {
+        abrir_partida(id);
+    }
+
+    // Reprodução automática
+    if let Ok(handle) = set_interval_with_handle(
+        move || {
+            if !tocando.get_untracked() {
+                return;
+            }
+            if passo.get_untracked() < ultimo_passo() {
+                passo.update(|p| *p += 1);
+            } else {
+                tocando.set(false);
+            }
+        },
+        Duration::from_millis(800),
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+
+    let destaque = Memo::new(move |_| {
+        registro.with(|r| {
+            r.as_ref()
+                .map(|r| estados.with(|e| destacar(r, e, mao.get(), passo.get())))
+                .unwrap_or_default()
+        })
+    });
+
+    let nome = move |assento: u32| {
+        registro.with(|r| {
+            r.as_ref()
+                .map(|r| r.nome(assento).to_string())
+                .unwrap_or_default()
+        })
+    };
+
+    // --- MESA NO PASSO ATUAL ---
+    let mesa = |jogos: &HashMap<u32, Vec<Carta>>| {
+        let mut lista: Vec<DetalheJogo> = jogos
+            .iter()
+            .map(|(id, cartas)| DetalheJogo {
+                id: *id,
+                cartas: cartas.clone(),
+            })
+            .collect();
+        lista.sort_by_key(|j| j.id);
+        lista
+    };
+    let mesa_a = Signal::derive(move || ler(estados, passo, |e| mesa(&e.jogos_time_a)));
+    let mesa_b = Signal::derive(move || ler(estados, passo, |e| mesa(&e.jogos_time_b)));
+    let tres_a = Signal::derive(move || ler(estados, passo, |e| e.tres_vermelhos_time_a.clone()));
+    let tres_b = Signal::derive(move || ler(estados, passo, |e| e.tres_vermelhos_time_b.clone()));
+    let lixo = Signal::derive(move || ler(estados, passo, |e| e.lixo.last().cloned()));
+    let qtd_lixo = Signal::derive(move || ler(estados, passo, |e| e.lixo.len() as u32));
+    let qtd_monte = Signal::derive(move || ler(estados, passo, |e| e.baralho.restantes() as u32));
+    let verso_monte = Signal::derive(move || {
+        verso_para_asset(ler(estados, passo, |e| {
+            e.baralho.cartas.last().map(|c| c.verso)
+        }))
+    });
+    let destaques_a = Signal::derive(move || destaque.get().jogos_a);
+    let destaques_b = Signal::derive(move || destaque.get().jogos_b);
+    let monte_destacado = Signal::derive(move || destaque.get().monte);
+    let lixo_destacado = Signal::derive(move || destaque.get().lixo);
+    let encerrada = move || ler(estados, passo, |e| e.partida_encerrada);
+
+    // Mão de um assento: aberta ou só a quantidade de cartas
+    let linha_mao = move |assento: u32, revelada: bool| {
+        let mut cartas = ler(estados, passo, |e| {
+            e.maos.get(assento as usize).cloned().unwrap_or_default()
+        });
+        cartas.sort();
+        let info = destaque.get();
+        let vez = ler(estados, passo, |e| {
+            e.turno_atual == assento && !e.partida_encerrada
+        });
+        let jogou = info.jogador == Some(assento);
+        let mut novas = if jogou { info.cartas_novas } else { Vec::new() };
+        let time = if assento.is_multiple_of(2) { "A" } else { "B" };
+        let titulo = format!(
+            "{}{} — Time {} ({} cartas)",
+            if vez { "▶ " } else { "" },
+            nome(assento),
+            time,
+            cartas.len()
+        );
+        let borda = if jogou {
+            "2px solid #00e5ff"
+        } else {
+            "1px solid rgba(255,255,255,0.1)"
+        };
+        let conteudo = if revelada {
+            cartas
+                .into_iter()
+                .map(|c| {
+                    let nova = match novas.iter().position(|n| *n == c) {
+                        Some(i) => {
+                            novas.remove(i);
+                            true
+                        }
+                        None => false,
+                    };
+                    view! {
+                        <div style="margin-right: -28px;">
+                            <Card
+                                id=carta_para_asset(&c)
+                                width="55px".to_string()
+                                theme=TEMA.to_string()
+                                selection_group=Signal::derive(move || if nova { Some(1) } else { None })
+                            />
+                        </div>
+                    }
+                })
+                .collect::<Vec<_>>()
+                .into_any()
+        } else {
+            view! { <span style="opacity: 0.6; font-size: 13px;">"Cartas escondidas"</span> }
+                .into_any()
+        };
+        view! {
+            <div style=format!("background: rgba(0,0,0,0.25); border: {}; border-radius: 10px; padding: 8px 10px;", borda)>
+                <div style="font-size: 13px; margin-bottom: 6px;">{titulo}</div>
+                <div style="display: flex; flex-wrap: wrap; padding-top: 20px; padding-right: 28px;">{conteudo}</div>
+            </div>
+        }
+    };
+
+    view! {
+        <div style="
+            min-height: 100vh; display: flex; align-items: flex-start; justify-content: center;
+            flex-wrap: wrap; gap: 30px; padding: 30px; box-sizing: border-box;
+            background: #1b5e20; font-family: sans-serif; color: white;
+        ">
+            // --- LISTA DE PARTIDAS ---
+            <div style=format!("{} width: 260px;", PAINEL)>
+                <div style="display: flex; justify-content: space-between; align-items: center;">
+                    <h2 style="margin: 0; color: #ffeb3b; font-size: 1.2rem;">"🎬 Replays"</h2>
+                    <a href="/" style="color: #ffeb3b; font-size: 13px;">"← Voltar às mesas"</a>
+                </div>
+                {move || {
+                    let lista = partidas.get();
+                    if lista.is_empty() {
+                        return view! { <div style="opacity: 0.7; font-size: 13px;">"Nenhuma partida arquivada."</div> }.into_any();
+                    }
+                    lista.into_iter().map(|p| {
+                        let id = p.id;
+                        view! {
+                            <div
+                                on:click=move |_| abrir_partida(id)
+                                style=move || format!(
+                                    "padding: 8px 10px; border-radius: 8px; cursor: pointer; background: {};",
+                                    if aberta.get() == Some(id) { "rgba(255,235,59,0.25)" } else { "rgba(255,255,255,0.08)" }
+                                )
+                            >
+                                <div style="font-weight: bold;">{format!("#{} {}", p.id, p.sala)}</div>
+                                <div style="font-size: 12px; opacity: 0.8;">{p.jogadores.join(", ")}</div>
+                                <div style="font-size: 12px;">
+                                    {format!("Time A {} x {} Time B{}", p.pontos_a, p.pontos_b, if p.importada { " (importada)" } else { "" })}
+                                </div>
+                            </div>
+                        }
+                    }).collect::<Vec<_>>().into_any()
+                }}
+            </div>
+
+            // --- VISUALIZADOR ---
+            <div style=format!("{} flex: 1; min-width: 700px; max-width: 1300px;", PAINEL)>
+                {move || erro.get().map(|e| view! {
+                    <div style="background: rgba(183, 28, 28, 0.9); padding: 8px; border-radius: 6px; font-size: 13px;">{e}</div>
+                })}
+
+                <Show
+                    when=move || registro.with(|r| r.is_some()) && !estados.with(|e| e.is_empty())
+                    fallback=|| view! { <div style="opacity: 0.7;">"Escolha uma partida para rever."</div> }
+                >
+                    // Cabeçalho: duplas, mão e visão
+                    <div style="display: flex; flex-wrap: wrap; gap: 12px; align-items: center; justify-content: space-between;">
+                        <div>
+                            <div style="font-weight: bold; color: #ffeb3b;">
+                                {move || registro.with(|r| r.as_ref().map(|r| r.sala.clone()).unwrap_or_default())}
+                            </div>
+                            <div style="font-size: 13px;">
+                                {move || format!("Time A: {} & {} — Time B: {} & {}", nome(0), nome(2), nome(1), nome(3))}
+                            </div>
+                        </div>
+                        <div style="display: flex; gap: 8px; align-items: center;">
+                            <Show when=move || registro.with(|r| r.as_ref().map(|r| r.maos.len() > 1).unwrap_or(false))>
+                                <select
+                                    style=SELECT
+                                    on:change=move |ev| {
+                                        if let Ok(i) = event_target_value(&ev).parse::<usize>() {
+                                            abrir_mao(i);
+                                        }
+                                    }
+                                >
+                                    {move || {
+                                        let total = registro.with(|r| r.as_ref().map(|r| r.maos.len()).unwrap_or(0));
+                                        (0..total).map(|i| view! {
+                                            <option value=i.to_string() selected=move || mao.get() == i>{format!("Mão {}", i + 1)}</option>
+                                        }).collect::<Vec<_>>()
+                                    }}
+                                </select>
+                            </Show>
+                            <select
+                                style=SELECT
+                                on:change=move |ev| visao.set(event_target_value(&ev).parse::<u32>().ok())
+                            >
+                                <option value="todos" selected=move || visao.get().is_none()>"Todas as mãos abertas"</option>
+                                {move || (0..4u32).map(|assento| view! {
+                                    <option value=assento.to_string() selected=move || visao.get() == Some(assento)>
+                                        {format!("Visão de {}", nome(assento))}
+                                    </option>
+                                }).collect::<Vec<_>>()}
+                            </select>
+                        </div>
+                    </div>
+
+                    // Controles
+                    <div style="display: flex; gap: 8px; align-items: center;">
+                        <button style=BOTAO title="Início" on:click=move |_| { tocando.set(false); passo.set(0); }>"⏮"</button>
+                        <button style=BOTAO title="Lance anterior" on:click=move |_| { tocando.set(false); passo.update(|p| *p = p.saturating_sub(1)); }>"◀"</button>
+                        <button style=BOTAO title="Tocar / pausar" on:click=move |_| {
+                            if passo.get_untracked() >= ultimo_passo() {
+                                passo.set(0);
+                            }
+                            tocando.update(|t| *t = !*t);
+                        }>
+                            {move || if tocando.get() { "⏸" } else { "⏵" }}
+                        </button>
+                        <button style=BOTAO title="Próximo lance" on:click=move |_| { tocando.set(false); passo.update(|p| *p = (*p + 1).min(ultimo_passo())); }>"▶"</button>
+                        <button style=BOTAO title="Fim da mão" on:click=move |_| { tocando.set(false); passo.set(ultimo_passo()); }>"⏭"</button>
+                        <input
+                            type="range"
+                            min="0"
+                            max=move || ultimo_passo().to_string()
+                            prop:value=move || passo.get().to_string()
+                            on:input=move |ev| {
+                                tocando.set(false);
+                                if let Ok(p) = event_target_value(&ev).parse::<usize>() {
+                                    passo.set(p.min(ultimo_passo()));
+                                }
+                            }
+                            style="flex: 1;"
+                        />
+                        <span style="font-size: 13px; min-width: 90px; text-align: right;">
+                            {move || format!("Lance {}/{}", passo.get(), ultimo_passo())}
+                        </span>
+                    </div>
+
+                    <div style="background: rgba(0,0,0,0.3); border-radius: 8px; padding: 8px 12px; font-size: 15px;">
+                        {move || destaque.get().texto}
+                    </div>
+
+                    // Mesa
+                    <div style="display: flex; gap: 15px; align-items: flex-start;">
+                        <Table
+                            titulo="Jogos do Time A".to_string()
+                            jogos=mesa_a
+                            tres_vermelhos=tres_a
+                            theme=TEMA.to_string()
+                            card_width="60px".to_string()
+                            destaques=destaques_a
+                        />
+                        <div style="display: flex; flex-direction: column; gap: 10px; align-items: center;">
+                            <Board
+                                lixo=lixo
+                                lixo_selecionado=lixo_destacado
+                                monte_destacado=monte_destacado
+                                theme=TEMA.to_string()
+                                card_width="70px".to_string()
+                                qtd_monte=qtd_monte
+                                qtd_lixo=qtd_lixo
+                                verso_monte=verso_monte
+                            />
+                            <div style="font-size: 14px; font-weight: bold;">
+                                {move || {
+                                    let (a, b) = ler(estados, passo, |e| (e.pontuacao_a, e.pontuacao_b));
+                                    format!("Time A {} x {} Time B", a, b)
+                                }}
+                            </div>
+                        </div>
+                        <Table
+                            titulo="Jogos do Time B".to_string()
+                            jogos=mesa_b
+                            tres_vermelhos=tres_b
+                            theme=TEMA.to_string()
+                            card_width="60px".to_string()
+                            destaques=destaques_b
+                        />
+                    </div>
+
+                    // Mãos
+                    <div style="display: grid; grid-template-columns: 1fr 1fr; gap: 10px;">
+                        {move || (0..4u32).map(|assento| {
+                            let revelada = match visao.get() {
+                                None => true,
+                                Some(observador) => observador == assento,
+                            };
+                            linha_mao(assento, revelada)
+                        }).collect::<Vec<_>>()}
+                    </div>
+
+                    // Placar da mão, item por item
+                    <Show when=encerrada>
+                        <div style="background: rgba(0,0,0,0.3); border-radius: 8px; padding: 10px 12px; display: flex; flex-direction: column; gap: 8px; align-items: center;">
+                            <div style="color: #ffeb3b; font-weight: bold;">
+                                {move || ler(estados, passo, |e| format!("Fim da mão: {}", e.motivo_fim()))}
+                            </div>
+                            {move || {
+                                let (a, b) = ler(estados, passo, |e| (e.composicao_placar(true), e.composicao_placar(false)));
+                                tabela_placar(a, b)
+                            }}
+                        </div>
+                    </Show>
+
+                    <a
+                        href=move || aberta.get().map(|id| format!("/api/partidas/{}", id)).unwrap_or_default()
+                        style="color: #ffeb3b; font-size: 13px;"
+                    >
+                        "⬇ Baixar registro da partida"
+                    </a>
+                </Show>
+            </div>
+        </div>
+    }
+}
@@ -1,4 +1,8 @@
+use buracao_core::acoes::ComandoSala;
 use leptos::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+const BOTAO_PEQUENO: &str = "border: none; border-radius: 4px; padding: 3px 6px; cursor: pointer; font-size: 11px; color: white;";
 
 #[component]
 pub fn SettingsModal(
@@ -9,7 +13,21 @@ pub fn SettingsModal(
     current_theme_path: RwSignal<String>,
     card_scale: RwSignal<f32>,
     volume: RwSignal<f64>,
+
+    // Moderação (ações vão direto para o servidor, sem passar pelo "Salvar")
+    #[prop(into)] meu_id: Signal<u32>,
+    #[prop(into)] host_id: Signal<Option<u32>>,
+    #[prop(into)] sala_trancada: Signal<bool>,
+    #[prop(into)] votos_expulsao: Signal<HashMap<u32, usize>>,
+    #[prop(into)] nomes: Signal<HashMap<u32, String>>,
+    #[prop(into)] on_comando: Callback<ComandoSala>,
+
+    // Reações: quem está silenciado e se as reações tocam som
+    silenciados: RwSignal<HashSet<u32>>,
+    som_reacoes: RwSignal<bool>,
 ) -> impl IntoView {
+    let sou_host = move || host_id.get() == Some(meu_id.get());
+
     // --- 1. ESTADOS LOCAIS (RASCUNHO) ---
     let (draft_theme, set_draft_theme) = signal(current_theme_path.get_untracked());
     let (draft_scale, set_draft_scale) = signal(card_scale.get_untracked());
@@ -113,6 +131,100 @@ pub fn SettingsModal(
                         />
                     </div>
 
+                    // --- MESA (Moderação) ---
+                    <div style="margin-bottom: 25px; border-top: 1px solid #444; padding-top: 15px;">
+                        <div style="display: flex; justify-content: space-between; align-items: center; margin-bottom: 8px;">
+                            <label style="font-size: 13px; color: #aaa;">
+                                {move || if sou_host() { "Mesa (você é o anfitrião)" } else { "Mesa" }}
+                            </label>
+                            <Show when=sou_host fallback=|| ()>
+                                <button
+                                    on:click=move |_| on_comando.run(ComandoSala::TrancarSala { trancada: !sala_trancada.get() })
+                                    style=format!("{} background: #455a64;", BOTAO_PEQUENO)
+                                >
+                                    {move || if sala_trancada.get() { "🔒 Destrancar" } else { "🔓 Trancar" }}
+                                </button>
+                            </Show>
+                        </div>
+
+                        <label style="display: flex; align-items: center; gap: 6px; font-size: 12px; margin-bottom: 8px;">
+                            <input type="checkbox" prop:checked=move || som_reacoes.get()
+                                on:change=move |e| som_reacoes.set(event_target_checked(&e)) />
+                            "Som nas reações"
+                        </label>
+
+                        {move || {
+                            let eu = meu_id.get();
+                            let host = host_id.get();
+                            let votos = votos_expulsao.get();
+                            let mudos = silenciados.get();
+                            let mut jogadores: Vec<(u32, String)> = nomes.get().into_iter().collect();
+                            jogadores.sort_by_key(|(id, _)| *id);
+
+                            jogadores.into_iter().map(|(id, nome)| {
+                                let coroa = if host == Some(id) { " 👑" } else { "" };
+                                let qtd_votos = votos.get(&id).copied().unwrap_or(0);
+                                let rotulo = if qtd_votos > 0 {
+                                    format!("{}{} ({} voto(s))", nome, coroa, qtd_votos)
+                                } else {
+                                    format!("{}{}", nome, coroa)
+                                };
+
+                                let botoes = if id == eu {
+                                    view! { <span style="font-size: 11px; color: #888;">"você"</span> }.into_any()
+                                } else if host == Some(eu) {
+                                    view! {
+                                        <div style="display: flex; gap: 4px;">
+                                            <button title="Passar o controle da sala"
+                                                on:click=move |_| on_comando.run(ComandoSala::TransferirHost { jogador: id })
+                                                style=format!("{} background: #1565c0;", BOTAO_PEQUENO)>"👑"</button>
+                                            <button title="Liberar o assento para outra pessoa"
+                                                on:click=move |_| on_comando.run(ComandoSala::LiberarAssento { jogador: id, robo: false })
+                                                style=format!("{} background: #6d4c41;", BOTAO_PEQUENO)>"Liberar"</button>
+                                            <button title="Liberar o assento e deixar o robô jogando por ele até alguém sentar"
+                                                on:click=move |_| on_comando.run(ComandoSala::LiberarAssento { jogador: id, robo: true })
+                                                style=format!("{} background: #5d4037;", BOTAO_PEQUENO)>"🤖"</button>
+                                            <button title="Expulsar da sala"
+                                                on:click=move |_| on_comando.run(ComandoSala::Expulsar { jogador: id })
+                                                style=format!("{} background: #d32f2f;", BOTAO_PEQUENO)>"Expulsar"</button>
+                                        </div>
+                                    }.into_any()
+                                } else {
+                                    view! {
+                                        <button title="Votar para expulsar"
+                                            on:click=move |_| on_comando.run(ComandoSala::VotarExpulsao { jogador: id })
+                                            style=format!("{} background: #d32f2f;", BOTAO_PEQUENO)>"Votar expulsão"</button>
+                                    }.into_any()
+                                };
+
+                                let silenciar = (id != eu).then(|| {
+                                    let mudo = mudos.contains(&id);
+                                    view! {
+                                        <button title=if mudo { "Voltar a ver as reações" } else { "Esconder as reações deste jogador" }
+                                            on:click=move |_| silenciados.update(|s| {
+                                                if !s.remove(&id) {
+                                                    s.insert(id);
+                                                }
+                                            })
+                                            style=format!("{} background: #455a64;", BOTAO_PEQUENO)>
+                                            {if mudo { "🔇" } else { "🔈" }}
+                                        </button>
+                                    }
+                                });
+
+                                view! {
+                                    <div style="display: flex; justify-content: space-between; align-items: center; padding: 4px 0; font-size: 13px;">
+                                        <span>{rotulo}</span>
+                                        <div style="display: flex; gap: 4px;">
+                                            {silenciar}
+                                            {botoes}
+                                        </div>
+                                    </div>
+                                }
+                            }).collect::<Vec<_>>()
+                        }}
+                    </div>
+
                     <div style="text-align: right;">
                         <button
                             on:click=salvar_alteracoes
@@ -15,6 +15,8 @@ pub fn Table(
     #[prop(optional, into, default = "PaperCards".to_string())] theme: String,
     #[prop(optional, into, default = false)] is_my_team: bool,
     #[prop(into, default = "80px".to_string().into())] card_width: Signal<String>,
+    // Jogos (por id) que acabaram de mudar, contornados em destaque (replay)
+    #[prop(into, default = Signal::derive(|| vec![]))] destaques: Signal<Vec<u32>>,
 ) -> impl IntoView {
     let interativo = on_click.is_some();
 
@@ -109,6 +111,7 @@ pub fn Table(
                                 }
 
                                 let total_cartas = cartas_visuais.len();
+                                let id_jogo = jogo.id;
                                 let cb_local = on_click;
                                 let width_local = card_width; // Signal é Copy
 
@@ -117,6 +120,11 @@ pub fn Table(
                                         on:click=move |_| { if let Some(cb) = cb_local { cb.run(idx); } }
                                         style=move || {
                                             let cursor = if interativo { "pointer" } else { "default" };
+                                            let borda = if destaques.get().contains(&id_jogo) {
+                                                "2px solid #00e5ff; box-shadow: 0 0 12px rgba(0, 229, 255, 0.6)"
+                                            } else {
+                                                "1px solid rgba(255,255,255,0.05)"
+                                            };
                                             format!("
                                                 background: rgba(0,0,0,0.2); 
                                                 padding: 8px; 
@@ -125,9 +133,9 @@ pub fn Table(
                                                 align-items: center; 
                                                 cursor: {}; 
                                                 transition: all 0.2s;
-                                                border: 1px solid rgba(255,255,255,0.05); 
+                                                border: {}; 
                                                 min-height: 90px;
-                                            ", cursor)
+                                            ", cursor, borda)
                                         }
                                     >
                                         {cartas_visuais.into_iter().enumerate().map(|(c_idx, c)| {
@@ -0,0 +1,408 @@
+use crate::services::api;
+use crate::utils::helper::{ler_chave_torneio, salvar_chave_torneio};
+use buracao_core::lobby::ConfigRelogio;
+use buracao_core::torneio::{
+    FormatoTorneio, NovaDupla, NovoTorneio, ResultadoManual, ResumoTorneio, StatusTorneio,
+};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use std::time::Duration;
+
+// --- PÁGINA DE TORNEIOS (/torneios) ---
+// Qualquer um acompanha rodadas e classificação; quem criou o torneio neste navegador
+// (ou colou a chave de organizador) inscreve duplas, gera rodadas e lança placares.
+
+fn texto_status(status: StatusTorneio) -> &'static str {
+    match status {
+        StatusTorneio::Inscricoes => "Inscrições abertas",
+        StatusTorneio::EmAndamento => "Rodada em andamento",
+        StatusTorneio::EntreRodadas => "Aguardando a próxima rodada",
+        StatusTorneio::Encerrado => "Encerrado",
+    }
+}
+
+fn texto_formato(formato: FormatoTorneio) -> String {
+    match formato {
+        FormatoTorneio::Suico { rodadas } => format!("Suíço, {} rodadas", rodadas),
+        FormatoTorneio::EliminatoriaSimples => "Eliminatória simples".to_string(),
+    }
+}
+
+const PAINEL: &str = "
+    background: rgba(0,0,0,0.5); padding: 20px; border-radius: 15px;
+    border: 2px solid #4caf50; display: flex; flex-direction: column; gap: 10px;
+    box-shadow: 0 10px 30px rgba(0,0,0,0.5);
+";
+const INPUT: &str =
+    "width: 100%; padding: 8px; margin-top: 4px; border-radius: 5px; border: none; box-sizing: border-box;";
+const BOTAO: &str = "background: #2e7d32; color: white; border: none; border-radius: 4px; padding: 6px 12px; cursor: pointer;";
+
+#[component]
+pub fn TournamentPage() -> impl IntoView {
+    let (torneios, set_torneios) = signal(Vec::<ResumoTorneio>::new());
+    let (selecionado, set_selecionado) = signal(Option::<ResumoTorneio>::None);
+    let (chave, set_chave) = signal(String::new());
+    let (erro, set_erro) = signal(Option::<String>::None);
+
+    // --- CRIAÇÃO ---
+    let (novo_nome, set_novo_nome) = signal(String::new());
+    let (eliminatoria, set_eliminatoria) = signal(false);
+    let (rodadas_suico, set_rodadas_suico) = signal(4_u32);
+    let (com_relogio, set_com_relogio) = signal(false);
+
+    // --- INSCRIÇÃO ---
+    let (jogador_1, set_jogador_1) = signal(String::new());
+    let (jogador_2, set_jogador_2) = signal(String::new());
+    let (nome_dupla, set_nome_dupla) = signal(String::new());
+
+    let atualizar_lista = move || {
+        spawn_local(async move {
+            match api::listar_torneios().await {
+                Ok(lista) => set_torneios.set(lista),
+                Err(e) => set_erro.set(Some(e)),
+            }
+        });
+    };
+
+    let abrir = move |id: u32| {
+        let chave = ler_chave_torneio(id).unwrap_or_default();
+        set_chave.set(chave.clone());
+        spawn_local(async move {
+            match api::buscar_torneio(id, &chave).await {
+                Ok(t) => {
+                    set_selecionado.set(Some(t));
+                    set_erro.set(None);
+                }
+                Err(e) => set_erro.set(Some(e)),
+            }
+        });
+    };
+
+    atualizar_lista();
+
+    // Placar das mesas chega sozinho quando as partidas acabam: recarrega de tempos em tempos
+    if let Ok(handle) = set_interval_with_handle(
+        move || {
+            if let Some(id) = selecionado.get_untracked().map(|t| t.id) {
+                let chave = chave.get_untracked();
+                spawn_local(async move {
+                    if let Ok(t) = api::buscar_torneio(id, &chave).await {
+                        set_selecionado.set(Some(t));
+                    }
+                });
+            }
+        },
+        Duration::from_secs(5),
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+
+    // Resposta das ações de organizador: mostra o torneio atualizado ou o erro
+    let aplicar = move |resultado: Result<ResumoTorneio, String>| match resultado {
+        Ok(t) => {
+            set_selecionado.set(Some(t));
+            set_erro.set(None);
+            atualizar_lista();
+        }
+        Err(e) => set_erro.set(Some(e)),
+    };
+
+    let criar = move |_| {
+        let nome = novo_nome.get();
+        if nome.trim().is_empty() {
+            set_erro.set(Some("Dê um nome para o torneio.".to_string()));
+            return;
+        }
+        let pedido = NovoTorneio {
+            nome,
+            formato: if eliminatoria.get() {
+                FormatoTorneio::EliminatoriaSimples
+            } else {
+                FormatoTorneio::Suico {
+                    rodadas: rodadas_suico.get(),
+                }
+            },
+            // Regras padrão do servidor
+            regras: None,
+            relogio: com_relogio.get().then(ConfigRelogio::default),
+        };
+        spawn_local(async move {
+            match api::criar_torneio(&pedido).await {
+                Ok(criado) => {
+                    salvar_chave_torneio(criado.torneio.id, &criado.chave_admin);
+                    set_chave.set(criado.chave_admin);
+                    set_novo_nome.set(String::new());
+                    aplicar(Ok(criado.torneio));
+                }
+                Err(e) => set_erro.set(Some(e)),
+            }
+        });
+    };
+
+    let inscrever = move |_| {
+        let Some(id) = selecionado.get().map(|t| t.id) else {
+            return;
+        };
+        let nome = nome_dupla.get();
+        let dupla = NovaDupla {
+            jogadores: [jogador_1.get(), jogador_2.get()],
+            nome: (!nome.trim().is_empty()).then_some(nome),
+        };
+        let chave = chave.get();
+        spawn_local(async move {
+            let resultado = api::inscrever_dupla(id, &chave, &dupla).await;
+            if resultado.is_ok() {
+                set_jogador_1.set(String::new());
+                set_jogador_2.set(String::new());
+                set_nome_dupla.set(String::new());
+            }
+            aplicar(resultado);
+        });
+    };
+
+    let proxima_rodada = move |_| {
+        let Some(id) = selecionado.get().map(|t| t.id) else {
+            return;
+        };
+        let chave = chave.get();
+        spawn_local(async move {
+            aplicar(api::gerar_rodada(id, &chave).await);
+        });
+    };
+
+    let lancar = move |resultado: ResultadoManual| {
+        let Some(id) = selecionado.get_untracked().map(|t| t.id) else {
+            return;
+        };
+        let chave = chave.get_untracked();
+        spawn_local(async move {
+            aplicar(api::lancar_resultado(id, &chave, &resultado).await);
+        });
+    };
+
+    let organizador = move || !chave.get().is_empty();
+
+    view! {
+        <div style="
+            min-height: 100vh; display: flex; align-items: flex-start; justify-content: center;
+            flex-wrap: wrap; gap: 30px; padding: 30px; box-sizing: border-box;
+            background: #1b5e20; font-family: sans-serif; color: white;
+        ">
+            // --- LISTA E CRIAÇÃO ---
+            <div style=format!("{} width: 300px;", PAINEL)>
+                <div style="display: flex; justify-content: space-between; align-items: center;">
+                    <h2 style="margin: 0; color: #ffeb3b; font-size: 1.2rem;">"🏆 Torneios"</h2>
+                    <a href="/" style="color: #ffeb3b; font-size: 13px;">"← Voltar às mesas"</a>
+                </div>
+
+                {move || {
+                    let lista = torneios.get();
+                    if lista.is_empty() {
+                        return view! { <div style="opacity: 0.7; font-size: 13px;">"Nenhum torneio ainda."</div> }.into_any();
+                    }
+                    lista.into_iter().map(|t| {
+                        let id = t.id;
+                        view! {
+                            <div
+                                on:click=move |_| abrir(id)
+                                style="padding: 8px 10px; border-radius: 8px; cursor: pointer; background: rgba(255,255,255,0.08);"
+                            >
+                                <strong>{t.nome.clone()}</strong>
+                                <div style="opacity: 0.7; font-size: 12px;">
+                                    {format!("{} · {} duplas · {}", texto_formato(t.formato), t.duplas.len(), texto_status(t.status))}
+                                </div>
+                            </div>
+                        }
+                    }).collect::<Vec<_>>().into_any()
+                }}
+
+                <div style="border-top: 1px solid rgba(255,255,255,0.2); padding-top: 10px; display: flex; flex-direction: column; gap: 8px; font-size: 13px;">
+                    <strong>"Novo torneio"</strong>
+                    <label>
+                        "Nome:"
+                        <input type="text" prop:value=novo_nome
+                            on:input=move |e| set_novo_nome.set(event_target_value(&e))
+                            style=INPUT />
+                    </label>
+                    <label>
+                        "Formato:"
+                        <select on:change=move |e| set_eliminatoria.set(event_target_value(&e) == "eliminatoria") style=INPUT>
+                            <option value="suico">"Suíço"</option>
+                            <option value="eliminatoria">"Eliminatória simples"</option>
+                        </select>
+                    </label>
+                    <Show when=move || !eliminatoria.get()>
+                        <label>
+                            "Rodadas:"
+                            <input type="number" min="1" max="15" prop:value=move || rodadas_suico.get().to_string()
+                                on:input=move |e| {
+                                    if let Ok(v) = event_target_value(&e).parse::<u32>() {
+                                        set_rodadas_suico.set(v);
+                                    }
+                                }
+                                style=INPUT />
+                        </label>
+                    </Show>
+                    <label style="display: flex; align-items: center; gap: 6px;">
+                        <input type="checkbox" prop:checked=com_relogio
+                            on:change=move |e| set_com_relogio.set(event_target_checked(&e)) />
+                        "Limite de tempo por turno"
+                    </label>
+                    <button on:click=criar style="background: #ffeb3b; color: black; font-weight: bold; border: none; border-radius: 5px; padding: 8px; cursor: pointer;">
+                        "CRIAR TORNEIO"
+                    </button>
+                </div>
+            </div>
+
+            // --- TORNEIO ESCOLHIDO ---
+            <div style=format!("{} width: 640px; max-width: 100%;", PAINEL)>
+                {move || erro.get().map(|e| view! {
+                    <div style="background: rgba(183, 28, 28, 0.9); padding: 8px; border-radius: 6px; font-size: 13px;">{e}</div>
+                })}
+
+                {move || match selecionado.get() {
+                    None => view! { <div style="opacity: 0.7;">"Escolha ou crie um torneio."</div> }.into_any(),
+                    Some(t) => {
+                        let inscricoes = t.status == StatusTorneio::Inscricoes;
+                        let pode_gerar = matches!(t.status, StatusTorneio::Inscricoes | StatusTorneio::EntreRodadas);
+                        let ultima_rodada = t.rodadas.len() as u32;
+                        let nome_dupla_de = {
+                            let t = t.clone();
+                            move |id: u32| t.dupla(id).map(|d| d.nome.clone()).unwrap_or_default()
+                        };
+                        view! {
+                            <div>
+                                <h2 style="margin: 0; color: #ffeb3b;">{t.nome.clone()}</h2>
+                                <small style="opacity: 0.8;">{format!("{} · {}", texto_formato(t.formato), texto_status(t.status))}</small>
+                            </div>
+
+                            // Chave de organizador (só aparece para quem criou; dá para colar a de outro navegador)
+                            <label style="font-size: 13px;">
+                                "Chave de organizador:"
+                                <input type="text" prop:value=chave
+                                    on:input=move |e| {
+                                        let valor = event_target_value(&e);
+                                        salvar_chave_torneio(t.id, &valor);
+                                        set_chave.set(valor);
+                                    }
+                                    placeholder="Só para quem organiza"
+                                    style=format!("{} font-family: monospace;", INPUT) />
+                            </label>
+
+                            // --- DUPLAS ---
+                            <div>
+                                <strong>{format!("Duplas ({})", t.duplas.len())}</strong>
+                                {t.duplas.iter().map(|d| view! {
+                                    <div style="font-size: 13px; opacity: 0.9;">
+                                        {format!("#{} {} — {} e {}", d.id, d.nome, d.jogadores[0], d.jogadores[1])}
+                                        // Chaves de assento: só vêm nas respostas ao organizador
+                                        {d.chaves.clone().map(|[c0, c1]| view! {
+                                            <span style="font-family: monospace; opacity: 0.8;">
+                                                {format!(" · chaves: {} ({}), {} ({})", c0, d.jogadores[0], c1, d.jogadores[1])}
+                                            </span>
+                                        })}
+                                    </div>
+                                }).collect::<Vec<_>>()}
+                            </div>
+                            <Show when=move || inscricoes && organizador()>
+                                <div style="display: flex; gap: 6px; align-items: flex-end; font-size: 13px;">
+                                    <input type="text" placeholder="Jogador 1" prop:value=jogador_1
+                                        on:input=move |e| set_jogador_1.set(event_target_value(&e)) style=INPUT />
+                                    <input type="text" placeholder="Jogador 2" prop:value=jogador_2
+                                        on:input=move |e| set_jogador_2.set(event_target_value(&e)) style=INPUT />
+                                    <input type="text" placeholder="Nome da dupla (opcional)" prop:value=nome_dupla
+                                        on:input=move |e| set_nome_dupla.set(event_target_value(&e)) style=INPUT />
+                                    <button on:click=inscrever style=BOTAO>"Inscrever"</button>
+                                </div>
+                                <small style="opacity: 0.7;">"Cada jogador entra na mesa com o nome inscrito e a sua chave de assento no campo de senha: passe a chave só para ele."</small>
+                            </Show>
+
+                            <Show when=move || pode_gerar && organizador()>
+                                <button on:click=proxima_rodada style="background: #ffeb3b; color: black; font-weight: bold; border: none; border-radius: 5px; padding: 8px; cursor: pointer;">
+                                    {if inscricoes { "COMEÇAR: GERAR 1ª RODADA" } else { "GERAR PRÓXIMA RODADA" }}
+                                </button>
+                            </Show>
+
+                            // --- RODADAS ---
+                            {t.rodadas.iter().rev().map(|rodada| {
+                                let numero = rodada.numero;
+                                let atual = numero == ultima_rodada;
+                                view! {
+                                    <div style="border-top: 1px solid rgba(255,255,255,0.2); padding-top: 8px;">
+                                        <strong>{format!("Rodada {}", numero)}</strong>
+                                        {rodada.mesas.iter().enumerate().map(|(indice, mesa)| {
+                                            let a = nome_dupla_de(mesa.dupla_a);
+                                            let Some(b) = mesa.dupla_b.map(&nome_dupla_de) else {
+                                                return view! {
+                                                    <div style="font-size: 13px; opacity: 0.8;">{format!("Folga: {}", a)}</div>
+                                                }.into_any();
+                                            };
+                                            let placar = mesa.resultado
+                                                .map(|r| format!("{} x {}", r.pontos_a, r.pontos_b))
+                                                .unwrap_or_else(|| "jogando".to_string());
+                                            let (pontos_a, set_pontos_a) = signal(String::new());
+                                            let (pontos_b, set_pontos_b) = signal(String::new());
+                                            let enviar = move |_| {
+                                                match (pontos_a.get().parse::<i32>(), pontos_b.get().parse::<i32>()) {
+                                                    (Ok(pontos_a), Ok(pontos_b)) => lancar(ResultadoManual { rodada: numero, mesa: indice, pontos_a, pontos_b }),
+                                                    _ => set_erro.set(Some("Informe os pontos das duas duplas.".to_string())),
+                                                }
+                                            };
+                                            view! {
+                                                <div style="display: flex; justify-content: space-between; align-items: center; gap: 8px; font-size: 13px; padding: 4px 0;">
+                                                    <span>{format!("Mesa {}: {} x {}", indice + 1, a, b)}</span>
+                                                    <span style="opacity: 0.8;">{format!("sala {}", mesa.sala)}</span>
+                                                    <strong style="color: #ffeb3b;">{placar}</strong>
+                                                    <Show when=move || atual && organizador()>
+                                                        <input type="number" placeholder="A" style="width: 60px; padding: 4px;"
+                                                            on:input=move |e| set_pontos_a.set(event_target_value(&e)) />
+                                                        <input type="number" placeholder="B" style="width: 60px; padding: 4px;"
+                                                            on:input=move |e| set_pontos_b.set(event_target_value(&e)) />
+                                                        <button on:click=enviar style=BOTAO title="Lançar ou corrigir o placar">"✔"</button>
+                                                    </Show>
+                                                </div>
+                                            }.into_any()
+                                        }).collect::<Vec<_>>()}
+                                    </div>
+                                }
+                            }).collect::<Vec<_>>()}
+
+                            // --- CLASSIFICAÇÃO ---
+                            <div style="border-top: 1px solid rgba(255,255,255,0.2); padding-top: 8px;">
+                                <strong>"Classificação"</strong>
+                                <table style="width: 100%; border-collapse: collapse; font-size: 13px;">
+                                    <thead>
+                                        <tr style="opacity: 0.7; text-align: right;">
+                                            <th style="text-align: left;">"#"</th>
+                                            <th style="text-align: left;">"Dupla"</th>
+                                            <th title="Pontos de torneio (vitória 2, empate 1)">"Pts"</th>
+                                            <th>"J"</th>
+                                            <th>"V"</th>
+                                            <th>"E"</th>
+                                            <th>"D"</th>
+                                            <th title="Pontos a favor menos pontos contra">"Saldo"</th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {t.classificacao.iter().enumerate().map(|(posicao, l)| view! {
+                                            <tr style=format!("text-align: right; opacity: {};", if l.eliminada { 0.5 } else { 1.0 })>
+                                                <td style="text-align: left;">{posicao + 1}</td>
+                                                <td style="text-align: left;">{l.nome.clone()}</td>
+                                                <td><strong>{l.pontos_torneio}</strong></td>
+                                                <td>{l.jogos}</td>
+                                                <td>{l.vitorias}</td>
+                                                <td>{l.empates}</td>
+                                                <td>{l.derrotas}</td>
+                                                <td>{l.saldo()}</td>
+                                            </tr>
+                                        }).collect::<Vec<_>>()}
+                                    </tbody>
+                                </table>
+                            </div>
+                        }.into_any()
+                    }
+                }}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,68 @@
+use buracao_core::acoes::TempoTurno;
+use leptos::prelude::*;
+use std::time::Duration;
+
+fn formatar(ms: u64) -> String {
+    let segundos = ms.div_ceil(1000);
+    format!("{}:{:02}", segundos / 60, segundos % 60)
+}
+
+// Contagem regressiva da vez. O servidor manda o tempo restante junto com a visão;
+// entre uma atualização e outra o relógio anda sozinho aqui no navegador.
+#[component]
+pub fn TurnClock(
+    // Tempo informado pelo servidor e o instante (Date.now) em que ele chegou
+    #[prop(into)] tempo: Signal<Option<(TempoTurno, f64)>>,
+    #[prop(into)] my_id: Signal<u32>,
+) -> impl IntoView {
+    let (agora, set_agora) = signal(js_sys::Date::now());
+    if let Ok(handle) = set_interval_with_handle(
+        move || set_agora.set(js_sys::Date::now()),
+        Duration::from_millis(250),
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+
+    let restante = move || {
+        tempo.get().map(|(t, recebido_em)| {
+            let passou = (agora.get() - recebido_em).max(0.0) as u64;
+            // O turno acaba primeiro; depois começa a correr o banco
+            let turno = t.restante_turno_ms.saturating_sub(passou);
+            let banco = t
+                .banco_ms
+                .saturating_sub(passou.saturating_sub(t.restante_turno_ms));
+            (t.jogador, turno, banco)
+        })
+    };
+
+    view! {
+        {move || restante().map(|(jogador, turno, banco)| {
+            let minha_vez = jogador == my_id.get();
+            let usando_banco = turno == 0;
+            let cor = if usando_banco && banco < 10_000 {
+                "#f44336"
+            } else if usando_banco {
+                "#ff9800"
+            } else {
+                "white"
+            };
+            let texto = if usando_banco {
+                format!("⏳ Banco {}", formatar(banco))
+            } else {
+                format!("⏱️ {} (+{})", formatar(turno), formatar(banco))
+            };
+            view! {
+                <div
+                    title=if minha_vez { "Seu tempo nesta vez" } else { "Tempo do jogador da vez" }
+                    style=format!("
+                        font-size: 13px; font-weight: bold; color: {};
+                        background: rgba(0,0,0,0.4); padding: 4px 10px; border-radius: 12px;
+                        border: {};
+                    ", cor, if minha_vez { "1px solid #ffeb3b" } else { "1px solid transparent" })
+                >
+                    {texto}
+                </div>
+            }
+        })}
+    }
+}
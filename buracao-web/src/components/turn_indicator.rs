@@ -1,3 +1,5 @@
+use crate::components::reactions::ReactionBubble;
+use buracao_core::acoes::{PresencaJogador, Reacao};
 use leptos::prelude::*;
 use std::collections::HashMap;
 
@@ -7,10 +9,13 @@ fn TurnDot(
     #[prop(into)] is_turn: Signal<bool>,
     rgb: (u8, u8, u8),
     #[prop(into)] label: Signal<String>,
+    #[prop(into)] reacao: Signal<Option<Reacao>>,
 ) -> impl IntoView {
     let (r, g, b) = rgb;
 
     view! {
+        <div style="position: relative;">
+        <ReactionBubble reacao=reacao />
         <div
             // O texto do tooltip (demora ~1s para aparecer no navegador)
             title=move || label.get()
@@ -42,6 +47,7 @@ fn TurnDot(
                 ", bg_color, border, shadow, transform)
             }
         ></div>
+        </div>
     }
 }
 
@@ -51,6 +57,10 @@ pub fn TurnIndicator(
     #[prop(into)] current_turn: Signal<u32>,
     names: ReadSignal<HashMap<u32, String>>,
     cards_count: ReadSignal<Vec<usize>>,
+    // Reação ativa de cada jogador (some depois de alguns segundos)
+    #[prop(into)] reactions: Signal<HashMap<u32, Reacao>>,
+    // Conexão e latência de cada assento (para saber se está pensando ou se caiu)
+    #[prop(into)] presence: Signal<HashMap<u32, PresencaJogador>>,
 ) -> impl IntoView {
     // Cores Base em RGB
     let blue_rgb = (41, 182, 246); // #29b6f6
@@ -70,10 +80,23 @@ pub fn TurnIndicator(
                 .unwrap_or(format!("Jogador {}", target_id));
             let count = all_counts.get(target_id as usize).copied().unwrap_or(0);
 
-            format!("{}\n{} Cartas", name, count)
+            let conexao = match presence.get().get(&target_id) {
+                Some(p) if !p.conectado => "\n⚠️ Desconectado".to_string(),
+                Some(p) if p.ausente => "\n💤 Ausente (jogando no automático)".to_string(),
+                Some(PresencaJogador {
+                    latencia_ms: Some(ms),
+                    ..
+                }) => format!("\n📶 {} ms", ms),
+                _ => String::new(),
+            };
+
+            format!("{}\n{} Cartas{}", name, count, conexao)
         })
     };
 
+    let reacao_de =
+        move |target_id: u32| Signal::derive(move || reactions.get().get(&target_id).copied());
+
     view! {
         <div style="
             display: grid;
@@ -93,6 +116,7 @@ pub fn TurnIndicator(
                         is_turn=Signal::derive(move || current_turn.get() == 2)
                         rgb=blue_rgb
                         label=make_label(2)
+                        reacao=reacao_de(2)
                     />
                 }}
             </div>
@@ -105,6 +129,7 @@ pub fn TurnIndicator(
                         is_turn=Signal::derive(move || current_turn.get() == 3)
                         rgb=orange_rgb
                         label=make_label(3)
+                        reacao=reacao_de(3)
                     />
                 }}
             </div>
@@ -117,6 +142,7 @@ pub fn TurnIndicator(
                         is_turn=Signal::derive(move || current_turn.get() == 1)
                         rgb=green_rgb
                         label=make_label(1)
+                        reacao=reacao_de(1)
                     />
                 }}
             </div>
@@ -129,6 +155,7 @@ pub fn TurnIndicator(
                         is_turn=Signal::derive(move || current_turn.get() == 0)
                         rgb=red_rgb
                         label=make_label(0)
+                        reacao=reacao_de(0)
                     />
                 }}
             </div>
@@ -4,11 +4,14 @@ use leptos::prelude::*;
 // Isso torna as pastas components, utils e state acessíveis em todo o projeto
 pub mod app;
 pub mod components;
+pub mod services;
 pub mod state; // Se ainda não criou os arquivos dentro de state, comente essa linha
 pub mod utils;
 
 // Importando o componente principal
 use app::App;
+use components::replay::ReplayPage;
+use components::tournament::TournamentPage;
 
 fn main() {
     // Integração com logs do navegador (console.log)
@@ -16,9 +19,20 @@ fn main() {
     console_error_panic_hook::set_once();
 
     // Inicia o App no elemento <body> do HTML
-    mount_to_body(|| {
-        view! {
-            <App/>
-        }
-    });
+    // O servidor entrega o index.html em qualquer caminho: /torneios abre a página do organizador
+    // e /replay o visualizador de partidas arquivadas
+    let caminho = web_sys::window()
+        .and_then(|w| w.location().pathname().ok())
+        .unwrap_or_default();
+    if caminho.starts_with("/torneios") {
+        mount_to_body(|| view! { <TournamentPage/> });
+    } else if caminho.starts_with("/replay") {
+        mount_to_body(|| view! { <ReplayPage/> });
+    } else {
+        mount_to_body(|| {
+            view! {
+                <App/>
+            }
+        });
+    }
 }
@@ -0,0 +1,151 @@
+use buracao_core::lobby::{EntradaRanking, NovaSala, ResumoSala, SalaCriada};
+use buracao_core::registro::{RegistroPartida, ResumoRegistro};
+use buracao_core::torneio::{
+    NovaDupla, NovoTorneio, ResultadoManual, ResumoTorneio, TorneioCriado,
+};
+use gloo_net::http::{Request, Response};
+
+// --- CLIENTE DA API REST DO SERVIDOR ---
+// Os caminhos são relativos: em produção o próprio servidor entrega o site,
+// e no `trunk serve` o proxy do Trunk.toml repassa /api para a porta 8080.
+
+#[derive(serde::Deserialize)]
+struct RespostaErro {
+    erro: String,
+}
+
+/// Converte respostas de erro ({"erro": "..."}) em uma mensagem legível.
+async fn ler_resposta<T: serde::de::DeserializeOwned>(resp: Response) -> Result<T, String> {
+    if resp.ok() {
+        resp.json::<T>()
+            .await
+            .map_err(|e| format!("Resposta inválida do servidor: {}", e))
+    } else {
+        match resp.json::<RespostaErro>().await {
+            Ok(corpo) => Err(corpo.erro),
+            Err(_) => Err(format!("Erro {} do servidor", resp.status())),
+        }
+    }
+}
+
+pub async fn listar_salas() -> Result<Vec<ResumoSala>, String> {
+    let resp = Request::get("/api/salas")
+        .send()
+        .await
+        .map_err(|e| format!("Falha de rede: {}", e))?;
+    ler_resposta(resp).await
+}
+
+pub async fn criar_sala(pedido: &NovaSala) -> Result<SalaCriada, String> {
+    let resp = Request::post("/api/salas")
+        .json(pedido)
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| format!("Falha de rede: {}", e))?;
+    ler_resposta(resp).await
+}
+
+pub async fn buscar_sala(codigo: &str) -> Result<ResumoSala, String> {
+    let resp = Request::get(&format!("/api/salas/{}", codigo))
+        .send()
+        .await
+        .map_err(|e| format!("Falha de rede: {}", e))?;
+    ler_resposta(resp).await
+}
+
+pub async fn ranking() -> Result<Vec<EntradaRanking>, String> {
+    let resp = Request::get("/api/ranking")
+        .send()
+        .await
+        .map_err(|e| format!("Falha de rede: {}", e))?;
+    ler_resposta(resp).await
+}
+
+// --- ARQUIVO DE PARTIDAS ---
+
+pub async fn listar_partidas() -> Result<Vec<ResumoRegistro>, String> {
+    let resp = Request::get("/api/partidas")
+        .send()
+        .await
+        .map_err(|e| format!("Falha de rede: {}", e))?;
+    ler_resposta(resp).await
+}
+
+pub async fn baixar_partida(id: i64) -> Result<RegistroPartida, String> {
+    let resp = Request::get(&format!("/api/partidas/{}", id))
+        .send()
+        .await
+        .map_err(|e| format!("Falha de rede: {}", e))?;
+    ler_resposta(resp).await
+}
+
+// --- TORNEIOS ---
+// As rotas de organização levam a chave recebida na criação do torneio.
+
+pub async fn listar_torneios() -> Result<Vec<ResumoTorneio>, String> {
+    let resp = Request::get("/api/torneios")
+        .send()
+        .await
+        .map_err(|e| format!("Falha de rede: {}", e))?;
+    ler_resposta(resp).await
+}
+
+/// Com a chave de organizador certa, a resposta traz também as chaves de assento.
+pub async fn buscar_torneio(id: u32, chave: &str) -> Result<ResumoTorneio, String> {
+    let resp = Request::get(&format!("/api/torneios/{}", id))
+        .header("x-chave-torneio", chave)
+        .send()
+        .await
+        .map_err(|e| format!("Falha de rede: {}", e))?;
+    ler_resposta(resp).await
+}
+
+pub async fn criar_torneio(pedido: &NovoTorneio) -> Result<TorneioCriado, String> {
+    let resp = Request::post("/api/torneios")
+        .json(pedido)
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| format!("Falha de rede: {}", e))?;
+    ler_resposta(resp).await
+}
+
+pub async fn inscrever_dupla(
+    id: u32,
+    chave: &str,
+    dupla: &NovaDupla,
+) -> Result<ResumoTorneio, String> {
+    let resp = Request::post(&format!("/api/torneios/{}/duplas", id))
+        .header("x-chave-torneio", chave)
+        .json(dupla)
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| format!("Falha de rede: {}", e))?;
+    ler_resposta(resp).await
+}
+
+pub async fn gerar_rodada(id: u32, chave: &str) -> Result<ResumoTorneio, String> {
+    let resp = Request::post(&format!("/api/torneios/{}/rodadas", id))
+        .header("x-chave-torneio", chave)
+        .send()
+        .await
+        .map_err(|e| format!("Falha de rede: {}", e))?;
+    ler_resposta(resp).await
+}
+
+pub async fn lancar_resultado(
+    id: u32,
+    chave: &str,
+    resultado: &ResultadoManual,
+) -> Result<ResumoTorneio, String> {
+    let resp = Request::post(&format!("/api/torneios/{}/resultados", id))
+        .header("x-chave-torneio", chave)
+        .json(resultado)
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| format!("Falha de rede: {}", e))?;
+    ler_resposta(resp).await
+}
@@ -0,0 +1,134 @@
+use buracao_core::acoes::{Envelope, MsgCliente, MsgServidor};
+use futures::channel::mpsc;
+use futures::{select, FutureExt, SinkExt, StreamExt};
+use gloo_net::websocket::{futures::WebSocket, Message};
+use gloo_timers::future::TimeoutFuture;
+use leptos::prelude::window;
+use leptos::task::spawn_local;
+
+// Espera entre tentativas de reconexão: dobra a cada falha até o teto
+const ESPERA_INICIAL_MS: u32 = 500;
+const ESPERA_MAXIMA_MS: u32 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusConexao {
+    Conectando,
+    Conectado,
+    Reconectando { tentativa: u32 },
+    // O servidor recusou ou expulsou: não adianta tentar de novo
+    Encerrada,
+}
+
+/// Endereço do WebSocket do jogo (no `trunk serve` o servidor fica na 8080).
+pub fn url_do_jogo() -> String {
+    let location = window().location();
+    let protocol = if location.protocol().unwrap() == "https:" {
+        "wss"
+    } else {
+        "ws"
+    };
+    let host = location.host().unwrap();
+    if host.contains("3000") {
+        "ws://127.0.0.1:8080/buraco".to_string()
+    } else {
+        format!("{}://{}/buraco", protocol, host)
+    }
+}
+
+/// Abre a conexão com o jogo e mantém ela viva: se cair, reconecta sozinha com backoff
+/// e apresenta a última `seq` recebida para o servidor reenviar só o que se perdeu.
+///
+/// `login` monta o Login de cada tentativa (recebe a última seq). O que for mandado
+/// pelo canal devolvido enquanto a conexão estiver caída sai quando ela voltar.
+pub fn conectar(
+    url: String,
+    login: impl Fn(Option<u64>) -> MsgCliente + 'static,
+    ao_receber: impl Fn(MsgServidor) + 'static,
+    ao_mudar_status: impl Fn(StatusConexao) + 'static,
+) -> mpsc::UnboundedSender<String> {
+    let (tx, mut saida) = mpsc::unbounded::<String>();
+
+    spawn_local(async move {
+        let mut ultimo_seq: Option<u64> = None;
+        let mut espera = ESPERA_INICIAL_MS;
+        let mut tentativa = 0;
+
+        ao_mudar_status(StatusConexao::Conectando);
+
+        loop {
+            leptos::logging::log!("Tentando conectar no WebSocket em: {}", url);
+            match WebSocket::open(&url) {
+                Ok(ws) => {
+                    let (mut write, mut read) = ws.split();
+                    let login_json = serde_json::to_string(&login(ultimo_seq)).unwrap();
+
+                    if write.send(Message::Text(login_json)).await.is_ok() {
+                        loop {
+                            select! {
+                                recebida = read.next().fuse() => match recebida {
+                                    Some(Ok(Message::Text(texto))) => {
+                                        let envelope = match serde_json::from_str::<Envelope>(&texto) {
+                                            Ok(e) => e,
+                                            Err(e) => {
+                                                leptos::logging::warn!("Mensagem do servidor ignorada: {:?}", e);
+                                                continue;
+                                            }
+                                        };
+                                        if envelope.seq.is_some() {
+                                            ultimo_seq = envelope.seq;
+                                        }
+
+                                        let encerrar = matches!(
+                                            envelope.msg,
+                                            MsgServidor::LoginRecusado(_) | MsgServidor::Expulso { .. }
+                                        );
+                                        if matches!(
+                                            envelope.msg,
+                                            MsgServidor::BoasVindas { .. }
+                                                | MsgServidor::BoasVindasEspectador { .. }
+                                        ) {
+                                            espera = ESPERA_INICIAL_MS;
+                                            tentativa = 0;
+                                            ao_mudar_status(StatusConexao::Conectado);
+                                        }
+
+                                        ao_receber(envelope.msg);
+
+                                        if encerrar {
+                                            ao_mudar_status(StatusConexao::Encerrada);
+                                            return;
+                                        }
+                                    }
+                                    Some(Ok(_)) => {}
+                                    Some(Err(e)) => {
+                                        leptos::logging::warn!("Conexão caiu: {:?}", e);
+                                        break;
+                                    }
+                                    None => break,
+                                },
+                                enviar = saida.next() => match enviar {
+                                    Some(json) => {
+                                        if let Err(e) = write.send(Message::Text(json)).await {
+                                            leptos::logging::error!("Falha envio: {:?}", e);
+                                            break;
+                                        }
+                                    }
+                                    // A tela largou o canal: fim da conexão
+                                    None => return,
+                                },
+                            }
+                        }
+                    }
+                }
+                Err(e) => leptos::logging::error!("Erro WS: {:?}", e),
+            }
+
+            tentativa += 1;
+            ao_mudar_status(StatusConexao::Reconectando { tentativa });
+            TimeoutFuture::new(espera).await;
+            espera = (espera * 2).min(ESPERA_MAXIMA_MS);
+        }
+    });
+
+    tx
+}
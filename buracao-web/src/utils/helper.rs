@@ -10,3 +10,99 @@ pub fn get_relative_position(my_id: u32, target_id: u32) -> &'static str {
         _ => "unknown",
     }
 }
+
+// ID aleatório do aparelho, guardado no LocalStorage para sobreviver a recargas
+pub fn get_or_create_device_id() -> String {
+    let window = web_sys::window().expect("no global `window` exists");
+    let storage = window
+        .local_storage()
+        .ok()
+        .flatten()
+        .expect("no local storage");
+
+    if let Ok(Some(id)) = storage.get_item("buraco_device_id") {
+        id
+    } else {
+        let new_id = format!("user_{}", rand::random::<u32>());
+        let _ = storage.set_item("buraco_device_id", &new_id);
+        new_id
+    }
+}
+
+// Chave do perfil do ranking. Quem copia a chave para outro aparelho leva o perfil junto.
+const CHAVE_IDENTIDADE: &str = "buraco_identidade";
+const CHAVE_ID_PERFIL: &str = "buraco_id_perfil";
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+pub fn ler_identidade() -> Option<String> {
+    local_storage()?
+        .get_item(CHAVE_IDENTIDADE)
+        .ok()?
+        .filter(|chave| !chave.is_empty())
+}
+
+pub fn salvar_identidade(chave: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.set_item(CHAVE_IDENTIDADE, chave.trim());
+    }
+}
+
+/// Id do perfil confirmado pelo servidor no último login (destaca a linha no ranking).
+pub fn ler_id_perfil() -> Option<i64> {
+    local_storage()?
+        .get_item(CHAVE_ID_PERFIL)
+        .ok()??
+        .parse()
+        .ok()
+}
+
+pub fn salvar_id_perfil(id_perfil: i64) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.set_item(CHAVE_ID_PERFIL, &id_perfil.to_string());
+    }
+}
+
+// Chave de organizador de cada torneio criado neste navegador
+fn chave_torneio_storage(id: u32) -> String {
+    format!("buraco_torneio_{}", id)
+}
+
+pub fn ler_chave_torneio(id: u32) -> Option<String> {
+    local_storage()?
+        .get_item(&chave_torneio_storage(id))
+        .ok()?
+        .filter(|chave| !chave.is_empty())
+}
+
+pub fn salvar_chave_torneio(id: u32, chave: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.set_item(&chave_torneio_storage(id), chave.trim());
+    }
+}
+
+// Token de anfitrião de cada sala criada neste navegador (o servidor aceita uma vez só)
+fn chave_anfitriao_storage(sala: &str) -> String {
+    format!("buraco_anfitriao_{}", sala)
+}
+
+pub fn ler_token_anfitriao(sala: &str) -> Option<String> {
+    local_storage()?
+        .get_item(&chave_anfitriao_storage(sala))
+        .ok()?
+        .filter(|token| !token.is_empty())
+}
+
+pub fn salvar_token_anfitriao(sala: &str, token: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.set_item(&chave_anfitriao_storage(sala), token);
+    }
+}
+
+pub fn apagar_token_anfitriao(sala: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(&chave_anfitriao_storage(sala));
+    }
+}